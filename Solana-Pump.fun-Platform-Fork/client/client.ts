@@ -0,0 +1,415 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Connection, Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { Pumpdotfun } from "../target/types/pumpdotfun";
+
+// Seeds of the program's PDAs, see programs/pumpdotfun/src/state.rs
+export const CONFIG_SEED = "CurveConfiguration";
+export const PROTOCOL_FEE_VAULT_SEED = "protocol_fee_vault";
+export const POOL_SEED_PREFIX = "liquidity_pool";
+export const SOL_VAULT_PREFIX = "liquidity_sol_vault";
+export const CREATOR_FEE_VAULT_SEED_PREFIX = "creator_fee_vault";
+export const LIQUIDITY_PROVIDER_SEED_PREFIX = "LiqudityProvider";
+export const WALLET_PURCHASES_SEED_PREFIX = "wallet_purchases";
+export const NFT_PURCHASES_SEED_PREFIX = "nft_purchases";
+export const REFERRER_SEED_PREFIX = "referrer";
+
+// Virtual reserves the default configuration allows, see programs/pumpdotfun/src/consts.rs
+export const V_SOL_AMOUNT = new BN("30000000000");
+export const V_TOKEN_AMOUNT = new BN("279900000000000000");
+
+// The curve every pool used before curves could be chosen, and the only one the
+// default configuration accepts
+export const DEFAULT_CURVE = {
+  virtualConstantProduct: { virtualSol: V_SOL_AMOUNT, virtualToken: V_TOKEN_AMOUNT },
+};
+
+export type PresaleProof =
+  | { allowlist: { allocation: BN; proof: number[][] } }
+  | { collection: {} };
+
+export interface TradeOptions {
+  // Token program of the pool's mint, Token-2022 unless the pool predates it, see `mintTokenProgram`
+  tokenProgram?: PublicKey;
+  referrer?: PublicKey;
+}
+
+export interface BuyOptions extends TradeOptions {
+  // Needed while the pool is in presale or within its launch protection window
+  trackPurchases?: boolean;
+  presaleProof?: PresaleProof;
+  // Accounts of the collection NFT for a `{ collection: {} }` proof
+  nft?: { mint: PublicKey; tokenAccount: PublicKey };
+}
+
+function pda(programId: PublicKey, ...seeds: (string | PublicKey)[]): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    seeds.map((seed) => (typeof seed === "string" ? Buffer.from(seed) : seed.toBuffer())),
+    programId
+  )[0];
+}
+
+export function configAddress(programId: PublicKey): PublicKey {
+  return pda(programId, CONFIG_SEED);
+}
+
+export function protocolFeeVaultAddress(programId: PublicKey): PublicKey {
+  return pda(programId, PROTOCOL_FEE_VAULT_SEED);
+}
+
+export function liquidityProviderAddress(programId: PublicKey, pool: PublicKey, user: PublicKey): PublicKey {
+  return pda(programId, LIQUIDITY_PROVIDER_SEED_PREFIX, pool, user);
+}
+
+export function referrerAddress(programId: PublicKey, authority: PublicKey): PublicKey {
+  return pda(programId, REFERRER_SEED_PREFIX, authority);
+}
+
+// Token program that owns `mint`. Tokens launched by the program are Token-2022 mints, pools
+// created before that hold legacy SPL Token mints.
+export async function mintTokenProgram(connection: Connection, mint: PublicKey): Promise<PublicKey> {
+  const account = await connection.getAccountInfo(mint);
+  if (!account) {
+    throw new Error(`Mint ${mint.toBase58()} not found`);
+  }
+  return account.owner;
+}
+
+// Every account tied to the pool of `mint`
+export function poolAddresses(programId: PublicKey, mint: PublicKey, tokenProgram = TOKEN_2022_PROGRAM_ID) {
+  const pool = pda(programId, POOL_SEED_PREFIX, mint);
+  return {
+    pool,
+    poolSolVault: pda(programId, SOL_VAULT_PREFIX, mint),
+    poolTokenAccount: getAssociatedTokenAddressSync(mint, pool, true, tokenProgram),
+    creatorFeeVault: pda(programId, CREATOR_FEE_VAULT_SEED_PREFIX, pool),
+  };
+}
+
+export function userTokenAccount(mint: PublicKey, user: PublicKey, tokenProgram = TOKEN_2022_PROGRAM_ID): PublicKey {
+  return getAssociatedTokenAddressSync(mint, user, false, tokenProgram);
+}
+
+export function initialize(program: Program<Pumpdotfun>, admin: PublicKey, feeBps: number) {
+  return program.methods.initialize(feeBps).accountsPartial({
+    dexConfigurationAccount: configAddress(program.programId),
+    protocolFeeVault: protocolFeeVaultAddress(program.programId),
+    admin,
+    rent: SYSVAR_RENT_PUBKEY,
+    systemProgram: SystemProgram.programId,
+  });
+}
+
+export function createToken(
+  program: Program<Pumpdotfun>,
+  payer: PublicKey,
+  mint: Keypair,
+  metadata: { name: string; symbol: string; uri: string },
+  totalSupply: BN,
+  revokeAuthorities = true
+) {
+  return program.methods
+    .createToken(metadata.name, metadata.symbol, metadata.uri, totalSupply, revokeAuthorities)
+    .accountsPartial({
+      payer,
+      mintAccount: mint.publicKey,
+      tokenAccount: userTokenAccount(mint.publicKey, payer),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .signers([mint]);
+}
+
+// Creates the token and its pool and opens trading in one transaction, with an optional
+// buy by the creator that no sniper can get ahead of
+export function launchToken(
+  program: Program<Pumpdotfun>,
+  creator: PublicKey,
+  mint: Keypair,
+  metadata: { name: string; symbol: string; uri: string },
+  totalSupply: BN,
+  devBuy: { amount: BN; minAmountOut: BN } | null = null,
+  curve: any = DEFAULT_CURVE
+) {
+  const { pool, poolTokenAccount, poolSolVault, creatorFeeVault } = poolAddresses(program.programId, mint.publicKey);
+  return program.methods
+    .launchToken({
+      ...metadata,
+      totalSupply,
+      curve,
+      devBuy,
+      launchProtection: null,
+      launchFee: null,
+    })
+    .accountsPartial({
+      dexConfigurationAccount: configAddress(program.programId),
+      protocolFeeVault: protocolFeeVaultAddress(program.programId),
+      tokenMint: mint.publicKey,
+      pool,
+      creatorFeeVault,
+      poolTokenAccount,
+      poolSolVault,
+      creatorTokenAccount: userTokenAccount(mint.publicKey, creator),
+      creator,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .signers([mint]);
+}
+
+export function createPool(
+  program: Program<Pumpdotfun>,
+  payer: PublicKey,
+  mint: PublicKey,
+  curve: any = DEFAULT_CURVE,
+  tokenProgram = TOKEN_2022_PROGRAM_ID
+) {
+  const { pool, poolTokenAccount, creatorFeeVault } = poolAddresses(program.programId, mint, tokenProgram);
+  return program.methods.createPool(curve).accountsPartial({
+    dexConfigurationAccount: configAddress(program.programId),
+    pool,
+    creatorFeeVault,
+    tokenMint: mint,
+    poolTokenAccount,
+    payer,
+    tokenProgram,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    rent: SYSVAR_RENT_PUBKEY,
+    systemProgram: SystemProgram.programId,
+  });
+}
+
+// The first provider must deposit the whole supply before trading can open
+export function addLiquidity(
+  program: Program<Pumpdotfun>,
+  user: PublicKey,
+  mint: PublicKey,
+  tokenAmount: BN,
+  maxSolAmount: BN,
+  tokenProgram = TOKEN_2022_PROGRAM_ID
+) {
+  const { pool, poolTokenAccount, poolSolVault } = poolAddresses(program.programId, mint, tokenProgram);
+  return program.methods.addLiquidity(tokenAmount, maxSolAmount).accountsPartial({
+    pool,
+    liquidityProvider: liquidityProviderAddress(program.programId, pool, user),
+    tokenMint: mint,
+    poolTokenAccount,
+    userTokenAccount: userTokenAccount(mint, user, tokenProgram),
+    poolSolVault,
+    user,
+    rent: SYSVAR_RENT_PUBKEY,
+    systemProgram: SystemProgram.programId,
+    tokenProgram,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+  });
+}
+
+export function removeLiquidity(
+  program: Program<Pumpdotfun>,
+  user: PublicKey,
+  mint: PublicKey,
+  shares: BN,
+  tokenProgram = TOKEN_2022_PROGRAM_ID
+) {
+  const { pool, poolTokenAccount, poolSolVault } = poolAddresses(program.programId, mint, tokenProgram);
+  return program.methods.removeLiquidity(shares).accountsPartial({
+    dexConfigurationAccount: configAddress(program.programId),
+    pool,
+    liquidityProvider: liquidityProviderAddress(program.programId, pool, user),
+    tokenMint: mint,
+    poolTokenAccount,
+    userTokenAccount: userTokenAccount(mint, user, tokenProgram),
+    poolSolVault,
+    user,
+    rent: SYSVAR_RENT_PUBKEY,
+    systemProgram: SystemProgram.programId,
+    tokenProgram,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+  });
+}
+
+// `lockPeriod` is how long in seconds the providers' seed stays locked, at least the
+// configured `liquidityLockPeriod`
+export function openTrading(
+  program: Program<Pumpdotfun>,
+  creator: PublicKey,
+  mint: PublicKey,
+  lockPeriod: BN,
+  tokenProgram = TOKEN_2022_PROGRAM_ID
+) {
+  const { pool, poolTokenAccount } = poolAddresses(program.programId, mint, tokenProgram);
+  return program.methods.openTrading(lockPeriod).accountsPartial({
+    dexConfigurationAccount: configAddress(program.programId),
+    pool,
+    tokenMint: mint,
+    poolTokenAccount,
+    creator,
+    tokenProgram,
+  });
+}
+
+function buyAccounts(program: Program<Pumpdotfun>, user: PublicKey, mint: PublicKey, options: BuyOptions) {
+  const tokenProgram = options.tokenProgram ?? TOKEN_2022_PROGRAM_ID;
+  const { pool, poolTokenAccount, poolSolVault, creatorFeeVault } = poolAddresses(
+    program.programId,
+    mint,
+    tokenProgram
+  );
+  const collection = options.nft !== undefined;
+  return {
+    dexConfigurationAccount: configAddress(program.programId),
+    protocolFeeVault: protocolFeeVaultAddress(program.programId),
+    pool,
+    creatorFeeVault,
+    referrer: options.referrer ? referrerAddress(program.programId, options.referrer) : null,
+    tokenMint: mint,
+    poolTokenAccount,
+    poolSolVault,
+    userTokenAccount: userTokenAccount(mint, user, tokenProgram),
+    walletPurchases:
+      options.trackPurchases && !collection ? pda(program.programId, WALLET_PURCHASES_SEED_PREFIX, pool, user) : null,
+    nftTokenAccount: collection ? options.nft.tokenAccount : null,
+    nftMint: collection ? options.nft.mint : null,
+    nftPurchases: collection ? pda(program.programId, NFT_PURCHASES_SEED_PREFIX, pool, options.nft.mint) : null,
+    user,
+    rent: SYSVAR_RENT_PUBKEY,
+    systemProgram: SystemProgram.programId,
+    tokenProgram,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+  };
+}
+
+// Spends `solAmount` lamports, fee included, for at least `minTokensOut` tokens
+export function buy(
+  program: Program<Pumpdotfun>,
+  user: PublicKey,
+  mint: PublicKey,
+  solAmount: BN,
+  minTokensOut: BN,
+  options: BuyOptions = {}
+) {
+  return program.methods
+    .buy(solAmount, minTokensOut, options.presaleProof ?? null)
+    .accountsPartial(buyAccounts(program, user, mint, options));
+}
+
+// Receives exactly `tokenAmount` tokens for at most `maxSolCost` lamports, fee included
+export function buyExactOut(
+  program: Program<Pumpdotfun>,
+  user: PublicKey,
+  mint: PublicKey,
+  tokenAmount: BN,
+  maxSolCost: BN,
+  options: BuyOptions = {}
+) {
+  return program.methods
+    .buyExactOut(tokenAmount, maxSolCost, options.presaleProof ?? null)
+    .accountsPartial(buyAccounts(program, user, mint, options));
+}
+
+// Sells `tokenAmount` tokens for at least `minSolOut` lamports after the fee
+export function sell(
+  program: Program<Pumpdotfun>,
+  user: PublicKey,
+  mint: PublicKey,
+  tokenAmount: BN,
+  minSolOut: BN,
+  options: TradeOptions = {}
+) {
+  const tokenProgram = options.tokenProgram ?? TOKEN_2022_PROGRAM_ID;
+  const { pool, poolTokenAccount, poolSolVault, creatorFeeVault } = poolAddresses(
+    program.programId,
+    mint,
+    tokenProgram
+  );
+  return program.methods.sell(tokenAmount, minSolOut).accountsPartial({
+    dexConfigurationAccount: configAddress(program.programId),
+    protocolFeeVault: protocolFeeVaultAddress(program.programId),
+    pool,
+    creatorFeeVault,
+    referrer: options.referrer ? referrerAddress(program.programId, options.referrer) : null,
+    tokenMint: mint,
+    poolTokenAccount,
+    poolSolVault,
+    userTokenAccount: userTokenAccount(mint, user, tokenProgram),
+    user,
+    rent: SYSVAR_RENT_PUBKEY,
+    systemProgram: SystemProgram.programId,
+    tokenProgram,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+  });
+}
+
+// Settings left out are kept as they are
+export function updateConfiguration(program: Program<Pumpdotfun>, admin: PublicKey, params: Record<string, any>) {
+  return program.methods.updateConfiguration(params as any).accountsPartial({
+    dexConfigurationAccount: configAddress(program.programId),
+    admin,
+  });
+}
+
+// Moves a graduated pool into the AMM, whose pool accounts for `mint` are given in `amm`.
+// Anyone can call it, `payer` pays the rent of the AMM accounts.
+export function migrate(
+  program: Program<Pumpdotfun>,
+  payer: PublicKey,
+  mint: PublicKey,
+  amm: { program: PublicKey; pool: PublicKey; tokenAccount: PublicKey },
+  tokenProgram = TOKEN_2022_PROGRAM_ID
+) {
+  const { pool, poolTokenAccount, poolSolVault } = poolAddresses(program.programId, mint, tokenProgram);
+  return program.methods.migrate().accountsPartial({
+    dexConfigurationAccount: configAddress(program.programId),
+    pool,
+    tokenMint: mint,
+    poolTokenAccount,
+    poolSolVault,
+    ammProgram: amm.program,
+    ammPool: amm.pool,
+    ammTokenAccount: amm.tokenAccount,
+    payer,
+    systemProgram: SystemProgram.programId,
+    tokenProgram,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+  });
+}
+
+// Sends `amount` lamports of accrued protocol fees to `recipient`
+export function withdraw(program: Program<Pumpdotfun>, admin: PublicKey, recipient: PublicKey, amount: BN) {
+  return program.methods.withdraw(amount).accountsPartial({
+    dexConfigurationAccount: configAddress(program.programId),
+    protocolFeeVault: protocolFeeVaultAddress(program.programId),
+    recipient,
+    admin,
+  });
+}
+
+// `anchor run client` prints the configuration of the cluster in Anchor.toml
+async function main() {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Pumpdotfun as Program<Pumpdotfun>;
+
+  const config = await program.account.curveConfiguration.fetch(configAddress(program.programId));
+  console.log("Program ID:", program.programId.toBase58());
+  console.log("Admin:", config.admin.toBase58());
+  console.log("Fee:", config.fees, "bps");
+  console.log("Graduation threshold:", config.graduationThreshold.toString(), "lamports");
+  console.log("AMM program:", config.ammProgram.toBase58());
+}
+
+if (require.main === module) {
+  main().catch((error) => {
+    console.error(error);
+    process.exit(1);
+  });
+}
@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{self, Mint, TokenAccount, TokenInterface},
+};
+
+declare_id!("6xH24LNpnMfkboui1YsrcwDacF27PKktnj9RCjpPd3KR");
+
+// Local stand-in for the AMM graduated pools migrate to. It only implements the
+// `initialize_pool` instruction `pumpdotfun::amm` calls, seeding a constant-product
+// pool with the reserves it is given.
+#[program]
+pub mod mock_amm {
+    use super::*;
+
+    // Creates the pool of `token_mint` ahead of migration so that `initialize_pool` takes
+    // `token_shortfall` fewer tokens than it is given, as a faulty AMM would
+    pub fn prepare_pool(ctx: Context<PreparePool>, token_shortfall: u64) -> Result<()> {
+        if ctx.accounts.amm_pool.reserve_sol != 0 {
+            return err!(AmmError::AlreadySeeded);
+        }
+
+        ctx.accounts.amm_pool.set_inner(Pool {
+            token: ctx.accounts.token_mint.key(),
+            reserve_sol: 0,
+            reserve_token: 0,
+            bump: ctx.bumps.amm_pool,
+            token_shortfall,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, sol_amount: u64, token_amount: u64) -> Result<()> {
+        if sol_amount == 0 || token_amount == 0 {
+            return err!(AmmError::EmptyReserves);
+        }
+        if ctx.accounts.amm_pool.reserve_sol != 0 {
+            return err!(AmmError::AlreadySeeded);
+        }
+        let token_amount = token_amount.saturating_sub(ctx.accounts.amm_pool.token_shortfall);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.sol_source.to_account_info(),
+                    to: ctx.accounts.amm_pool.to_account_info(),
+                },
+            ),
+            sol_amount,
+        )?;
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::TransferChecked {
+                    from: ctx.accounts.token_source.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.amm_token_account.to_account_info(),
+                    authority: ctx.accounts.token_source_authority.to_account_info(),
+                },
+            ),
+            token_amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        ctx.accounts.amm_pool.set_inner(Pool {
+            token: ctx.accounts.token_mint.key(),
+            reserve_sol: sol_amount,
+            reserve_token: token_amount,
+            bump: ctx.bumps.amm_pool,
+            token_shortfall: ctx.accounts.amm_pool.token_shortfall,
+        });
+
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Pool {
+    pub token: Pubkey,
+    pub reserve_sol: u64,
+    pub reserve_token: u64,
+    pub bump: u8,
+    pub token_shortfall: u64, // Tokens `initialize_pool` leaves behind, set by `prepare_pool`
+}
+
+impl Pool {
+    pub const SEED_PREFIX: &'static str = "pool";
+
+    // Discriminator (8) + Pubkey (32) + reserve sol (8) + reserve token (8) + Bump (1)
+    // + token shortfall (8)
+    pub const ACCOUNT_SIZE: usize = 8 + 32 + 8 + 8 + 1 + 8;
+}
+
+#[derive(Accounts)]
+pub struct PreparePool<'info> {
+    #[account(
+        init_if_needed,
+        space = Pool::ACCOUNT_SIZE,
+        payer = payer,
+        seeds = [Pool::SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub amm_pool: Box<Account<'info, Pool>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init_if_needed,
+        space = Pool::ACCOUNT_SIZE,
+        payer = payer,
+        seeds = [Pool::SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub amm_pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = amm_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub amm_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub sol_source: Signer<'info>,
+
+    #[account(mut)]
+    pub token_source: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_source_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum AmmError {
+    #[msg("Pool must be seeded with both reserves")]
+    EmptyReserves,
+
+    #[msg("Pool was already seeded")]
+    AlreadySeeded,
+}
@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+///////////////////////////////////////////////////////////////
+//
+//              Constant-product AMM interface
+//
+///////////////////////////////////////////////////////////////
+//
+//  Graduated pools are migrated by calling `initialize_pool(sol_amount, token_amount)`
+//  on the AMM set in `CurveConfiguration::amm_program`. The AMM creates its pool for the
+//  mint, takes `sol_amount` lamports from the SOL source and `token_amount` tokens from
+//  the token source, both signed for by this program.
+//
+//  `programs/mock-amm` implements it for local testing. There is no production adapter
+//  yet, a real AMM has to be plugged in with an adapter program exposing the same
+//  instruction. Pools do not graduate until `amm_program` is set.
+
+// sha256("global:initialize_pool")[..8], the Anchor discriminator of the instruction
+pub const INITIALIZE_POOL_DISCRIMINATOR: [u8; 8] = [95, 180, 10, 172, 84, 174, 232, 40];
+
+/// Accounts of `initialize_pool`, in instruction order
+pub struct InitializePool<'info> {
+    pub amm_pool: AccountInfo<'info>,
+    pub amm_token_account: AccountInfo<'info>,
+    pub token_mint: AccountInfo<'info>,
+    pub sol_source: AccountInfo<'info>,
+    pub token_source: AccountInfo<'info>,
+    pub token_source_authority: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> InitializePool<'info> {
+    fn metas(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.amm_pool.key(), false),
+            AccountMeta::new(self.amm_token_account.key(), false),
+            AccountMeta::new_readonly(self.token_mint.key(), false),
+            AccountMeta::new(self.sol_source.key(), true),
+            AccountMeta::new(self.token_source.key(), false),
+            AccountMeta::new_readonly(self.token_source_authority.key(), true),
+            AccountMeta::new(self.payer.key(), true),
+            AccountMeta::new_readonly(self.token_program.key(), false),
+            AccountMeta::new_readonly(self.associated_token_program.key(), false),
+            AccountMeta::new_readonly(self.system_program.key(), false),
+        ]
+    }
+
+    fn infos(&self) -> Vec<AccountInfo<'info>> {
+        vec![
+            self.amm_pool.clone(),
+            self.amm_token_account.clone(),
+            self.token_mint.clone(),
+            self.sol_source.clone(),
+            self.token_source.clone(),
+            self.token_source_authority.clone(),
+            self.payer.clone(),
+            self.token_program.clone(),
+            self.associated_token_program.clone(),
+            self.system_program.clone(),
+        ]
+    }
+}
+
+pub fn initialize_pool<'info>(
+    amm_program: &AccountInfo<'info>,
+    accounts: InitializePool<'info>,
+    sol_amount: u64,
+    token_amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = INITIALIZE_POOL_DISCRIMINATOR.to_vec();
+    sol_amount.serialize(&mut data)?;
+    token_amount.serialize(&mut data)?;
+
+    let instruction = Instruction {
+        program_id: amm_program.key(),
+        accounts: accounts.metas(),
+        data,
+    };
+
+    let mut infos = accounts.infos();
+    infos.push(amm_program.clone());
+    invoke_signed(&instruction, &infos, signer_seeds)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::hash::hash;
+
+    #[test]
+    fn discriminator_matches_the_anchor_sighash() {
+        assert_eq!(hash(b"global:initialize_pool").to_bytes()[..8], INITIALIZE_POOL_DISCRIMINATOR);
+    }
+}
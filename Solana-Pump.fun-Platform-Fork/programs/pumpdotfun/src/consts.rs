@@ -2,6 +2,8 @@ pub const INITIAL_PRICE_DIVIDER: u64 = 800_000;       // lamports per one token
 pub const INITIAL_LAMPORTS_FOR_POOL: u64 = 10_000_000;   // 0.01SOL
 pub const TOKEN_SELL_LIMIT_PERCENT: u64 = 8000;     //  80%
 pub const PROPORTION: u64 = 1280;      //  800M token is sold on 500SOL ===> (500 * 2 / 800) = 1.25 ===> 800 : 1.25 = 640 ====> 640 * 2 = 1280
-pub const V_SOL_AMOUNT: f64 = 30.0;
-pub const V_TOKEN_AMOUNT: f64 = 279900000.0;
-pub const ONE_BILLION: f64 = 1_000_000_000.0;
+pub const V_SOL_AMOUNT: u64 = 30_000_000_000;             // 30 SOL in lamports
+pub const V_TOKEN_AMOUNT: u64 = 279_900_000_000_000_000;  // 279.9M tokens with 9 decimals
+pub const TOKEN_UNIT: u64 = 1_000_000_000;              // base units in one whole token (9 decimals)
+pub const FEE_DENOMINATOR: u64 = 10_000;                 // fees are expressed in basis points
+pub const GRADUATION_THRESHOLD: u64 = 80_000_000_000;     // 80 SOL in reserve moves a pool to the AMM
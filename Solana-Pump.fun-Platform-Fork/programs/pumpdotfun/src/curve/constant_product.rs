@@ -0,0 +1,340 @@
+use anchor_lang::prelude::*;
+
+use crate::consts::TOKEN_UNIT;
+use crate::curve::BondingCurve;
+use crate::errors::CustomError;
+
+///////////////////////////////////////////////////////////////
+//
+//              Virtual constant product curve
+//
+///////////////////////////////////////////////////////////////
+//
+//  (x + X) * (y + Y) = k
+//  Real reserves => x, y
+//  Virtual reserves => X, Y
+//
+//  All math is done on u128 so that `reserve_in * reserve_out` never
+//  overflows for u64 reserves. Amounts paid out are rounded down and
+//  amounts charged are rounded up, so `k` can only grow.
+
+/// Result of a swap against the curve, with the reserves after the swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapResult {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub reserve_in: u128,
+    pub reserve_out: u128,
+}
+
+/// Adds the virtual offset on top of a real reserve
+pub fn virtual_reserve(reserve: u64, virtual_amount: u64) -> Result<u128> {
+    (reserve as u128)
+        .checked_add(virtual_amount as u128)
+        .ok_or(error!(CustomError::OverflowOrUnderflowOccurred))
+}
+
+/// Amount received for depositing exactly `amount_in`
+///
+/// amount_out = reserve_out * amount_in / (reserve_in + amount_in)
+pub fn quote_out(amount_in: u64, reserve_in: u128, reserve_out: u128) -> Result<u64> {
+    let numerator = reserve_out
+        .checked_mul(amount_in as u128)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    let denominator = reserve_in
+        .checked_add(amount_in as u128)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    if denominator == 0 {
+        return err!(CustomError::OverflowOrUnderflowOccurred);
+    }
+
+    u64::try_from(numerator / denominator).map_err(|_| error!(CustomError::OverflowOrUnderflowOccurred))
+}
+
+/// Amount that must be deposited to receive exactly `amount_out`
+///
+/// amount_in = ceil(reserve_in * amount_out / (reserve_out - amount_out))
+pub fn quote_in(amount_out: u64, reserve_in: u128, reserve_out: u128) -> Result<u64> {
+    if amount_out as u128 >= reserve_out {
+        return err!(CustomError::NotEnoughTokenInVault);
+    }
+
+    let numerator = reserve_in
+        .checked_mul(amount_out as u128)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    let denominator = reserve_out - amount_out as u128;
+
+    u64::try_from(numerator.div_ceil(denominator))
+        .map_err(|_| error!(CustomError::OverflowOrUnderflowOccurred))
+}
+
+/// Swaps exactly `amount_in` into the curve
+pub fn swap_exact_in(amount_in: u64, reserve_in: u128, reserve_out: u128) -> Result<SwapResult> {
+    let amount_out = quote_out(amount_in, reserve_in, reserve_out)?;
+    apply_swap(amount_in, amount_out, reserve_in, reserve_out)
+}
+
+/// Swaps whatever is needed to take exactly `amount_out` out of the curve
+pub fn swap_exact_out(amount_out: u64, reserve_in: u128, reserve_out: u128) -> Result<SwapResult> {
+    let amount_in = quote_in(amount_out, reserve_in, reserve_out)?;
+    apply_swap(amount_in, amount_out, reserve_in, reserve_out)
+}
+
+fn apply_swap(
+    amount_in: u64,
+    amount_out: u64,
+    reserve_in: u128,
+    reserve_out: u128,
+) -> Result<SwapResult> {
+    Ok(SwapResult {
+        amount_in,
+        amount_out,
+        reserve_in: reserve_in
+            .checked_add(amount_in as u128)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?,
+        reserve_out: reserve_out
+            .checked_sub(amount_out as u128)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?,
+    })
+}
+
+/// Constant product curve over real reserves plus optional virtual offsets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConstantProduct {
+    pub reserve_sol: u128,
+    pub reserve_token: u128,
+    pub supply: u64,
+}
+
+impl ConstantProduct {
+    pub fn new(
+        reserve_sol: u64,
+        reserve_token: u64,
+        virtual_sol: u64,
+        virtual_token: u64,
+        supply: u64,
+    ) -> Result<Self> {
+        Ok(Self {
+            reserve_sol: virtual_reserve(reserve_sol, virtual_sol)?,
+            reserve_token: virtual_reserve(reserve_token, virtual_token)?,
+            supply,
+        })
+    }
+}
+
+impl BondingCurve for ConstantProduct {
+    fn supply(&self) -> u64 {
+        self.supply
+    }
+
+    // Moving along the curve to `supply` keeps k, so price = k / y^2 there
+    fn price_at(&self, supply: u64) -> Result<u64> {
+        let reserve_token = if supply <= self.supply {
+            self.reserve_token.checked_add((self.supply - supply) as u128)
+        } else {
+            self.reserve_token.checked_sub((supply - self.supply) as u128)
+        }
+        .filter(|reserve| *reserve > 0)
+        .ok_or(CustomError::NotEnoughTokenInVault)?;
+
+        let k = self
+            .reserve_sol
+            .checked_mul(self.reserve_token)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        let price = (k / reserve_token)
+            .checked_mul(TOKEN_UNIT as u128)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?
+            / reserve_token;
+        u64::try_from(price).map_err(|_| error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+
+    fn cost_to_buy(&self, tokens: u64) -> Result<u64> {
+        quote_in(tokens, self.reserve_sol, self.reserve_token)
+    }
+
+    fn proceeds_from_sell(&self, tokens: u64) -> Result<u64> {
+        quote_out(tokens, self.reserve_token, self.reserve_sol)
+    }
+
+    fn tokens_for_sol(&self, sol_in: u64) -> Result<u64> {
+        quote_out(sol_in, self.reserve_sol, self.reserve_token)
+    }
+
+    fn spot_price(&self) -> Result<u64> {
+        if self.reserve_token == 0 {
+            return err!(CustomError::NotEnoughTokenInVault);
+        }
+
+        let price = self
+            .reserve_sol
+            .checked_mul(TOKEN_UNIT as u128)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?
+            / self.reserve_token;
+        u64::try_from(price).map_err(|_| error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::{V_SOL_AMOUNT, V_TOKEN_AMOUNT};
+    use crate::curve::CurveKind;
+
+    // Small deterministic generator so the property tests are reproducible
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            self.0
+        }
+
+        fn below(&mut self, max: u64) -> u64 {
+            1 + self.next() % max
+        }
+    }
+
+    fn k(reserve_in: u128, reserve_out: u128) -> u128 {
+        reserve_in * reserve_out
+    }
+
+    #[test]
+    fn quote_out_matches_formula() {
+        // 1 SOL into a fresh virtual curve
+        let out = quote_out(1_000_000_000, V_SOL_AMOUNT as u128, V_TOKEN_AMOUNT as u128).unwrap();
+        assert_eq!(out, 9_029_032_258_064_516);
+    }
+
+    #[test]
+    fn quote_in_rounds_up() {
+        let reserve_in = 1_000_u128;
+        let reserve_out = 3_000_u128;
+        let amount_in = quote_in(1, reserve_in, reserve_out).unwrap();
+        assert_eq!(amount_in, 1);
+        assert!(quote_out(amount_in, reserve_in, reserve_out).unwrap() >= 1);
+    }
+
+    #[test]
+    fn quote_in_rejects_draining_the_curve() {
+        assert!(quote_in(3_000, 1_000, 3_000).is_err());
+        assert!(quote_in(3_001, 1_000, 3_000).is_err());
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        let err = quote_out(u64::MAX, u128::MAX, u128::MAX).unwrap_err();
+        assert_eq!(err, error!(CustomError::OverflowOrUnderflowOccurred));
+    }
+
+    #[test]
+    fn k_never_decreases_on_buy_sell_round_trip() {
+        let mut rng = Lcg(42);
+        for _ in 0..10_000 {
+            let sol = virtual_reserve(rng.below(1_000_000_000_000), V_SOL_AMOUNT).unwrap();
+            let token = virtual_reserve(rng.below(800_000_000_000_000_000), V_TOKEN_AMOUNT).unwrap();
+            let sol_in = rng.below(100_000_000_000);
+            let k_start = k(sol, token);
+
+            let buy = swap_exact_in(sol_in, sol, token).unwrap();
+            let k_after_buy = k(buy.reserve_in, buy.reserve_out);
+            assert!(k_after_buy >= k_start);
+
+            let sell = swap_exact_in(buy.amount_out, buy.reserve_out, buy.reserve_in).unwrap();
+            let k_after_sell = k(sell.reserve_out, sell.reserve_in);
+            assert!(k_after_sell >= k_after_buy);
+
+            // The round trip can never pay back more than was put in
+            assert!(sell.amount_out <= sol_in);
+        }
+    }
+
+    #[test]
+    fn k_never_decreases_on_exact_out_buy() {
+        let mut rng = Lcg(7);
+        for _ in 0..10_000 {
+            let sol = virtual_reserve(rng.below(1_000_000_000_000), V_SOL_AMOUNT).unwrap();
+            let token = virtual_reserve(rng.below(800_000_000_000_000_000), V_TOKEN_AMOUNT).unwrap();
+            let tokens_out = rng.below(100_000_000_000_000_000);
+            let k_start = k(sol, token);
+
+            let buy = swap_exact_out(tokens_out, sol, token).unwrap();
+            assert_eq!(buy.amount_out, tokens_out);
+            assert!(k(buy.reserve_in, buy.reserve_out) >= k_start);
+
+            // Paying the quoted amount in always yields at least what was asked for
+            assert!(quote_out(buy.amount_in, sol, token).unwrap() >= tokens_out);
+        }
+    }
+
+    fn kinds() -> [CurveKind; 2] {
+        [
+            CurveKind::ConstantProduct,
+            CurveKind::VirtualConstantProduct {
+                virtual_sol: V_SOL_AMOUNT,
+                virtual_token: V_TOKEN_AMOUNT,
+            },
+        ]
+    }
+
+    #[test]
+    fn price_is_continuous_across_the_old_test_pool_threshold() {
+        // Pools used to switch formula when reserve_token dropped under 1M tokens
+        let threshold = 1_000_000_000_000_000_u64;
+        for kind in kinds() {
+            let reserve_sol = 50_000_000_000;
+            let above = kind.at(reserve_sol, threshold, 0).unwrap().spot_price().unwrap();
+            let below = kind.at(reserve_sol + 1, threshold - 1, 0).unwrap().spot_price().unwrap();
+
+            assert!(below >= above);
+            assert!(below - above <= above / 1_000_000 + 1, "{:?}: {} -> {}", kind, above, below);
+        }
+    }
+
+    #[test]
+    fn splitting_a_buy_never_pays_more_than_one_buy() {
+        let mut rng = Lcg(99);
+        for kind in kinds() {
+            for _ in 0..2_000 {
+                let reserve_sol = rng.below(1_000_000_000_000);
+                let reserve_token = rng.below(800_000_000_000_000_000);
+                let first = rng.below(10_000_000_000);
+                let second = rng.below(10_000_000_000);
+
+                let whole = kind.at(reserve_sol, reserve_token, 0).unwrap().tokens_for_sol(first + second).unwrap();
+
+                let curve = kind.at(reserve_sol, reserve_token, 0).unwrap();
+                let first_out = curve.tokens_for_sol(first).unwrap();
+                if whole > reserve_token {
+                    // The virtual reserve quotes more than the pool holds, the buy is rejected
+                    continue;
+                }
+                let curve = kind.at(reserve_sol + first, reserve_token - first_out, 0).unwrap();
+                let second_out = curve.tokens_for_sol(second).unwrap();
+
+                // Rounding down on each leg can only lose a few base units
+                assert!(first_out + second_out <= whole);
+                assert!(whole - (first_out + second_out) <= 2);
+            }
+        }
+    }
+
+    #[test]
+    fn price_rises_monotonically_with_buys() {
+        for kind in kinds() {
+            let mut reserve_sol = 10_000_000;
+            let mut reserve_token = 800_000_000_000_000_000;
+            let mut last_price = kind.at(reserve_sol, reserve_token, 0).unwrap().spot_price().unwrap();
+
+            for _ in 0..50 {
+                let curve = kind.at(reserve_sol, reserve_token, 0).unwrap();
+                let tokens = curve.tokens_for_sol(1_000_000_000).unwrap();
+                reserve_sol += 1_000_000_000;
+                reserve_token -= tokens;
+
+                let price = kind.at(reserve_sol, reserve_token, 0).unwrap().spot_price().unwrap();
+                assert!(price >= last_price);
+                last_price = price;
+            }
+        }
+    }
+}
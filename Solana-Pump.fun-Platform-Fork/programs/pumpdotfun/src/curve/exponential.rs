@@ -0,0 +1,221 @@
+use anchor_lang::prelude::*;
+
+use crate::consts::{FEE_DENOMINATOR, TOKEN_UNIT};
+use crate::curve::{area_to_lamports, max_tokens_for, BondingCurve};
+use crate::errors::CustomError;
+
+///////////////////////////////////////////////////////////////
+//
+//              Exponential bonding curve
+//
+///////////////////////////////////////////////////////////////
+//
+//  P(s) = P0 * q ^ floor(s / S_q)
+//  Supply sold => s
+//  Initial price => P0 (lamports per whole token)
+//  Growth => q = 1 + growth_bps / 10000 every S_q tokens
+//
+//  The price is constant inside a step, so the cost of the first k steps is a
+//  geometric series: S_q * P0 * G(k), G(k) = (q^k - 1) / (q - 1)
+//
+//  Powers of q are kept in Q32 fixed point, which rounds them. To keep the cost
+//  additive, step k is priced at P0 * (G(k + 1) - G(k)) from the rounded series,
+//  and the cost of a trade is the difference of one cumulative cost function.
+//  Splitting a trade then only changes the rounding to lamports.
+
+const ONE: u128 = 1 << 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Exponential {
+    pub initial_price: u64,
+    pub growth: u128, // q in Q32
+    pub step_supply: u64,
+    pub supply: u64,
+    pub available: u64, // Tokens still held by the pool
+}
+
+impl Exponential {
+    pub fn new(
+        initial_price: u64,
+        growth_bps: u16,
+        step_supply: u64,
+        supply: u64,
+        available: u64,
+    ) -> Result<Self> {
+        if initial_price == 0 || growth_bps == 0 || step_supply == 0 {
+            return err!(CustomError::InvalidCurveParameters);
+        }
+
+        let growth = (FEE_DENOMINATOR as u128 + growth_bps as u128) * ONE / FEE_DENOMINATOR as u128;
+        Ok(Self { initial_price, growth, step_supply, supply, available })
+    }
+
+    // q ^ exponent in Q32, by squaring
+    fn growth_pow(&self, mut exponent: u64) -> Result<u128> {
+        let mut result = ONE;
+        let mut base = self.growth;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.checked_mul(base).ok_or(CustomError::OverflowOrUnderflowOccurred)? >> 32;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.checked_mul(base).ok_or(CustomError::OverflowOrUnderflowOccurred)? >> 32;
+            }
+        }
+        Ok(result)
+    }
+
+    // G(k) in Q32, the sum of q^i for i < k
+    fn growth_sum(&self, steps: u64) -> Result<u128> {
+        let sum = (self.growth_pow(steps)? - ONE)
+            .checked_mul(ONE)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        Ok(sum / (self.growth - ONE))
+    }
+
+    // Price of step `step` in Q32 lamports per whole token
+    fn step_price(&self, step: u64) -> Result<u128> {
+        let growth = self
+            .growth_sum(step + 1)?
+            .checked_sub(self.growth_sum(step)?)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        (self.initial_price as u128)
+            .checked_mul(growth)
+            .ok_or(error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+
+    // Area under the price curve from zero to `supply`, whole steps plus the partial step
+    fn cumulative_area(&self, supply: u64) -> Result<u128> {
+        let step = supply / self.step_supply;
+        let (whole, price) = (self.growth_sum(step)?, self.step_price(step)?);
+        let partial = (supply % self.step_supply) as u128;
+        (self.step_supply as u128)
+            .checked_mul(whole)
+            .and_then(|area| area.checked_mul(self.initial_price as u128))
+            .and_then(|area| area.checked_add(partial.checked_mul(price)?))
+            .ok_or(error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+
+    // Area under the price curve between two supplies, in base units x Q32 lamports per whole token
+    fn area(&self, from: u64, to: u64) -> Result<u128> {
+        self.cumulative_area(to)?
+            .checked_sub(self.cumulative_area(from)?)
+            .ok_or(error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+}
+
+impl BondingCurve for Exponential {
+    fn supply(&self) -> u64 {
+        self.supply
+    }
+
+    fn price_at(&self, supply: u64) -> Result<u64> {
+        let price = self.step_price(supply / self.step_supply)? >> 32;
+        u64::try_from(price).map_err(|_| error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+
+    fn cost_to_buy(&self, tokens: u64) -> Result<u64> {
+        if tokens > self.available {
+            return err!(CustomError::NotEnoughTokenInVault);
+        }
+        let to = self.supply.checked_add(tokens).ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        let area = self.area(self.supply, to)?;
+        area_to_lamports(area, TOKEN_UNIT as u128 * ONE, true)
+    }
+
+    fn proceeds_from_sell(&self, tokens: u64) -> Result<u64> {
+        if tokens > self.supply {
+            return err!(CustomError::TokenAmountToSellTooBig);
+        }
+        let area = self.area(self.supply - tokens, self.supply)?;
+        area_to_lamports(area, TOKEN_UNIT as u128 * ONE, false)
+    }
+
+    fn tokens_for_sol(&self, sol_in: u64) -> Result<u64> {
+        Ok(max_tokens_for(sol_in, self.available, |tokens| self.cost_to_buy(tokens)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(supply: u64, available: u64) -> Exponential {
+        // 28 lamports per token, +1% every 10M tokens
+        Exponential::new(28, 100, 10_000_000 * TOKEN_UNIT, supply, available).unwrap()
+    }
+
+    #[test]
+    fn price_compounds_per_step() {
+        let curve = curve(0, 800_000_000 * TOKEN_UNIT);
+        assert_eq!(curve.price_at(0).unwrap(), 28);
+        assert_eq!(curve.price_at(10_000_000 * TOKEN_UNIT - 1).unwrap(), 28);
+        // 28 * 1.01^80 = 62.2
+        assert_eq!(curve.price_at(800_000_000 * TOKEN_UNIT).unwrap(), 62);
+    }
+
+    #[test]
+    fn series_matches_step_by_step_sum() {
+        let curve = curve(0, 800_000_000 * TOKEN_UNIT);
+        let from = 3 * 10_000_000 * TOKEN_UNIT + 123_456;
+        let to = 47 * 10_000_000 * TOKEN_UNIT + 654_321;
+
+        let mut expected = 0_u128;
+        let mut position = from;
+        while position < to {
+            let step = position / curve.step_supply;
+            let end = ((step + 1) * curve.step_supply).min(to);
+            expected += (end - position) as u128 * curve.step_price(step).unwrap();
+            position = end;
+        }
+
+        assert_eq!(curve.area(from, to).unwrap(), expected);
+    }
+
+    #[test]
+    fn round_trip_never_pays_out_more() {
+        for supply in [0, 1, 12_345_678_901, 400_000_000 * TOKEN_UNIT] {
+            let tokens = 30_000_000 * TOKEN_UNIT + 7;
+            let cost = curve(supply, u64::MAX).cost_to_buy(tokens).unwrap();
+            let proceeds = curve(supply + tokens, u64::MAX).proceeds_from_sell(tokens).unwrap();
+            assert!(proceeds <= cost);
+        }
+    }
+
+    #[test]
+    fn tokens_for_sol_is_the_largest_affordable_amount() {
+        let curve = curve(5_000_000 * TOKEN_UNIT, 800_000_000 * TOKEN_UNIT);
+        let tokens = curve.tokens_for_sol(1_000_000_000).unwrap();
+        assert!(curve.cost_to_buy(tokens).unwrap() <= 1_000_000_000);
+        assert!(curve.cost_to_buy(tokens + 1).unwrap() > 1_000_000_000);
+    }
+
+    #[test]
+    fn splitting_a_buy_never_pays_less_than_one_buy() {
+        // Parts crossing several steps, where each power of q is rounded on its own
+        let part = 25_000_000 * TOKEN_UNIT + 13;
+        for parts in [6, 30] {
+            let whole = curve(0, u64::MAX).cost_to_buy(parts * part).unwrap();
+            let split: u64 = (0..parts).map(|i| curve(i * part, u64::MAX).cost_to_buy(part).unwrap()).sum();
+            assert!(split >= whole);
+
+            let proceeds: u64 =
+                (1..=parts).map(|i| curve(i * part, u64::MAX).proceeds_from_sell(part).unwrap()).sum();
+            assert!(proceeds <= curve(parts * part, u64::MAX).proceeds_from_sell(parts * part).unwrap());
+        }
+
+        // Many small buys inside and across steps
+        let part = 1_000_000 * TOKEN_UNIT + 1;
+        let whole = curve(0, u64::MAX).cost_to_buy(150 * part).unwrap();
+        let split: u64 = (0..150).map(|i| curve(i * part, u64::MAX).cost_to_buy(part).unwrap()).sum();
+        assert!(split >= whole);
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        assert!(Exponential::new(0, 100, 1, 0, 0).is_err());
+        assert!(Exponential::new(28, 0, 1, 0, 0).is_err());
+        assert!(Exponential::new(28, 100, 0, 0, 0).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::consts::FEE_DENOMINATOR;
+use crate::errors::CustomError;
+
+/// Fee charged on `amount` for a fee in basis points, rounded down
+pub fn fee_on(amount: u64, fee_bps: u16) -> Result<u64> {
+    if fee_bps as u64 > FEE_DENOMINATOR {
+        return err!(CustomError::InvalidFee);
+    }
+
+    // amount * fee_bps / 10000 <= amount, so the result always fits back into u64
+    Ok((amount as u128 * fee_bps as u128 / FEE_DENOMINATOR as u128) as u64)
+}
+
+/// Smallest gross amount that still leaves at least `net` once `fee_on(gross)` is taken out
+///
+/// gross - floor(gross * f / D) >= net  <=>  gross > (net - 1) * D / (D - f)
+pub fn amount_with_fee(net: u64, fee_bps: u16) -> Result<u64> {
+    if fee_bps as u64 >= FEE_DENOMINATOR {
+        return err!(CustomError::InvalidFee);
+    }
+    if net == 0 {
+        return Ok(0);
+    }
+
+    let gross = (net as u128 - 1) * FEE_DENOMINATOR as u128
+        / (FEE_DENOMINATOR - fee_bps as u64) as u128
+        + 1;
+
+    u64::try_from(gross).map_err(|_| error!(CustomError::OverflowOrUnderflowOccurred))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_on_rounds_down() {
+        assert_eq!(fee_on(1_000_000_000, 100).unwrap(), 10_000_000);
+        assert_eq!(fee_on(199, 100).unwrap(), 1);
+        assert_eq!(fee_on(99, 100).unwrap(), 0);
+        assert_eq!(fee_on(1_000, 0).unwrap(), 0);
+        assert_eq!(fee_on(u64::MAX, 10_000).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn fee_on_rejects_more_than_full_fee() {
+        assert!(fee_on(1, 10_001).is_err());
+    }
+
+    #[test]
+    fn amount_with_fee_is_minimal() {
+        for fee_bps in [0, 1, 25, 100, 250, 999, 5000, 9999] {
+            for net in (0..5_000).chain(999_990_000..1_000_010_000).step_by(7) {
+                let gross = amount_with_fee(net, fee_bps).unwrap();
+                assert!(gross - fee_on(gross, fee_bps).unwrap() >= net);
+                if gross > 0 {
+                    assert!((gross - 1) - fee_on(gross - 1, fee_bps).unwrap() < net);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn amount_with_fee_rejects_full_fee() {
+        assert!(amount_with_fee(1, 10_000).is_err());
+    }
+}
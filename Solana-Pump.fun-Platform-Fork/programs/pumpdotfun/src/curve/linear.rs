@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+
+use crate::consts::TOKEN_UNIT;
+use crate::curve::{area_to_lamports, max_tokens_for, BondingCurve};
+use crate::errors::CustomError;
+
+///////////////////////////////////////////////////////////////
+//
+//              Linear bonding curve
+//
+///////////////////////////////////////////////////////////////
+//
+//  P(s) = P0 + I * s / S_I
+//  Supply sold => s
+//  Initial price => P0 (lamports per whole token)
+//  Price increment => I every S_I tokens
+//
+//  Cost of buying from a to b => integral of P(s) ds from a to b
+//    = P0 * (b - a) + I * (b - a) * (b + a) / (2 * S_I)
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Linear {
+    pub initial_price: u64,
+    pub price_increment: u64,
+    pub increment_supply: u64,
+    pub supply: u64,
+    pub available: u64, // Tokens still held by the pool
+}
+
+impl Linear {
+    pub fn new(
+        initial_price: u64,
+        price_increment: u64,
+        increment_supply: u64,
+        supply: u64,
+        available: u64,
+    ) -> Result<Self> {
+        if initial_price == 0 || increment_supply == 0 {
+            return err!(CustomError::InvalidCurveParameters);
+        }
+
+        Ok(Self { initial_price, price_increment, increment_supply, supply, available })
+    }
+
+    // Area under the price curve between two supplies, in base units x lamports per whole token,
+    // rounded up or down. The slope term width * (to + from) * I / (2 * S_I) is divided once:
+    // with a = width * (to + from) = q * d + r, it is q * I + r * I / d and r * I fits in u128.
+    fn area(&self, from: u64, to: u64, round_up: bool) -> Result<u128> {
+        let width = (to - from) as u128;
+        let base = width
+            .checked_mul(self.initial_price as u128)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+        let a = width
+            .checked_mul(to as u128 + from as u128)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        let d = 2 * self.increment_supply as u128;
+        let increment = self.price_increment as u128;
+        let (fraction, inexact) = ((a % d) * increment / d, (a % d) * increment % d);
+        let mut slope = (a / d)
+            .checked_mul(increment)
+            .and_then(|slope| slope.checked_add(fraction))
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        if round_up && inexact > 0 {
+            slope += 1;
+        }
+
+        base.checked_add(slope).ok_or(error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+}
+
+impl BondingCurve for Linear {
+    fn supply(&self) -> u64 {
+        self.supply
+    }
+
+    fn price_at(&self, supply: u64) -> Result<u64> {
+        let increase = (supply as u128 * self.price_increment as u128) / self.increment_supply as u128;
+        u64::try_from(self.initial_price as u128 + increase)
+            .map_err(|_| error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+
+    fn cost_to_buy(&self, tokens: u64) -> Result<u64> {
+        if tokens > self.available {
+            return err!(CustomError::NotEnoughTokenInVault);
+        }
+        let to = self.supply.checked_add(tokens).ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        let area = self.area(self.supply, to, true)?;
+        area_to_lamports(area, TOKEN_UNIT as u128, true)
+    }
+
+    fn proceeds_from_sell(&self, tokens: u64) -> Result<u64> {
+        if tokens > self.supply {
+            return err!(CustomError::TokenAmountToSellTooBig);
+        }
+        let area = self.area(self.supply - tokens, self.supply, false)?;
+        area_to_lamports(area, TOKEN_UNIT as u128, false)
+    }
+
+    fn tokens_for_sol(&self, sol_in: u64) -> Result<u64> {
+        Ok(max_tokens_for(sol_in, self.available, |tokens| self.cost_to_buy(tokens)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(supply: u64, available: u64) -> Linear {
+        // 28 lamports per token, +1 lamport every 1M tokens
+        Linear::new(28, 1, 1_000_000 * TOKEN_UNIT, supply, available).unwrap()
+    }
+
+    #[test]
+    fn price_grows_linearly() {
+        let curve = curve(0, 800_000_000 * TOKEN_UNIT);
+        assert_eq!(curve.price_at(0).unwrap(), 28);
+        assert_eq!(curve.price_at(1_000_000 * TOKEN_UNIT).unwrap(), 29);
+        assert_eq!(curve.price_at(800_000_000 * TOKEN_UNIT).unwrap(), 828);
+    }
+
+    #[test]
+    fn cost_matches_the_integral() {
+        // Buying the first 2M tokens: 28 * 2M + (2M^2 / 2) / 1M = 58M lamports
+        let curve = curve(0, 800_000_000 * TOKEN_UNIT);
+        assert_eq!(curve.cost_to_buy(2_000_000 * TOKEN_UNIT).unwrap(), 58_000_000);
+    }
+
+    #[test]
+    fn round_trip_never_pays_out_more() {
+        for supply in [0, 1, 12_345_678_901, 400_000_000 * TOKEN_UNIT] {
+            let tokens = 3_000_000 * TOKEN_UNIT + 7;
+            let cost = curve(supply, u64::MAX).cost_to_buy(tokens).unwrap();
+            let proceeds = curve(supply + tokens, u64::MAX).proceeds_from_sell(tokens).unwrap();
+            assert!(proceeds <= cost);
+        }
+    }
+
+    #[test]
+    fn tokens_for_sol_is_the_largest_affordable_amount() {
+        let curve = curve(5_000_000 * TOKEN_UNIT, 800_000_000 * TOKEN_UNIT);
+        let tokens = curve.tokens_for_sol(1_000_000_000).unwrap();
+        assert!(curve.cost_to_buy(tokens).unwrap() <= 1_000_000_000);
+        assert!(curve.cost_to_buy(tokens + 1).unwrap() > 1_000_000_000);
+    }
+
+    #[test]
+    fn splitting_a_buy_never_pays_less_than_one_buy() {
+        // A steep curve, where rounding the slope term early drops whole lamports per buy
+        let steep = |supply| Linear::new(1, 1_000_000_000_000, 3 * TOKEN_UNIT + 1, supply, u64::MAX).unwrap();
+        let part = TOKEN_UNIT / 7 + 3;
+
+        let whole = steep(0).cost_to_buy(10 * part).unwrap();
+        let split: u64 = (0..10).map(|i| steep(i * part).cost_to_buy(part).unwrap()).sum();
+        assert!(split >= whole);
+
+        let proceeds: u64 = (1..=10).map(|i| steep(i * part).proceeds_from_sell(part).unwrap()).sum();
+        assert!(proceeds <= steep(10 * part).proceeds_from_sell(10 * part).unwrap());
+    }
+
+    #[test]
+    fn cannot_sell_more_than_was_sold() {
+        assert!(curve(10, 100).proceeds_from_sell(11).is_err());
+        assert!(curve(10, 100).cost_to_buy(101).is_err());
+    }
+}
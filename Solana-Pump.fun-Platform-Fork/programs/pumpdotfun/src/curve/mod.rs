@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::CustomError;
+
+pub mod constant_product;
+pub mod exponential;
+pub mod fees;
+pub mod linear;
+pub use constant_product::*;
+pub use exponential::*;
+pub use fees::*;
+pub use linear::*;
+
+/// Pricing shared by every curve family, positioned at the pool's current supply.
+/// `supply` is the number of tokens sold out of the pool. Amounts are net of fees.
+pub trait BondingCurve {
+    /// Tokens currently sold out of the pool
+    fn supply(&self) -> u64;
+
+    /// Marginal price in lamports per whole token once `supply` tokens are sold
+    fn price_at(&self, supply: u64) -> Result<u64>;
+
+    /// Lamports needed to receive exactly `tokens` tokens
+    fn cost_to_buy(&self, tokens: u64) -> Result<u64>;
+
+    /// Lamports received for selling exactly `tokens` tokens
+    fn proceeds_from_sell(&self, tokens: u64) -> Result<u64>;
+
+    /// Tokens received for paying exactly `sol_in` lamports
+    fn tokens_for_sol(&self, sol_in: u64) -> Result<u64>;
+
+    /// Marginal price at the current supply
+    fn spot_price(&self) -> Result<u64> {
+        self.price_at(self.supply())
+    }
+}
+
+/// Pricing model of a pool, fixed when the pool is created
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveKind {
+    /// x * y = k on the real reserves only
+    ConstantProduct,
+    /// (x + X) * (y + Y) = k with virtual reserves X, Y
+    VirtualConstantProduct { virtual_sol: u64, virtual_token: u64 },
+    /// Price grows by `price_increment` every `increment_supply` tokens sold
+    Linear { initial_price: u64, price_increment: u64, increment_supply: u64 },
+    /// Price grows by `growth_bps` every `step_supply` tokens sold
+    Exponential { initial_price: u64, growth_bps: u16, step_supply: u64 },
+}
+
+impl CurveKind {
+    // Tag (1) + largest variant (8 + 8 + 8)
+    pub const SIZE: usize = 1 + 8 + 8 + 8;
+
+    /// Curve of this kind positioned at the given real reserves and supply sold
+    pub fn at(&self, reserve_sol: u64, reserve_token: u64, supply: u64) -> Result<Box<dyn BondingCurve>> {
+        Ok(match *self {
+            CurveKind::ConstantProduct => {
+                Box::new(ConstantProduct::new(reserve_sol, reserve_token, 0, 0, supply)?)
+            }
+            CurveKind::VirtualConstantProduct { virtual_sol, virtual_token } => Box::new(
+                ConstantProduct::new(reserve_sol, reserve_token, virtual_sol, virtual_token, supply)?,
+            ),
+            CurveKind::Linear { initial_price, price_increment, increment_supply } => Box::new(
+                Linear::new(initial_price, price_increment, increment_supply, supply, reserve_token)?,
+            ),
+            CurveKind::Exponential { initial_price, growth_bps, step_supply } => Box::new(
+                Exponential::new(initial_price, growth_bps, step_supply, supply, reserve_token)?,
+            ),
+        })
+    }
+}
+
+/// Largest amount in `0..=max_tokens` whose `cost` fits in `budget`, for curves
+/// that only have a closed form for the cost of a given amount
+pub fn max_tokens_for(budget: u64, max_tokens: u64, cost: impl Fn(u64) -> Result<u64>) -> u64 {
+    let (mut low, mut high) = (0_u64, max_tokens);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        match cost(mid) {
+            Ok(amount) if amount <= budget => low = mid,
+            _ => high = mid - 1,
+        }
+    }
+    low
+}
+
+/// Converts an area under a price curve (base units x lamports per whole token,
+/// scaled by `scale`) into lamports, rounding in favour of the pool
+pub fn area_to_lamports(area: u128, scale: u128, round_up: bool) -> Result<u64> {
+    let lamports = if round_up { area.div_ceil(scale) } else { area / scale };
+    u64::try_from(lamports).map_err(|_| error!(CustomError::OverflowOrUnderflowOccurred))
+}
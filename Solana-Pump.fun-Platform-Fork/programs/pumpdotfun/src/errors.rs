@@ -52,4 +52,121 @@ pub enum CustomError {
    
     #[msg("Amount is negative")]
     NegativeNumber,
+
+    #[msg("Slippage tolerance exceeded")]
+    SlippageExceeded,
+
+    #[msg("Signer is not the proposed admin")]
+    NotPendingAdmin,
+
+    #[msg("Virtual reserves are outside the configured bounds")]
+    InvalidVirtualReserves,
+
+    #[msg("Invalid bonding curve parameters")]
+    InvalidCurveParameters,
+
+    #[msg("Pool is not open for trading")]
+    PoolNotTrading,
+
+    #[msg("Pool has not graduated")]
+    PoolNotGraduated,
+
+    #[msg("Liquidity can only be added before trading opens")]
+    PoolAlreadyFunded,
+
+    #[msg("Pool has not been funded")]
+    PoolNotFunded,
+
+    #[msg("Only a trading pool can graduate")]
+    PoolCannotGraduate,
+
+    #[msg("Liquidity cannot be removed before the pool is funded or during its presale")]
+    LiquidityLocked,
+
+    #[msg("Mint and freeze authorities must be revoked")]
+    MintAuthorityNotRevoked,
+
+    #[msg("Mint has an unsupported extension")]
+    UnsupportedMintExtension,
+
+    #[msg("Pool reserves are not backed by its vaults")]
+    ReserveMismatch,
+
+    #[msg("Pool reserves do not add up to its supply")]
+    SupplyMismatch,
+
+    #[msg("Pool is closed")]
+    PoolClosed,
+
+    #[msg("Invalid graduation threshold")]
+    InvalidGraduationThreshold,
+
+    #[msg("AMM program does not match the configuration")]
+    InvalidAmmProgram,
+
+    #[msg("AMM did not take the migrated reserves")]
+    MigrationFailed,
+
+    #[msg("Creator and referral fees exceed the trading fee")]
+    InvalidFeeSplit,
+
+    #[msg("Traders cannot refer themselves")]
+    SelfReferral,
+
+    #[msg("Buys are paused")]
+    BuysPaused,
+
+    #[msg("Sells are paused")]
+    SellsPaused,
+
+    #[msg("Pool creation is paused")]
+    PoolCreationPaused,
+
+    #[msg("Liquidity removal is paused")]
+    LiquidityRemovalPaused,
+
+    #[msg("Only the admin can lift a pause")]
+    GuardianCannotUnpause,
+
+    #[msg("Liquidity is locked until its unlock time")]
+    LiquidityStillLocked,
+
+    #[msg("Invalid liquidity lock period")]
+    InvalidLockPeriod,
+
+    #[msg("Trading has not started yet")]
+    LaunchNotStarted,
+
+    #[msg("Purchase exceeds the per-wallet cap of the launch window")]
+    BuyCapExceeded,
+
+    #[msg("Invalid launch protection")]
+    InvalidLaunchProtection,
+
+    #[msg("Invalid presale")]
+    InvalidPresale,
+
+    #[msg("Wallet is not eligible for the presale")]
+    NotOnPresaleAllowlist,
+
+    #[msg("Purchase exceeds the wallet's presale allocation")]
+    PresaleAllocationExceeded,
+
+    #[msg("Invalid launch fee")]
+    InvalidLaunchFee,
+
+    #[msg("Bonding curve parameters are outside the configured bounds")]
+    CurveOutOfBounds,
+
+    #[msg("The pool must hold the whole token supply before it opens")]
+    SupplyNotInPool,
+
+    #[msg("Buys during the presale or launch window need the wallet or NFT purchases account")]
+    PurchasesAccountRequired,
+
+    #[msg("A presale can only start on a funded pool that has not opened for trading")]
+    PresaleNotAllowed,
+
+    #[msg("Mint transfer fees must have no fee or withdraw authority")]
+    TransferFeeAuthoritySet,
 }
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+
+use crate::curve::CurveKind;
+use crate::state::{LaunchFee, LaunchProtection, PauseFlags, PoolStatus, PresaleAccess};
+
+// Emitted through `emit_cpi!` so indexers read them from the instruction data
+// rather than from logs, which can be truncated
+
+#[event]
+pub struct TokenCreated {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub total_supply: u64,
+}
+
+#[event]
+pub struct PoolCreated {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub curve: CurveKind,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub provider: Pubkey,
+    pub shares: u64, // Shares minted to the provider
+    pub reserves_after: Reserves,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub pool: Pubkey,
+    pub provider: Pubkey,
+    pub shares: u64, // Shares burned by the provider
+    pub sol: u64,
+    pub tokens: u64,
+    pub reserves_after: Reserves,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reserves {
+    pub sol: u64,
+    pub token: u64,
+}
+
+#[event]
+pub struct Trade {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub side: TradeSide,
+    pub sol: u64,          // Lamports paid / received by the user, fee excluded
+    pub tokens: u64,       // Tokens received / paid by the user
+    pub fee: u64,          // Lamports taken as fee
+    pub creator_fee: u64,  // Part of `fee` paid to the pool creator
+    pub referral_fee: u64, // Part of `fee` paid to the referrer
+    pub referrer: Option<Pubkey>,
+    pub reserves_after: Reserves,
+    pub price: u64,        // Realised price in lamports per whole token
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub admin: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReservesSynced {
+    pub pool: Pubkey,
+    pub reserves_before: Reserves,
+    pub reserves_after: Reserves,
+}
+
+#[event]
+pub struct CreatorFeesClaimed {
+    pub pool: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralFeesClaimed {
+    pub referrer: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub pool: Option<Pubkey>, // None for the global flags
+    pub authority: Pubkey,
+    pub paused: PauseFlags,
+}
+
+// Emitted next to the event of the action that moved the pool, see `PoolStatus::next`
+#[event]
+pub struct PoolStatusChanged {
+    pub pool: Pubkey,
+    pub from: PoolStatus,
+    pub to: PoolStatus,
+}
+
+#[event]
+pub struct TradingOpened {
+    pub pool: Pubkey,
+    pub unlock_at: i64, // When the providers' seed may be withdrawn
+}
+
+#[event]
+pub struct PresaleStarted {
+    pub pool: Pubkey,
+    pub presale: PresaleAccess,
+}
+
+#[event]
+pub struct PoolGraduated {
+    pub pool: Pubkey,
+    pub reserves: Reserves, // Reserves after the buy that crossed the threshold
+}
+
+#[event]
+pub struct PoolMigrated {
+    pub pool: Pubkey,
+    pub amm_program: Pubkey,
+    pub sol: u64,    // Lamports moved to the AMM, the providers' seed excluded
+    pub tokens: u64, // Tokens moved to the AMM
+}
+
+#[event]
+pub struct LaunchFeeUpdated {
+    pub pool: Pubkey,
+    pub launch_fee: Option<LaunchFee>,
+}
+
+#[event]
+pub struct LaunchProtectionUpdated {
+    pub pool: Pubkey,
+    pub launch_protection: Option<LaunchProtection>,
+}
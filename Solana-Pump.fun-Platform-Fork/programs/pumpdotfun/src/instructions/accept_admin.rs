@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::{errors::CustomError, state::*};
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+
+    if dex_config.pending_admin == Pubkey::default() || dex_config.pending_admin != ctx.accounts.new_admin.key() {
+        return err!(CustomError::NotPendingAdmin);
+    }
+
+    dex_config.admin = ctx.accounts.new_admin.key();
+    dex_config.pending_admin = Pubkey::default();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub new_admin: Signer<'info>,
+}
@@ -1,30 +1,56 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::state::{LiquidityPool, LiquidityPoolAccount};
+use crate::{
+    events::{LiquidityAdded, PoolStatusChanged, Reserves},
+    state::{LiquidityPool, LiquidityPoolAccount, LiquidityProvider, PoolAccounts, PoolTransition},
+};
 
-pub fn add_liquidity(ctx: Context<AddLiquidity>) -> Result<()> {
+pub fn add_liquidity(ctx: Context<AddLiquidity>, token_amount: u64, max_sol_amount: u64) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
+    let from = pool.status;
+    pool.transition(PoolTransition::Fund)?;
 
-    let token_accounts = (
-        &mut *ctx.accounts.token_mint,
-        &mut *ctx.accounts.pool_token_account,
-        &mut *ctx.accounts.user_token_account,
-    );
+    let accounts = PoolAccounts {
+        token_mint: &ctx.accounts.token_mint,
+        pool_token_account: &mut ctx.accounts.pool_token_account,
+        user_token_account: &mut ctx.accounts.user_token_account,
+        pool_sol_vault: &ctx.accounts.pool_sol_vault,
+        pool_sol_vault_bump: ctx.bumps.pool_sol_vault,
+        user: &ctx.accounts.user,
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+    };
 
-    pool.add_liquidity(
-        token_accounts,
-        &mut ctx.accounts.pool_sol_vault,
-        &ctx.accounts.user,
-        &ctx.accounts.token_program,
-        &ctx.accounts.system_program,
+    let shares = pool.add_liquidity(
+        &mut ctx.accounts.liquidity_provider,
+        accounts,
+        token_amount,
+        max_sol_amount,
     )?;
+
+    emit_cpi!(LiquidityAdded {
+        pool: pool.key(),
+        provider: ctx.accounts.user.key(),
+        shares,
+        reserves_after: Reserves { sol: pool.reserve_sol, token: pool.reserve_token },
+    });
+    // Only the first provider funds the pool
+    if pool.status != from {
+        emit_cpi!(PoolStatusChanged { pool: pool.key(), from, to: pool.status });
+    }
+
+    ctx.accounts
+        .pool
+        .check_invariants(&mut ctx.accounts.pool_token_account, &ctx.accounts.pool_sol_vault)?;
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct AddLiquidity<'info> {
     #[account(
@@ -34,22 +60,33 @@ pub struct AddLiquidity<'info> {
     )]
     pub pool: Account<'info, LiquidityPool>,
 
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = LiquidityProvider::ACCOUNT_SIZE,
+        seeds = [LiquidityProvider::SEED_PREFIX.as_bytes(), pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub liquidity_provider: Box<Account<'info, LiquidityProvider>>,
+
     #[account(mut)]
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = pool
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
     )]
-    pub pool_token_account: Box<Account<'info, TokenAccount>>,
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = token_mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK:
     #[account(
@@ -63,6 +100,6 @@ pub struct AddLiquidity<'info> {
     pub user: Signer<'info>,
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
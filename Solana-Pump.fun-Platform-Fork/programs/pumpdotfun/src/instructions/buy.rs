@@ -1,41 +1,221 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::state::{CurveConfiguration, LiquidityPool, LiquidityPoolAccount};
+use crate::{
+    errors::CustomError,
+    events::{PoolGraduated, PoolStatusChanged, Reserves, Trade, TradeSide},
+    state::{
+        CreatorFeeVault, CurveConfiguration, FeeAccounts, LiquidityPool, LiquidityPoolAccount, NftPurchases,
+        PoolAccounts, PoolStatus, PresaleProof, ProtocolFeeVault, Referrer, TradeResult, WalletPurchases,
+    },
+};
+
+// Tokens the user may buy if the pool is in its presale, `None` once it is open to everyone
+fn presale_allocation(accounts: &Buy, presale_proof: Option<&PresaleProof>) -> Result<Option<u64>> {
+    if accounts.pool.status != PoolStatus::Presale {
+        return Ok(None);
+    }
+
+    let presale = accounts.pool.presale.ok_or(CustomError::InvalidPresale)?;
+    let proof = presale_proof.ok_or(CustomError::NotOnPresaleAllowlist)?;
+    let nft = match (&accounts.nft_token_account, &accounts.nft_mint) {
+        (Some(token_account), Some(mint)) => Some((&**token_account, &**mint)),
+        _ => None,
+    };
+
+    presale.allocation_for(&accounts.user.key(), proof, nft).map(Some)
+}
+
+// Holds a presale buy to the wallet's or the NFT's allocation, or a public one to the
+// launch protection
+fn record_buy(
+    accounts: &mut Buy,
+    allocation: Option<u64>,
+    presale_proof: Option<&PresaleProof>,
+    trade: &TradeResult,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if !accounts.pool.limits_purchases(now) {
+        return Ok(());
+    }
+
+    let wallet_purchases = accounts.wallet_purchases.as_deref_mut();
+    match (allocation, presale_proof) {
+        // Collection allocations follow the NFT rather than whoever holds it
+        (Some(allocation), Some(PresaleProof::Collection)) => {
+            let purchases = accounts
+                .nft_purchases
+                .as_deref_mut()
+                .ok_or(CustomError::PurchasesAccountRequired)?;
+            accounts.pool.record_presale_buy(&mut purchases.presale_tokens, allocation, trade)
+        }
+        (Some(allocation), _) => {
+            let purchases = wallet_purchases.ok_or(CustomError::PurchasesAccountRequired)?;
+            accounts.pool.record_presale_buy(&mut purchases.presale_tokens, allocation, trade)
+        }
+        (None, _) => {
+            let purchases = wallet_purchases.ok_or(CustomError::PurchasesAccountRequired)?;
+            accounts.pool.record_protected_buy(now, purchases, trade)
+        }
+    }
+}
+
+pub fn buy(ctx: Context<Buy>, amount: u64, min_amount_out: u64, presale_proof: Option<PresaleProof>) -> Result<()> {
+    let allocation = presale_allocation(ctx.accounts, presale_proof.as_ref())?;
 
-pub fn buy(ctx: Context<Buy>, amount: u64) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
+    let from = pool.status;
 
-    let token_one_accounts = (
-        &mut *ctx.accounts.token_mint,
-        &mut *ctx.accounts.pool_token_account,
-        &mut *ctx.accounts.user_token_account,
-    );
+    let accounts = PoolAccounts {
+        token_mint: &ctx.accounts.token_mint,
+        pool_token_account: &mut ctx.accounts.pool_token_account,
+        user_token_account: &mut ctx.accounts.user_token_account,
+        pool_sol_vault: &ctx.accounts.pool_sol_vault,
+        pool_sol_vault_bump: ctx.bumps.pool_sol_vault,
+        user: &ctx.accounts.user,
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+    };
 
-    pool.buy(
-        &mut ctx.accounts.dex_configuration_account,
-        token_one_accounts,
-        &mut ctx.accounts.pool_sol_vault,
+    let trade = pool.buy(
+        &ctx.accounts.dex_configuration_account,
+        FeeAccounts {
+            protocol_fee_vault: &mut ctx.accounts.protocol_fee_vault,
+            creator_fee_vault: &mut ctx.accounts.creator_fee_vault,
+            referrer: ctx.accounts.referrer.as_deref_mut(),
+        },
+        accounts,
         amount,
-        &ctx.accounts.user,
-        &ctx.accounts.token_program,
-        &ctx.accounts.system_program,
     )?;
+
+    if trade.token_amount < min_amount_out {
+        return err!(CustomError::SlippageExceeded);
+    }
+
+    record_buy(ctx.accounts, allocation, presale_proof.as_ref(), &trade)?;
+
+    let pool = &ctx.accounts.pool;
+    emit_cpi!(Trade {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        side: TradeSide::Buy,
+        sol: trade.sol_amount,
+        tokens: trade.token_amount,
+        fee: trade.fee,
+        creator_fee: trade.creator_fee,
+        referral_fee: trade.referral_fee,
+        referrer: ctx.accounts.referrer.as_ref().map(|referrer| referrer.key()),
+        reserves_after: Reserves { sol: pool.reserve_sol, token: pool.reserve_token },
+        price: trade.price,
+    });
+    // The only move a buy makes
+    if pool.status != from {
+        emit_cpi!(PoolStatusChanged { pool: pool.key(), from, to: pool.status });
+        emit_cpi!(PoolGraduated {
+            pool: pool.key(),
+            reserves: Reserves { sol: pool.reserve_sol, token: pool.reserve_token },
+        });
+    }
+
+    ctx.accounts
+        .pool
+        .check_invariants(&mut ctx.accounts.pool_token_account, &ctx.accounts.pool_sol_vault)?;
+
+    Ok(())
+}
+
+pub fn buy_exact_out(
+    ctx: Context<Buy>,
+    token_amount: u64,
+    max_sol_cost: u64,
+    presale_proof: Option<PresaleProof>,
+) -> Result<()> {
+    let allocation = presale_allocation(ctx.accounts, presale_proof.as_ref())?;
+
+    let pool = &mut ctx.accounts.pool;
+    let from = pool.status;
+
+    let accounts = PoolAccounts {
+        token_mint: &ctx.accounts.token_mint,
+        pool_token_account: &mut ctx.accounts.pool_token_account,
+        user_token_account: &mut ctx.accounts.user_token_account,
+        pool_sol_vault: &ctx.accounts.pool_sol_vault,
+        pool_sol_vault_bump: ctx.bumps.pool_sol_vault,
+        user: &ctx.accounts.user,
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+    };
+
+    let trade = pool.buy_exact_out(
+        &ctx.accounts.dex_configuration_account,
+        FeeAccounts {
+            protocol_fee_vault: &mut ctx.accounts.protocol_fee_vault,
+            creator_fee_vault: &mut ctx.accounts.creator_fee_vault,
+            referrer: ctx.accounts.referrer.as_deref_mut(),
+        },
+        accounts,
+        token_amount,
+    )?;
+
+    let sol_cost = trade
+        .sol_amount
+        .checked_add(trade.fee)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    if sol_cost > max_sol_cost {
+        return err!(CustomError::SlippageExceeded);
+    }
+
+    record_buy(ctx.accounts, allocation, presale_proof.as_ref(), &trade)?;
+
+    let pool = &ctx.accounts.pool;
+    emit_cpi!(Trade {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        side: TradeSide::Buy,
+        sol: trade.sol_amount,
+        tokens: trade.token_amount,
+        fee: trade.fee,
+        creator_fee: trade.creator_fee,
+        referral_fee: trade.referral_fee,
+        referrer: ctx.accounts.referrer.as_ref().map(|referrer| referrer.key()),
+        reserves_after: Reserves { sol: pool.reserve_sol, token: pool.reserve_token },
+        price: trade.price,
+    });
+    // The only move a buy makes
+    if pool.status != from {
+        emit_cpi!(PoolStatusChanged { pool: pool.key(), from, to: pool.status });
+        emit_cpi!(PoolGraduated {
+            pool: pool.key(),
+            reserves: Reserves { sol: pool.reserve_sol, token: pool.reserve_token },
+        });
+    }
+
+    ctx.accounts
+        .pool
+        .check_invariants(&mut ctx.accounts.pool_token_account, &ctx.accounts.pool_sol_vault)?;
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Buy<'info> {
     #[account(
-        mut,
         seeds = [CurveConfiguration::SEED.as_bytes()],
         bump,
     )]
     pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
 
+    #[account(
+        mut,
+        seeds = [ProtocolFeeVault::SEED.as_bytes()],
+        bump
+    )]
+    pub protocol_fee_vault: Box<Account<'info, ProtocolFeeVault>>,
+
     #[account(
         mut,
         seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
@@ -43,15 +223,30 @@ pub struct Buy<'info> {
     )]
     pub pool: Box<Account<'info, LiquidityPool>>,
 
+    #[account(
+        mut,
+        seeds = [CreatorFeeVault::SEED_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: Box<Account<'info, CreatorFeeVault>>,
+
+    // Partner app the trade was routed through, paid part of the fee
+    #[account(
+        mut,
+        constraint = referrer.authority != user.key() @ CustomError::SelfReferral
+    )]
+    pub referrer: Option<Box<Account<'info, Referrer>>>,
+
     #[account(mut)]
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = pool
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
     )]
-    pub pool_token_account: Box<Account<'info, TokenAccount>>,
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK:
     #[account(
@@ -66,13 +261,41 @@ pub struct Buy<'info> {
         payer = user,
         associated_token::mint = token_mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // What the user bought during the pool's presale and launch window, see `PresaleAccess`
+    // and `LaunchProtection`. Only needed while one of them applies.
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = WalletPurchases::ACCOUNT_SIZE,
+        seeds = [WalletPurchases::SEED_PREFIX.as_bytes(), pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub wallet_purchases: Option<Box<Account<'info, WalletPurchases>>>,
+
+    // Collection NFT held by the user, for a `PresaleProof::Collection` buy
+    pub nft_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: Checked against `nft_token_account` and the presale collection by `PresaleAccess::allocation_for`
+    pub nft_mint: Option<UncheckedAccount<'info>>,
+
+    // What was bought with the collection NFT, for a `PresaleProof::Collection` buy
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = NftPurchases::ACCOUNT_SIZE,
+        seeds = [NftPurchases::SEED_PREFIX.as_bytes(), pool.key().as_ref(), nft_mint.as_ref().ok_or(CustomError::PurchasesAccountRequired)?.key().as_ref()],
+        bump
     )]
-    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub nft_purchases: Option<Box<Account<'info, NftPurchases>>>,
 
     #[account(mut)]
     pub user: Signer<'info>,
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
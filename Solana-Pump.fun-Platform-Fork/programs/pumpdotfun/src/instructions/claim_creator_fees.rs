@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::{errors::CustomError, events::CreatorFeesClaimed, state::*};
+
+pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    if ctx.accounts.creator.key() != ctx.accounts.pool.creator {
+        return err!(CustomError::NotCreator);
+    }
+
+    let amount = ctx.accounts.creator_fee_vault.fees;
+
+    // The vault keeps its rent on top of the accrued fees
+    if ctx.accounts.creator_fee_vault.to_account_info().lamports() < amount {
+        return err!(CustomError::InsufficientFunds);
+    }
+
+    **ctx.accounts.creator_fee_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.creator.try_borrow_mut_lamports()? += amount;
+
+    ctx.accounts.creator_fee_vault.fees = 0;
+
+    emit_cpi!(CreatorFeesClaimed {
+        pool: ctx.accounts.pool.key(),
+        creator: ctx.accounts.creator.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(
+        seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), pool.token.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(
+        mut,
+        seeds = [CreatorFeeVault::SEED_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: Box<Account<'info, CreatorFeeVault>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
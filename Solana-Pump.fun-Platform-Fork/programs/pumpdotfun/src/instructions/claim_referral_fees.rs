@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::{errors::CustomError, events::ReferralFeesClaimed, state::*};
+
+pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+    let amount = ctx.accounts.referrer.fees;
+
+    // The account keeps its rent on top of the accrued fees
+    if ctx.accounts.referrer.to_account_info().lamports() < amount {
+        return err!(CustomError::InsufficientFunds);
+    }
+
+    **ctx.accounts.referrer.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.authority.try_borrow_mut_lamports()? += amount;
+
+    ctx.accounts.referrer.fees = 0;
+
+    emit_cpi!(ReferralFeesClaimed {
+        referrer: ctx.accounts.referrer.key(),
+        authority: ctx.accounts.authority.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(
+        mut,
+        seeds = [Referrer::SEED_PREFIX.as_bytes(), authority.key().as_ref()],
+        bump
+    )]
+    pub referrer: Box<Account<'info, Referrer>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
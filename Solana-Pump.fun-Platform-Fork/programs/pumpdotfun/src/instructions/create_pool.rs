@@ -1,23 +1,51 @@
-use crate::state::*;
+use crate::{curve::CurveKind, errors::CustomError, events::PoolCreated, state::*, utils::check_mint_extensions};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-pub fn create_pool(ctx: Context<CreateLiquidityPool>) -> Result<()> {
+pub fn create_pool(ctx: Context<CreateLiquidityPool>, curve: CurveKind) -> Result<()> {
+    ctx.accounts.dex_configuration_account.paused.check(PauseScope::CreatePool)?;
+    ctx.accounts.dex_configuration_account.check_curve(&curve)?;
+
+    // A mint that can still be inflated or frozen would let the creator rug buyers
+    let mint = &ctx.accounts.token_mint;
+    if !ctx.accounts.dex_configuration_account.allow_mint_authorities
+        && (mint.mint_authority.is_some() || mint.freeze_authority.is_some())
+    {
+        return err!(CustomError::MintAuthorityNotRevoked);
+    }
+    check_mint_extensions(&mint.to_account_info())?;
+
     let pool = &mut ctx.accounts.pool;
 
     pool.set_inner(LiquidityPool::new(
         ctx.accounts.payer.key(),
         ctx.accounts.token_mint.key(),
         ctx.bumps.pool,
+        curve,
     ));
+
+    emit_cpi!(PoolCreated {
+        pool: pool.key(),
+        mint: pool.token,
+        creator: pool.creator,
+        curve,
+    });
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CreateLiquidityPool<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
     #[account(
         init,
         space = LiquidityPool::ACCOUNT_SIZE,
@@ -27,20 +55,30 @@ pub struct CreateLiquidityPool<'info> {
     )]
     pub pool: Box<Account<'info, LiquidityPool>>,
 
+    #[account(
+        init,
+        space = CreatorFeeVault::ACCOUNT_SIZE,
+        payer = payer,
+        seeds = [CreatorFeeVault::SEED_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: Box<Account<'info, CreatorFeeVault>>,
+
     #[account(mut)]
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         init,
         payer = payer,
         associated_token::mint = token_mint,
-        associated_token::authority = pool
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
     )]
-    pub pool_token_account: Box<Account<'info, TokenAccount>>,
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
@@ -1,10 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    metadata::{
-        create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3, Metadata
-    },
-    token::{mint_to, Mint, MintTo, Token, TokenAccount},
+    token_2022::ID as TOKEN_2022_PROGRAM_ID,
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface},
+};
+use crate::{
+    events::TokenCreated,
+    utils::{initialize_token_metadata, revoke_mint_authorities},
 };
 
 pub fn create_token(
@@ -13,37 +15,21 @@ pub fn create_token(
     symbol: String,
     uri: String,
     total_supply: u64,
+    revoke_authorities: bool,
 ) -> Result<()> {
     let mint = &ctx.accounts.mint_account;
     let payer = &ctx.accounts.payer;
     let token_program = &ctx.accounts.token_program;
 
-    // Create Metadata Account
-    let _ = create_metadata_accounts_v3(
-        CpiContext::new(
-            ctx.accounts.token_metadata_program.to_account_info(),
-            CreateMetadataAccountsV3 {
-                metadata: ctx.accounts.metadata_account.to_account_info(),
-                mint: ctx.accounts.mint_account.to_account_info(),
-                mint_authority: ctx.accounts.payer.to_account_info(),
-                update_authority: ctx.accounts.payer.to_account_info(),
-                payer: ctx.accounts.payer.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-                rent: ctx.accounts.rent.to_account_info(),
-            },
-        ),
-        DataV2 {
-            name,
-            symbol,
-            uri,
-            seller_fee_basis_points: 0,
-            creators: None,
-            collection: None,
-            uses: None,
-        },
-        false, // Is Mutable
-        true,  // Update authority is signer
-        None,  // Collection details
+    // Metadata lives in the mint itself, pointed to by its metadata pointer extension
+    initialize_token_metadata(
+        token_program.to_account_info(),
+        mint.to_account_info(),
+        payer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        name.clone(),
+        symbol.clone(),
+        uri.clone(),
     )?;
 
     // Mint the Total Supply to the Payer's Token Account
@@ -56,9 +42,27 @@ pub fn create_token(
     let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
     mint_to(cpi_ctx, total_supply)?;
 
+    if revoke_authorities {
+        revoke_mint_authorities(
+            token_program.to_account_info(),
+            mint.to_account_info(),
+            payer.to_account_info(),
+        )?;
+    }
+
+    emit_cpi!(TokenCreated {
+        mint: mint.key(),
+        creator: payer.key(),
+        name,
+        symbol,
+        uri,
+        total_supply,
+    });
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CreateToken<'info> {
     #[account(mut)]
@@ -70,28 +74,24 @@ pub struct CreateToken<'info> {
         mint::decimals = 9,
         mint::authority = payer.key(),
         mint::freeze_authority = payer.key(),
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = payer.key(),
+        extensions::metadata_pointer::metadata_address = mint_account.key(),
     )]
-    pub mint_account: Account<'info, Mint>,
-
-    /// CHECK: safe
-    #[account(
-        mut,
-        seeds = [b"metadata", token_metadata_program.key().as_ref(), mint_account.key().as_ref()],
-        bump,
-        seeds::program = token_metadata_program.key()
-    )]
-    pub metadata_account: UncheckedAccount<'info>,
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         init,
         payer = payer,
         associated_token::mint = mint_account,
         associated_token::authority = payer,
+        associated_token::token_program = token_program,
     )]
-    pub token_account: Account<'info, TokenAccount>,
-    pub token_metadata_program: Program<'info, Metadata>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    // New tokens are minted under Token-2022, pools also accept legacy SPL mints
+    #[account(address = TOKEN_2022_PROGRAM_ID)]
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -1,17 +1,17 @@
-use crate::{errors::CustomError, state::*};
+use crate::{consts::FEE_DENOMINATOR, errors::CustomError, state::*};
 use anchor_lang::prelude::*;
 
 pub fn initialize(
     ctx: Context<InitializeCurveConfiguration>,
-    fees: f64,
+    fees: u16,
 ) -> Result<()> {
     let dex_config = &mut ctx.accounts.dex_configuration_account;
 
-    if fees < 0_f64 || fees > 10000_f64 {
+    if fees as u64 > FEE_DENOMINATOR {
         return err!(CustomError::InvalidFee);
     }
 
-    dex_config.set_inner(CurveConfiguration::new(fees, ctx.accounts.admin.key(), 0));
+    dex_config.set_inner(CurveConfiguration::new(fees, ctx.accounts.admin.key()));
 
     Ok(())
 }
@@ -27,6 +27,15 @@ pub struct InitializeCurveConfiguration<'info> {
     )]
     pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
 
+    #[account(
+        init,
+        space = ProtocolFeeVault::ACCOUNT_SIZE,
+        payer = admin,
+        seeds = [ProtocolFeeVault::SEED.as_bytes()],
+        bump,
+    )]
+    pub protocol_fee_vault: Box<Account<'info, ProtocolFeeVault>>,
+
     #[account(mut)]
     pub admin: Signer<'info>,
     pub rent: Sysvar<'info, Rent>,
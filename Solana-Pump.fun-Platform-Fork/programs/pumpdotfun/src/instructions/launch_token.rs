@@ -0,0 +1,275 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::ID as TOKEN_2022_PROGRAM_ID,
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface},
+};
+use crate::{
+    consts::INITIAL_LAMPORTS_FOR_POOL,
+    curve::CurveKind,
+    errors::CustomError,
+    events::{
+        LiquidityAdded, PoolCreated, PoolGraduated, PoolStatusChanged, Reserves, TokenCreated, Trade, TradeSide,
+        TradingOpened,
+    },
+    state::*,
+    utils::{initialize_token_metadata, revoke_mint_authorities},
+};
+
+// Buy made by the creator as part of the launch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DevBuy {
+    pub amount: u64,
+    pub min_amount_out: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LaunchTokenParams {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub total_supply: u64,
+    pub curve: CurveKind,
+    pub dev_buy: Option<DevBuy>,
+    pub launch_protection: Option<LaunchProtection>,
+    pub launch_fee: Option<LaunchFee>,
+}
+
+pub fn launch_token(ctx: Context<LaunchToken>, params: LaunchTokenParams) -> Result<()> {
+    ctx.accounts.dex_configuration_account.paused.check(PauseScope::CreatePool)?;
+    ctx.accounts.dex_configuration_account.check_curve(&params.curve)?;
+
+    if params.total_supply == 0 {
+        return err!(CustomError::InvalidAmount);
+    }
+
+    initialize_token_metadata(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        params.name.clone(),
+        params.symbol.clone(),
+        params.uri.clone(),
+    )?;
+
+    // The whole supply goes straight to the pool, the creator never holds it
+    mint_to(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.pool_token_account.to_account_info(),
+                authority: ctx.accounts.creator.to_account_info(),
+            },
+        ),
+        params.total_supply,
+    )?;
+
+    // Launched tokens always have a fixed supply and cannot be frozen
+    revoke_mint_authorities(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.creator.to_account_info(),
+    )?;
+
+    emit_cpi!(TokenCreated {
+        mint: ctx.accounts.token_mint.key(),
+        creator: ctx.accounts.creator.key(),
+        name: params.name,
+        symbol: params.symbol,
+        uri: params.uri,
+        total_supply: params.total_supply,
+    });
+
+    let pool = &mut ctx.accounts.pool;
+    pool.set_inner(LiquidityPool::new(
+        ctx.accounts.creator.key(),
+        ctx.accounts.token_mint.key(),
+        ctx.bumps.pool,
+        params.curve,
+    ));
+    pool.set_launch_protection(params.launch_protection)?;
+    pool.set_launch_fee(params.launch_fee, ctx.accounts.dex_configuration_account.max_launch_fee)?;
+
+    emit_cpi!(PoolCreated {
+        pool: pool.key(),
+        mint: pool.token,
+        creator: pool.creator,
+        curve: params.curve,
+    });
+
+    // Seed the SOL vault, as `add_liquidity` does for a pool created on its own
+    let from = pool.status;
+    pool.transition(PoolTransition::Fund)?;
+    pool.fund_sol_vault_rent(&ctx.accounts.creator, &ctx.accounts.pool_sol_vault, &ctx.accounts.system_program)?;
+    pool.transfer_sol_to_pool(
+        &ctx.accounts.creator,
+        &ctx.accounts.pool_sol_vault,
+        INITIAL_LAMPORTS_FOR_POOL,
+        &ctx.accounts.system_program,
+    )?;
+    pool.total_supply = params.total_supply;
+    pool.update_reserves(params.total_supply, INITIAL_LAMPORTS_FOR_POOL)?;
+
+    emit_cpi!(LiquidityAdded {
+        pool: pool.key(),
+        provider: ctx.accounts.creator.key(),
+        // Trading opens below, so this liquidity can never be withdrawn and mints no shares
+        shares: 0,
+        reserves_after: Reserves { sol: pool.reserve_sol, token: pool.reserve_token },
+    });
+    emit_cpi!(PoolStatusChanged { pool: pool.key(), from, to: pool.status });
+
+    // The launch liquidity mints no shares, so there is no seed to lock
+    let clock = Clock::get()?;
+    let from = pool.status;
+    pool.open_trading(clock.slot, clock.unix_timestamp)?;
+    emit_cpi!(PoolStatusChanged { pool: pool.key(), from, to: pool.status });
+    emit_cpi!(TradingOpened { pool: pool.key(), unlock_at: clock.unix_timestamp });
+
+    // Made in the launch transaction itself, so no sniper can get ahead of it and the
+    // launch protection does not apply. It does pay the launch fee.
+    if let Some(dev_buy) = params.dev_buy {
+        // Pick up the supply minted above
+        ctx.accounts.pool_token_account.reload()?;
+        let from = pool.status;
+
+        let accounts = PoolAccounts {
+            token_mint: &ctx.accounts.token_mint,
+            pool_token_account: &mut ctx.accounts.pool_token_account,
+            user_token_account: &mut ctx.accounts.creator_token_account,
+            pool_sol_vault: &ctx.accounts.pool_sol_vault,
+            pool_sol_vault_bump: ctx.bumps.pool_sol_vault,
+            user: &ctx.accounts.creator,
+            token_program: &ctx.accounts.token_program,
+            system_program: &ctx.accounts.system_program,
+        };
+
+        let trade = pool.buy(
+            &ctx.accounts.dex_configuration_account,
+            FeeAccounts {
+                protocol_fee_vault: &mut ctx.accounts.protocol_fee_vault,
+                creator_fee_vault: &mut ctx.accounts.creator_fee_vault,
+                referrer: None,
+            },
+            accounts,
+            dev_buy.amount,
+        )?;
+
+        if trade.token_amount < dev_buy.min_amount_out {
+            return err!(CustomError::SlippageExceeded);
+        }
+
+        let pool = &ctx.accounts.pool;
+        emit_cpi!(Trade {
+            pool: pool.key(),
+            user: ctx.accounts.creator.key(),
+            side: TradeSide::Buy,
+            sol: trade.sol_amount,
+            tokens: trade.token_amount,
+            fee: trade.fee,
+            creator_fee: trade.creator_fee,
+            referral_fee: trade.referral_fee,
+            referrer: None,
+            reserves_after: Reserves { sol: pool.reserve_sol, token: pool.reserve_token },
+            price: trade.price,
+        });
+        // A dev buy large enough graduates the pool straight away
+        if pool.status != from {
+            emit_cpi!(PoolStatusChanged { pool: pool.key(), from, to: pool.status });
+            emit_cpi!(PoolGraduated {
+                pool: pool.key(),
+                reserves: Reserves { sol: pool.reserve_sol, token: pool.reserve_token },
+            });
+        }
+    }
+
+    ctx.accounts
+        .pool
+        .check_invariants(&mut ctx.accounts.pool_token_account, &ctx.accounts.pool_sol_vault)?;
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LaunchToken<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    #[account(
+        mut,
+        seeds = [ProtocolFeeVault::SEED.as_bytes()],
+        bump
+    )]
+    pub protocol_fee_vault: Box<Account<'info, ProtocolFeeVault>>,
+
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = 9,
+        mint::authority = creator.key(),
+        mint::freeze_authority = creator.key(),
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = creator.key(),
+        extensions::metadata_pointer::metadata_address = token_mint.key(),
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        space = LiquidityPool::ACCOUNT_SIZE,
+        payer = creator,
+        seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(
+        init,
+        space = CreatorFeeVault::ACCOUNT_SIZE,
+        payer = creator,
+        seeds = [CreatorFeeVault::SEED_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: Box<Account<'info, CreatorFeeVault>>,
+
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = token_mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [LiquidityPool::SOL_VAULT_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_sol_vault: AccountInfo<'info>,
+
+    // Receives the dev-buy, created even when there is none so the creator can trade later
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = TOKEN_2022_PROGRAM_ID)]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use crate::{
+    amm,
+    errors::CustomError,
+    events::{PoolMigrated, PoolStatusChanged},
+    state::{CurveConfiguration, LiquidityPool, PoolTransition},
+};
+
+pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+    if ctx.accounts.amm_program.key() != ctx.accounts.dex_configuration_account.amm_program {
+        return err!(CustomError::InvalidAmmProgram);
+    }
+
+    let from = ctx.accounts.pool.status;
+    ctx.accounts.pool.transition(PoolTransition::Migrate)?;
+
+    // Everything held by the vaults moves, including lamports sent to them outside of a
+    // trade, except the seed the providers have yet to withdraw and the vault's rent
+    let seed_sol = ctx.accounts.pool.seed_sol;
+    let kept_sol = seed_sol
+        .checked_add(LiquidityPool::sol_vault_rent()?)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    let sol_amount = ctx
+        .accounts
+        .pool_sol_vault
+        .lamports()
+        .checked_sub(kept_sol)
+        .ok_or(CustomError::NotEnoughSolInVault)?;
+    let token_amount = ctx.accounts.pool_token_account.amount;
+
+    debug_msg!("Migrating to AMM {}:", ctx.accounts.amm_program.key());
+    debug_msg!("  Reserve SOL: {}", sol_amount);
+    debug_msg!("  Reserve Token: {}", token_amount);
+
+    let mint = ctx.accounts.token_mint.key();
+    let pool_seeds: &[&[u8]] = &[
+        LiquidityPool::POOL_SEED_PREFIX.as_bytes(),
+        mint.as_ref(),
+        &[ctx.accounts.pool.bump],
+    ];
+    let vault_seeds: &[&[u8]] = &[
+        LiquidityPool::SOL_VAULT_PREFIX.as_bytes(),
+        mint.as_ref(),
+        &[ctx.bumps.pool_sol_vault],
+    ];
+
+    amm::initialize_pool(
+        &ctx.accounts.amm_program,
+        amm::InitializePool {
+            amm_pool: ctx.accounts.amm_pool.to_account_info(),
+            amm_token_account: ctx.accounts.amm_token_account.to_account_info(),
+            token_mint: ctx.accounts.token_mint.to_account_info(),
+            sol_source: ctx.accounts.pool_sol_vault.to_account_info(),
+            token_source: ctx.accounts.pool_token_account.to_account_info(),
+            token_source_authority: ctx.accounts.pool.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        sol_amount,
+        token_amount,
+        &[pool_seeds, vault_seeds],
+    )?;
+
+    // Do not trust the AMM to have taken what it was given
+    ctx.accounts.pool_token_account.reload()?;
+    if ctx.accounts.pool_sol_vault.lamports() != kept_sol || ctx.accounts.pool_token_account.amount != 0 {
+        return err!(CustomError::MigrationFailed);
+    }
+
+    // What is left is there for `remove_liquidity`
+    let pool = &mut ctx.accounts.pool;
+    pool.reserve_sol = seed_sol;
+    pool.reserve_token = 0;
+
+    emit_cpi!(PoolStatusChanged { pool: pool.key(), from, to: pool.status });
+    emit_cpi!(PoolMigrated {
+        pool: pool.key(),
+        amm_program: ctx.accounts.amm_program.key(),
+        sol: sol_amount,
+        tokens: token_amount,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    #[account(
+        mut,
+        seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [LiquidityPool::SOL_VAULT_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_sol_vault: AccountInfo<'info>,
+
+    /// CHECK: checked against the configured AMM
+    #[account(executable)]
+    pub amm_program: UncheckedAccount<'info>,
+
+    /// CHECK: created and validated by the AMM
+    #[account(mut)]
+    pub amm_pool: UncheckedAccount<'info>,
+
+    /// CHECK: created and validated by the AMM
+    #[account(mut)]
+    pub amm_token_account: UncheckedAccount<'info>,
+
+    // Anyone can migrate a graduated pool, paying the rent of the AMM accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::{errors::CustomError, state::*};
+
+// Leading fields that every layout of `CurveConfiguration` shares
+#[derive(AnchorDeserialize)]
+struct CurveConfigurationHeader {
+    _legacy_fees: u64,
+    admin: Pubkey,
+    legacy_shares: u64,
+}
+
+pub fn migrate_configuration(ctx: Context<MigrateCurveConfiguration>) -> Result<()> {
+    let config_info = ctx.accounts.dex_configuration_account.to_account_info();
+
+    // The account may still be in a layout `Account<CurveConfiguration>` cannot read,
+    // so check the admin against the raw header first
+    let header = {
+        let data = config_info.try_borrow_data()?;
+        if data.len() < CurveConfiguration::LEGACY_ACCOUNT_SIZE
+            || data[..8] != *CurveConfiguration::DISCRIMINATOR
+        {
+            return err!(ErrorCode::AccountDiscriminatorMismatch);
+        }
+        CurveConfigurationHeader::deserialize(&mut &data[8..])?
+    };
+
+    if header.admin != ctx.accounts.admin.key() {
+        return err!(CustomError::InvalidAdmin);
+    }
+
+    // Top up rent from the admin so the account stays rent-exempt at the current layout
+    // once the fees it still holds are moved out, then grow it to that layout
+    let account_size = config_info.data_len().max(CurveConfiguration::ACCOUNT_SIZE);
+    let minimum_balance = Rent::get()?
+        .minimum_balance(account_size)
+        .checked_add(header.legacy_shares)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    let top_up = minimum_balance.saturating_sub(config_info.lamports());
+
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: config_info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    if config_info.data_len() < CurveConfiguration::ACCOUNT_SIZE {
+        config_info.resize(CurveConfiguration::ACCOUNT_SIZE)?;
+    }
+
+    let mut data = config_info.try_borrow_mut_data()?;
+    let mut dex_config = CurveConfiguration::try_deserialize(&mut &data[..])?;
+    dex_config.migrate()?;
+
+    // Fees used to accrue in the configuration itself, they now belong in the vault
+    let legacy_shares = dex_config.legacy_shares;
+    **config_info.try_borrow_mut_lamports()? -= legacy_shares;
+    **ctx.accounts.protocol_fee_vault.to_account_info().try_borrow_mut_lamports()? += legacy_shares;
+    ctx.accounts.protocol_fee_vault.fees = ctx
+        .accounts
+        .protocol_fee_vault
+        .fees
+        .checked_add(legacy_shares)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    dex_config.legacy_shares = 0;
+
+    dex_config.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateCurveConfiguration<'info> {
+    /// CHECK: deserialized by hand since older layouts do not match `CurveConfiguration`
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub dex_configuration_account: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        space = ProtocolFeeVault::ACCOUNT_SIZE,
+        payer = admin,
+        seeds = [ProtocolFeeVault::SEED.as_bytes()],
+        bump,
+    )]
+    pub protocol_fee_vault: Box<Account<'info, ProtocolFeeVault>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
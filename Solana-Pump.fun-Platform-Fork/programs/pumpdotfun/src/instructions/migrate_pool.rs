@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::Mint;
+use crate::{errors::CustomError, state::*};
+
+// Brings a pool created before curves and lifecycles to the current layout, so the
+// tokens and SOL it holds can be traded again
+pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+    if ctx.accounts.admin.key() != ctx.accounts.dex_configuration_account.admin {
+        return err!(CustomError::InvalidAdmin);
+    }
+
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let legacy = {
+        let data = pool_info.try_borrow_data()?;
+        if data.len() != LiquidityPool::LEGACY_ACCOUNT_SIZE || data[..8] != *LiquidityPool::DISCRIMINATOR {
+            return err!(ErrorCode::AccountDiscriminatorMismatch);
+        }
+        LegacyLiquidityPool::deserialize(&mut &data[8..])?
+    };
+
+    let pool = LiquidityPool::from_legacy(legacy, ctx.accounts.token_mint.supply, Clock::get()?.slot)?;
+
+    // The admin pays the rent for the larger layout, and for the SOL vault, whose rent
+    // used to count towards the reserve
+    let vault_info = ctx.accounts.pool_sol_vault.to_account_info();
+    let vault_minimum = pool
+        .reserve_sol
+        .checked_add(LiquidityPool::sol_vault_rent()?)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    let pool_minimum = Rent::get()?.minimum_balance(LiquidityPool::ACCOUNT_SIZE);
+    let top_ups = [
+        (pool_info.clone(), pool_minimum.saturating_sub(pool_info.lamports())),
+        (vault_info.clone(), vault_minimum.saturating_sub(vault_info.lamports())),
+    ];
+    for (to, top_up) in top_ups {
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer { from: ctx.accounts.admin.to_account_info(), to },
+                ),
+                top_up,
+            )?;
+        }
+    }
+
+    pool_info.resize(LiquidityPool::ACCOUNT_SIZE)?;
+    let mut data = pool_info.try_borrow_mut_data()?;
+    pool.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    /// CHECK: deserialized by hand since the legacy layout does not match `LiquidityPool`
+    #[account(
+        mut,
+        seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK:
+    #[account(
+        mut,
+        seeds = [LiquidityPool::SOL_VAULT_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_sol_vault: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
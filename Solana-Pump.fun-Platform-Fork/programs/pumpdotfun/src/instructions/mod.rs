@@ -1,19 +1,51 @@
 pub mod add_liquidity;
 pub mod create_pool;
 pub mod initialize;
+pub mod migrate_configuration;
+pub mod migrate_pool;
 pub mod remove_liquidity;
 pub mod buy;
 pub mod sell;
 pub mod create_token;
 pub mod withdraw;
-// pub mod add_raydium_liquidity;
+pub mod update_configuration;
+pub mod propose_admin;
+pub mod accept_admin;
+pub mod migrate;
+pub mod open_trading;
+pub mod launch_token;
+pub mod sync_reserves;
+pub mod claim_creator_fees;
+pub mod register_referrer;
+pub mod claim_referral_fees;
+pub mod set_pause;
+pub mod set_pool_pause;
+pub mod set_launch_protection;
+pub mod start_presale;
+pub mod set_launch_fee;
 
 pub use add_liquidity::*;
 pub use create_pool::*;
 pub use initialize::*;
+pub use migrate_configuration::*;
+pub use migrate_pool::*;
 pub use remove_liquidity::*;
 pub use buy::*;
 pub use sell::*;
 pub use create_token::*;
 pub use withdraw::*;
-// pub mod add_raydium_liquidity::*;
\ No newline at end of file
+pub use update_configuration::*;
+pub use propose_admin::*;
+pub use accept_admin::*;
+pub use migrate::*;
+pub use open_trading::*;
+pub use launch_token::*;
+pub use sync_reserves::*;
+pub use claim_creator_fees::*;
+pub use register_referrer::*;
+pub use claim_referral_fees::*;
+pub use set_pause::*;
+pub use set_pool_pause::*;
+pub use set_launch_protection::*;
+pub use start_presale::*;
+pub use set_launch_fee::*;
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::CustomError,
+    events::{PoolStatusChanged, TradingOpened},
+    state::*,
+    utils::withheld_transfer_fees,
+};
+
+// `lock_period` is how long in seconds the providers' seed stays in the pool once
+// trading opens, at least the configured minimum
+pub fn open_trading(ctx: Context<OpenTrading>, lock_period: i64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    if pool.creator != ctx.accounts.creator.key() {
+        return err!(CustomError::NotCreator);
+    }
+
+    let withheld = withheld_transfer_fees(
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.pool_token_account.to_account_info(),
+    )?;
+    pool.check_whole_supply(ctx.accounts.token_mint.supply, withheld)?;
+
+    if lock_period < ctx.accounts.dex_configuration_account.liquidity_lock_period {
+        return err!(CustomError::InvalidLockPeriod);
+    }
+
+    let clock = Clock::get()?;
+    let unlock_at = clock
+        .unix_timestamp
+        .checked_add(lock_period)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    let from = pool.status;
+    pool.open_trading(clock.slot, unlock_at)?;
+
+    emit_cpi!(PoolStatusChanged { pool: pool.key(), from, to: pool.status });
+    emit_cpi!(TradingOpened { pool: pool.key(), unlock_at });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OpenTrading<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    #[account(
+        mut,
+        seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), pool.token.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(address = pool.token)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = token_mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub creator: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::{errors::CustomError, state::*};
+
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+
+    // Check if the signer is the admin
+    if ctx.accounts.admin.key() != dex_config.admin {
+        return err!(CustomError::InvalidAdmin);
+    }
+
+    // The current admin stays in charge until the new one accepts, so a wrong
+    // key can simply be replaced by proposing again
+    dex_config.pending_admin = new_admin;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub admin: Signer<'info>,
+}
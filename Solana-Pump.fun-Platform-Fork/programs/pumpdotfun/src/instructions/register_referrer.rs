@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+
+pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+    ctx.accounts.referrer.set_inner(Referrer {
+        authority: ctx.accounts.authority.key(),
+        fees: 0,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        init,
+        space = Referrer::ACCOUNT_SIZE,
+        payer = authority,
+        seeds = [Referrer::SEED_PREFIX.as_bytes(), authority.key().as_ref()],
+        bump
+    )]
+    pub referrer: Box<Account<'info, Referrer>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,35 +1,73 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use crate::{
+    events::{LiquidityRemoved, PoolStatusChanged, Reserves},
+    state::{
+        CurveConfiguration, LiquidityPool, LiquidityPoolAccount, LiquidityProvider, PauseScope, PoolAccounts,
+        PoolTransition,
+    },
 };
-use crate::{errors::CustomError, state::{LiquidityPool, LiquidityPoolAccount}};
 
-pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, bump: u8) -> Result<()> {
+pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, shares: u64) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
-    if pool.creator.key() != ctx.accounts.user.key() {
-        return Err(CustomError::NotCreator.into());
-    }
+    // Any provider may withdraw while the pool is funded, the last one out closes it.
+    // Later on only the seed comes back, see `LiquidityPool::quote_withdrawal`
+    pool.status.next(PoolTransition::RemoveLiquidity)?;
+    pool.check_not_paused(&ctx.accounts.dex_configuration_account, PauseScope::RemoveLiquidity)?;
 
-    let token_accounts = (
-        &mut *ctx.accounts.token_mint,
-        &mut *ctx.accounts.pool_token_account,
-        &mut *ctx.accounts.user_token_account,
-    );
+    let accounts = PoolAccounts {
+        token_mint: &ctx.accounts.token_mint,
+        pool_token_account: &mut ctx.accounts.pool_token_account,
+        user_token_account: &mut ctx.accounts.user_token_account,
+        pool_sol_vault: &ctx.accounts.pool_sol_vault,
+        pool_sol_vault_bump: ctx.bumps.pool_sol_vault,
+        user: &ctx.accounts.user,
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+    };
 
-    pool.remove_liquidity(
-        token_accounts,
-        &mut ctx.accounts.pool_sol_vault,
-        &ctx.accounts.user,
-        bump,
-        &ctx.accounts.token_program,
-        &ctx.accounts.system_program,
+    let (tokens, sol) = pool.remove_liquidity(
+        &mut ctx.accounts.liquidity_provider,
+        accounts,
+        shares,
     )?;
+
+    let from = pool.status;
+    if pool.total_shares == 0 {
+        pool.transition(PoolTransition::RemoveLiquidity)?;
+    }
+
+    emit_cpi!(LiquidityRemoved {
+        pool: pool.key(),
+        provider: ctx.accounts.user.key(),
+        shares,
+        sol,
+        tokens,
+        reserves_after: Reserves { sol: pool.reserve_sol, token: pool.reserve_token },
+    });
+    if pool.status != from {
+        emit_cpi!(PoolStatusChanged { pool: pool.key(), from, to: pool.status });
+    }
+
+    ctx.accounts
+        .pool
+        .check_invariants(&mut ctx.accounts.pool_token_account, &ctx.accounts.pool_sol_vault)?;
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct RemoveLiquidity<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
     #[account(
         mut,
        seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
@@ -37,22 +75,31 @@ pub struct RemoveLiquidity<'info> {
     )]
     pub pool: Box<Account<'info, LiquidityPool>>,
 
+    #[account(
+        mut,
+        seeds = [LiquidityProvider::SEED_PREFIX.as_bytes(), pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub liquidity_provider: Box<Account<'info, LiquidityProvider>>,
+
     #[account(mut)]
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = pool
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
     )]
-    pub pool_token_account: Box<Account<'info, TokenAccount>>,
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = token_mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK:
     #[account(
@@ -66,6 +113,6 @@ pub struct RemoveLiquidity<'info> {
     pub user: Signer<'info>,
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
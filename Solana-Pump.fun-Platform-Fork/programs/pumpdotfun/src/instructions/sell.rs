@@ -1,41 +1,84 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use crate::{
+    errors::CustomError,
+    events::{Reserves, Trade, TradeSide},
+    state::{
+        CreatorFeeVault, CurveConfiguration, FeeAccounts, LiquidityPool, LiquidityPoolAccount, PoolAccounts,
+        ProtocolFeeVault, Referrer,
+    },
 };
-use crate::state::{CurveConfiguration, LiquidityPool, LiquidityPoolAccount};
 
-pub fn sell(ctx: Context<Sell>, amount: u64) -> Result<()> {
+pub fn sell(ctx: Context<Sell>, amount: u64, min_amount_out: u64) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
 
-    let token_one_accounts = (
-        &mut *ctx.accounts.token_mint,
-        &mut *ctx.accounts.pool_token_account,
-        &mut *ctx.accounts.user_token_account,
-    );
+    let accounts = PoolAccounts {
+        token_mint: &ctx.accounts.token_mint,
+        pool_token_account: &mut ctx.accounts.pool_token_account,
+        user_token_account: &mut ctx.accounts.user_token_account,
+        pool_sol_vault: &ctx.accounts.pool_sol_vault,
+        pool_sol_vault_bump: ctx.bumps.pool_sol_vault,
+        user: &ctx.accounts.user,
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+    };
 
-    pool.sell(
-        &mut ctx.accounts.dex_configuration_account,
-        token_one_accounts,
-        &mut ctx.accounts.pool_sol_vault,
+    let trade = pool.sell(
+        &ctx.accounts.dex_configuration_account,
+        FeeAccounts {
+            protocol_fee_vault: &mut ctx.accounts.protocol_fee_vault,
+            creator_fee_vault: &mut ctx.accounts.creator_fee_vault,
+            referrer: ctx.accounts.referrer.as_deref_mut(),
+        },
+        accounts,
         amount,
-        ctx.bumps.pool_sol_vault,
-        &ctx.accounts.user,
-        &ctx.accounts.token_program,
-        &ctx.accounts.system_program,
     )?;
+
+    if trade.sol_amount < min_amount_out {
+        return err!(CustomError::SlippageExceeded);
+    }
+
+    let pool = &ctx.accounts.pool;
+    emit_cpi!(Trade {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        side: TradeSide::Sell,
+        sol: trade.sol_amount,
+        tokens: trade.token_amount,
+        fee: trade.fee,
+        creator_fee: trade.creator_fee,
+        referral_fee: trade.referral_fee,
+        referrer: ctx.accounts.referrer.as_ref().map(|referrer| referrer.key()),
+        reserves_after: Reserves { sol: pool.reserve_sol, token: pool.reserve_token },
+        price: trade.price,
+    });
+
+    ctx.accounts
+        .pool
+        .check_invariants(&mut ctx.accounts.pool_token_account, &ctx.accounts.pool_sol_vault)?;
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Sell<'info> {
     #[account(
-        mut,
         seeds = [CurveConfiguration::SEED.as_bytes()],
         bump,
     )]
     pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
 
+    #[account(
+        mut,
+        seeds = [ProtocolFeeVault::SEED.as_bytes()],
+        bump
+    )]
+    pub protocol_fee_vault: Box<Account<'info, ProtocolFeeVault>>,
+
     #[account(
         mut,
         seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
@@ -43,15 +86,30 @@ pub struct Sell<'info> {
     )]
     pub pool: Box<Account<'info, LiquidityPool>>,
 
+    #[account(
+        mut,
+        seeds = [CreatorFeeVault::SEED_PREFIX.as_bytes(), pool.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: Box<Account<'info, CreatorFeeVault>>,
+
+    // Partner app the trade was routed through, paid part of the fee
+    #[account(
+        mut,
+        constraint = referrer.authority != user.key() @ CustomError::SelfReferral
+    )]
+    pub referrer: Option<Box<Account<'info, Referrer>>>,
+
     #[account(mut)]
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = pool
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
     )]
-    pub pool_token_account: Box<Account<'info, TokenAccount>>,
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK:
     #[account(
@@ -65,13 +123,14 @@ pub struct Sell<'info> {
         mut,
         associated_token::mint = token_mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub user: Signer<'info>,
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
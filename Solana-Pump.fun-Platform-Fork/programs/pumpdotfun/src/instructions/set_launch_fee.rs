@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::{errors::CustomError, events::LaunchFeeUpdated, state::*};
+
+pub fn set_launch_fee(ctx: Context<SetLaunchFee>, launch_fee: Option<LaunchFee>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    if pool.creator != ctx.accounts.creator.key() {
+        return err!(CustomError::NotCreator);
+    }
+
+    // Presale buyers and traders rely on the schedule they saw when buying in
+    if !matches!(pool.status, PoolStatus::Created | PoolStatus::Funded) {
+        return err!(CustomError::InvalidLaunchFee);
+    }
+
+    pool.set_launch_fee(launch_fee, ctx.accounts.dex_configuration_account.max_launch_fee)?;
+
+    emit_cpi!(LaunchFeeUpdated { pool: pool.key(), launch_fee });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetLaunchFee<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    #[account(
+        mut,
+        seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), pool.token.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub creator: Signer<'info>,
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::{errors::CustomError, events::LaunchProtectionUpdated, state::*};
+
+pub fn set_launch_protection(
+    ctx: Context<SetLaunchProtection>,
+    launch_protection: Option<LaunchProtection>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    if pool.creator != ctx.accounts.creator.key() {
+        return err!(CustomError::NotCreator);
+    }
+
+    // Buyers rely on the rules they saw when trading opened
+    if !matches!(pool.status, PoolStatus::Created | PoolStatus::Funded) {
+        return err!(CustomError::InvalidLaunchProtection);
+    }
+
+    pool.set_launch_protection(launch_protection)?;
+
+    emit_cpi!(LaunchProtectionUpdated { pool: pool.key(), launch_protection });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetLaunchProtection<'info> {
+    #[account(
+        mut,
+        seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), pool.token.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub creator: Signer<'info>,
+}
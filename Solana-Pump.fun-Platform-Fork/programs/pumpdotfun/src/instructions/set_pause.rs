@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::{events::PauseUpdated, state::*};
+
+pub fn set_pause(ctx: Context<SetPause>, paused: PauseFlags) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+    dex_config.check_pause_authority(ctx.accounts.authority.key(), dex_config.paused, paused)?;
+
+    dex_config.paused = paused;
+
+    emit_cpi!(PauseUpdated {
+        pool: None,
+        authority: ctx.accounts.authority.key(),
+        paused,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    // The admin, or the guardian when raising flags
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::{events::PauseUpdated, state::*};
+
+pub fn set_pool_pause(ctx: Context<SetPoolPause>, paused: PoolPauseFlags) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    ctx.accounts.dex_configuration_account.check_pause_authority(
+        ctx.accounts.authority.key(),
+        pool.paused.into(),
+        paused.into(),
+    )?;
+
+    pool.paused = paused;
+
+    emit_cpi!(PauseUpdated {
+        pool: Some(pool.key()),
+        authority: ctx.accounts.authority.key(),
+        paused: paused.into(),
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPoolPause<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    #[account(
+        mut,
+        seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), pool.token.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    // The admin, or the guardian when raising flags
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::CustomError,
+    events::{PoolStatusChanged, PresaleStarted},
+    state::*,
+    utils::withheld_transfer_fees,
+};
+
+pub fn start_presale(ctx: Context<StartPresale>, presale: PresaleAccess) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    if pool.creator != ctx.accounts.creator.key() {
+        return err!(CustomError::NotCreator);
+    }
+
+    let withheld = withheld_transfer_fees(
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.pool_token_account.to_account_info(),
+    )?;
+    pool.check_whole_supply(ctx.accounts.token_mint.supply, withheld)?;
+
+    presale.check()?;
+    let from = pool.status;
+    pool.transition(PoolTransition::StartPresale)?;
+    pool.presale = Some(presale);
+
+    emit_cpi!(PoolStatusChanged { pool: pool.key(), from, to: pool.status });
+    emit_cpi!(PresaleStarted { pool: pool.key(), presale });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct StartPresale<'info> {
+    #[account(
+        mut,
+        seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), pool.token.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(address = pool.token)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = token_mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub creator: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
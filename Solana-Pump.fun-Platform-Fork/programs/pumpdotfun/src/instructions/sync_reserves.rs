@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{
+    errors::CustomError,
+    events::{Reserves, ReservesSynced},
+    state::*,
+};
+
+// Resets the reserves to the vault balances, for recovering a pool whose accounting
+// drifted or absorbing tokens and lamports sent straight to its vaults
+pub fn sync_reserves(ctx: Context<SyncReserves>) -> Result<()> {
+    // Check if the signer is the admin
+    if ctx.accounts.admin.key() != ctx.accounts.dex_configuration_account.admin {
+        return err!(CustomError::InvalidAdmin);
+    }
+
+    let pool = &mut ctx.accounts.pool;
+    if pool.status == PoolStatus::Closed {
+        return err!(CustomError::PoolClosed);
+    }
+
+    let reserves_before = Reserves { sol: pool.reserve_sol, token: pool.reserve_token };
+
+    let sol_available = ctx
+        .accounts
+        .pool_sol_vault
+        .lamports()
+        .saturating_sub(LiquidityPool::sol_vault_rent()?);
+    pool.update_reserves(ctx.accounts.pool_token_account.amount, sol_available)?;
+    pool.total_supply = pool
+        .reserve_token
+        .checked_add(pool.tokens_sold)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+    emit_cpi!(ReservesSynced {
+        pool: pool.key(),
+        reserves_before,
+        reserves_after: Reserves { sol: pool.reserve_sol, token: pool.reserve_token },
+    });
+
+    ctx.accounts
+        .pool
+        .check_invariants(&mut ctx.accounts.pool_token_account, &ctx.accounts.pool_sol_vault)?;
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SyncReserves<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    #[account(
+        mut,
+        seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK:
+    #[account(
+        seeds = [LiquidityPool::SOL_VAULT_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_sol_vault: AccountInfo<'info>,
+
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use crate::{
+    consts::{FEE_DENOMINATOR, INITIAL_LAMPORTS_FOR_POOL},
+    errors::CustomError,
+    state::*,
+};
+
+// Settings left as `None` are kept as they are
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ConfigurationParams {
+    pub fees: Option<u16>,
+    pub min_virtual_sol: Option<u64>,
+    pub max_virtual_sol: Option<u64>,
+    pub min_virtual_token: Option<u64>,
+    pub max_virtual_token: Option<u64>,
+    pub graduation_threshold: Option<u64>,
+    pub amm_program: Option<Pubkey>,
+    pub allow_mint_authorities: Option<bool>,
+    pub creator_fees: Option<u16>,
+    pub referral_fees: Option<u16>,
+    pub guardian: Option<Pubkey>, // `Pubkey::default()` removes the guardian
+    pub liquidity_lock_period: Option<i64>,
+    pub min_initial_price: Option<u64>,
+    pub max_initial_price: Option<u64>,
+    pub max_price_increment: Option<u64>,
+    pub min_increment_supply: Option<u64>,
+    pub max_growth_bps: Option<u16>,
+    pub min_step_supply: Option<u64>,
+    pub max_launch_fee: Option<u16>,
+}
+
+pub fn update_configuration(
+    ctx: Context<UpdateCurveConfiguration>,
+    params: ConfigurationParams,
+) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+
+    // Check if the signer is the admin
+    if ctx.accounts.admin.key() != dex_config.admin {
+        return err!(CustomError::InvalidAdmin);
+    }
+
+    if let Some(fees) = params.fees {
+        if fees as u64 > FEE_DENOMINATOR {
+            return err!(CustomError::InvalidFee);
+        }
+        dex_config.fees = fees;
+    }
+
+    if let Some(min_virtual_sol) = params.min_virtual_sol {
+        dex_config.min_virtual_sol = min_virtual_sol;
+    }
+    if let Some(max_virtual_sol) = params.max_virtual_sol {
+        dex_config.max_virtual_sol = max_virtual_sol;
+    }
+    if let Some(min_virtual_token) = params.min_virtual_token {
+        dex_config.min_virtual_token = min_virtual_token;
+    }
+    if let Some(max_virtual_token) = params.max_virtual_token {
+        dex_config.max_virtual_token = max_virtual_token;
+    }
+
+    // A zero virtual reserve would let the first trade price against an empty curve
+    if dex_config.min_virtual_sol == 0
+        || dex_config.min_virtual_token == 0
+        || dex_config.min_virtual_sol > dex_config.max_virtual_sol
+        || dex_config.min_virtual_token > dex_config.max_virtual_token
+    {
+        return err!(CustomError::InvalidVirtualReserves);
+    }
+
+    if let Some(graduation_threshold) = params.graduation_threshold {
+        // A pool starts with the initial lamports in reserve and would graduate on its first buy
+        if graduation_threshold <= INITIAL_LAMPORTS_FOR_POOL {
+            return err!(CustomError::InvalidGraduationThreshold);
+        }
+        dex_config.graduation_threshold = graduation_threshold;
+    }
+
+    if let Some(amm_program) = params.amm_program {
+        dex_config.amm_program = amm_program;
+    }
+
+    if let Some(allow_mint_authorities) = params.allow_mint_authorities {
+        dex_config.allow_mint_authorities = allow_mint_authorities;
+    }
+
+    if let Some(creator_fees) = params.creator_fees {
+        dex_config.creator_fees = creator_fees;
+    }
+    if let Some(referral_fees) = params.referral_fees {
+        dex_config.referral_fees = referral_fees;
+    }
+    dex_config.check_fee_split()?;
+
+    if let Some(guardian) = params.guardian {
+        dex_config.guardian = guardian;
+    }
+
+    if let Some(liquidity_lock_period) = params.liquidity_lock_period {
+        if liquidity_lock_period < 0 {
+            return err!(CustomError::InvalidLockPeriod);
+        }
+        dex_config.liquidity_lock_period = liquidity_lock_period;
+    }
+
+    if let Some(min_initial_price) = params.min_initial_price {
+        dex_config.min_initial_price = min_initial_price;
+    }
+    if let Some(max_initial_price) = params.max_initial_price {
+        dex_config.max_initial_price = max_initial_price;
+    }
+    if let Some(max_price_increment) = params.max_price_increment {
+        dex_config.max_price_increment = max_price_increment;
+    }
+    if let Some(min_increment_supply) = params.min_increment_supply {
+        dex_config.min_increment_supply = min_increment_supply;
+    }
+    if let Some(max_growth_bps) = params.max_growth_bps {
+        dex_config.max_growth_bps = max_growth_bps;
+    }
+    if let Some(min_step_supply) = params.min_step_supply {
+        dex_config.min_step_supply = min_step_supply;
+    }
+
+    if dex_config.min_initial_price > dex_config.max_initial_price {
+        return err!(CustomError::CurveOutOfBounds);
+    }
+
+    if let Some(max_launch_fee) = params.max_launch_fee {
+        // A launch fee must stay below 100%, see `LiquidityPool::set_launch_fee`
+        if max_launch_fee as u64 >= FEE_DENOMINATOR {
+            return err!(CustomError::InvalidLaunchFee);
+        }
+        dex_config.max_launch_fee = max_launch_fee;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateCurveConfiguration<'info> {
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub admin: Signer<'info>,
+}
@@ -1,43 +1,60 @@
 use anchor_lang::prelude::*;
-use crate::{errors::CustomError, state::*};
+use crate::{errors::CustomError, events::FeesWithdrawn, state::*};
 
-pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
 
     // Check if the signer is the admin
     if ctx.accounts.admin.key() != ctx.accounts.dex_configuration_account.admin {
         return err!(CustomError::InvalidAdmin);
     }
 
-    // Ensure that the dex_configuration_account contains enough lamports
-    let amount_to_transfer = ctx.accounts.dex_configuration_account.shares;
+    if amount == 0 {
+        return err!(CustomError::InvalidAmount);
+    }
+
+    // Only accrued fees can be withdrawn, never the rent keeping the vault alive
+    let vault_info = ctx.accounts.protocol_fee_vault.to_account_info();
+    let rent_exempt_balance = Rent::get()?.minimum_balance(vault_info.data_len());
 
-    if ctx.accounts.dex_configuration_account.to_account_info().lamports() < amount_to_transfer {
+    if amount > ctx.accounts.protocol_fee_vault.fees
+        || vault_info.lamports().saturating_sub(amount) < rent_exempt_balance
+    {
         return err!(CustomError::InsufficientFunds);
     }
 
-    // Transfer lamports from the dex_configuration_account to the admin
-
-    // Decrease lamports in the escrow account
-    **ctx.accounts.dex_configuration_account.to_account_info().try_borrow_mut_lamports()? -= amount_to_transfer;
+    **vault_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.recipient.try_borrow_mut_lamports()? += amount;
 
-    // Increase lamports in the authority account
-    **ctx.accounts.admin.try_borrow_mut_lamports()? += amount_to_transfer;
+    ctx.accounts.protocol_fee_vault.fees -= amount;
 
-    // After transfer, set the shares to 0
-    ctx.accounts.dex_configuration_account.shares = 0;
+    emit_cpi!(FeesWithdrawn {
+        admin: ctx.accounts.admin.key(),
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+    });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
     #[account(
-        mut,
         seeds = [CurveConfiguration::SEED.as_bytes()],
         bump,
     )]
     pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
 
+    #[account(
+        mut,
+        seeds = [ProtocolFeeVault::SEED.as_bytes()],
+        bump,
+    )]
+    pub protocol_fee_vault: Box<Account<'info, ProtocolFeeVault>>,
+
+    /// CHECK: only receives lamports
     #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
     pub admin: Signer<'info>,
 }
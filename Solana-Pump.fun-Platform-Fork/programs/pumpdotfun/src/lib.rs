@@ -1,11 +1,25 @@
 use anchor_lang::prelude::*;
 
+// `msg!` for debugging output, only logged when built with the `debug-logs` feature
+macro_rules! debug_msg {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "debug-logs") {
+            msg!($($arg)*);
+        }
+    };
+}
+
 pub mod errors;
 pub mod utils;
 pub mod instructions;
 pub mod state;
 pub mod consts;
+pub mod curve;
+pub mod amm;
+pub mod events;
 
+use crate::curve::CurveKind;
+use crate::state::{LaunchFee, LaunchProtection, PauseFlags, PoolPauseFlags, PresaleAccess, PresaleProof};
 use crate::instructions::*;
 
 declare_id!("YoBKRApxG4TVThpMaBVcg8ewoMrmHHrrotiFBVX6snW");
@@ -14,43 +28,126 @@ declare_id!("YoBKRApxG4TVThpMaBVcg8ewoMrmHHrrotiFBVX6snW");
 pub mod pumpdotfun {
     use super::*;
 
-    pub fn initialize(ctx: Context<InitializeCurveConfiguration>, fee: f64) -> Result<()> {
+    pub fn initialize(ctx: Context<InitializeCurveConfiguration>, fee: u16) -> Result<()> {
         instructions::initialize(ctx, fee)
     }
 
-    pub fn create_token(ctx: Context<CreateToken>, name: String, symbol: String, uri: String, total_supply: u64) -> Result<()> {
-        instructions::create_token(ctx, name, symbol, uri, total_supply)
+    pub fn migrate_configuration(ctx: Context<MigrateCurveConfiguration>) -> Result<()> {
+        instructions::migrate_configuration(ctx)
+    }
+
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        instructions::migrate_pool(ctx)
+    }
+
+    pub fn create_token(ctx: Context<CreateToken>, name: String, symbol: String, uri: String, total_supply: u64, revoke_authorities: bool) -> Result<()> {
+        instructions::create_token(ctx, name, symbol, uri, total_supply, revoke_authorities)
+    }
+
+    pub fn launch_token(ctx: Context<LaunchToken>, params: LaunchTokenParams) -> Result<()> {
+        instructions::launch_token(ctx, params)
     }
 
-    pub fn create_pool(ctx: Context<CreateLiquidityPool>) -> Result<()> {
-        instructions::create_pool(ctx)
+    pub fn create_pool(ctx: Context<CreateLiquidityPool>, curve: CurveKind) -> Result<()> {
+        instructions::create_pool(ctx, curve)
     }
 
     pub fn add_liquidity(
         ctx: Context<AddLiquidity>,
+        token_amount: u64,
+        max_sol_amount: u64,
+    ) -> Result<()> {
+        instructions::add_liquidity(ctx, token_amount, max_sol_amount)
+    }
+
+    pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, shares: u64) -> Result<()> {
+        instructions::remove_liquidity(ctx, shares)
+    }
+
+    pub fn buy(
+        ctx: Context<Buy>,
+        amount: u64,
+        min_amount_out: u64,
+        presale_proof: Option<PresaleProof>,
+    ) -> Result<()> {
+        instructions::buy(ctx, amount, min_amount_out, presale_proof)
+    }
+
+    pub fn buy_exact_out(
+        ctx: Context<Buy>,
+        token_amount: u64,
+        max_sol_cost: u64,
+        presale_proof: Option<PresaleProof>,
+    ) -> Result<()> {
+        instructions::buy_exact_out(ctx, token_amount, max_sol_cost, presale_proof)
+    }
+
+    pub fn sell(ctx: Context<Sell>, amount: u64, min_amount_out: u64) -> Result<()> {
+        instructions::sell(ctx, amount, min_amount_out)
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        instructions::withdraw(ctx, amount)
+    }
+
+    pub fn update_configuration(ctx: Context<UpdateCurveConfiguration>, params: ConfigurationParams) -> Result<()> {
+        instructions::update_configuration(ctx, params)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    pub fn set_launch_protection(
+        ctx: Context<SetLaunchProtection>,
+        launch_protection: Option<LaunchProtection>,
     ) -> Result<()> {
-        instructions::add_liquidity(ctx)
+        instructions::set_launch_protection(ctx, launch_protection)
+    }
+
+    pub fn set_launch_fee(ctx: Context<SetLaunchFee>, launch_fee: Option<LaunchFee>) -> Result<()> {
+        instructions::set_launch_fee(ctx, launch_fee)
+    }
+
+    pub fn start_presale(ctx: Context<StartPresale>, presale: PresaleAccess) -> Result<()> {
+        instructions::start_presale(ctx, presale)
+    }
+
+    pub fn open_trading(ctx: Context<OpenTrading>, lock_period: i64) -> Result<()> {
+        instructions::open_trading(ctx, lock_period)
     }
 
-    pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, bump: u8) -> Result<()> {
-        instructions::remove_liquidity(ctx, bump)
+    pub fn set_pause(ctx: Context<SetPause>, paused: PauseFlags) -> Result<()> {
+        instructions::set_pause(ctx, paused)
     }
 
-    pub fn buy(ctx: Context<Buy>, amount: u64) -> Result<()> {
-        instructions::buy(ctx, amount)
+    pub fn set_pool_pause(ctx: Context<SetPoolPause>, paused: PoolPauseFlags) -> Result<()> {
+        instructions::set_pool_pause(ctx, paused)
     }
 
-    pub fn sell(ctx: Context<Sell>, amount: u64) -> Result<()> {
-        instructions::sell(ctx, amount)
+    pub fn sync_reserves(ctx: Context<SyncReserves>) -> Result<()> {
+        instructions::sync_reserves(ctx)
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
-        instructions::withdraw(ctx)
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        instructions::claim_creator_fees(ctx)
     }
 
-    // pub fn add_raydium_liquidity(ctx: Context<AddRaydiumLiquidity>, nonce: u8, open_time: u64) -> Result<()> {
-    //     instructions::add_raydium_liquidity(ctx, nonce, open_time)
-    // }
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        instructions::register_referrer(ctx)
+    }
+
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+        instructions::claim_referral_fees(ctx)
+    }
+
+    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+        instructions::migrate(ctx)
+    }
     
 }
 
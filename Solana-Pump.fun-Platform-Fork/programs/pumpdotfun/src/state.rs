@@ -1,50 +1,560 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+use crate::consts::FEE_DENOMINATOR;
+use crate::consts::GRADUATION_THRESHOLD;
 use crate::consts::INITIAL_LAMPORTS_FOR_POOL;
-use crate::consts::ONE_BILLION;
+use crate::consts::TOKEN_UNIT;
 use crate::consts::V_SOL_AMOUNT;
 use crate::consts::V_TOKEN_AMOUNT;
+use crate::curve::{self, BondingCurve, CurveKind};
 use crate::errors::CustomError;
+use crate::utils::{allowlist_leaf, amount_before_transfer_fee, token_group_of, verify_merkle_proof};
 
 #[account]
 pub struct CurveConfiguration {
-    pub fees: f64,
+    pub legacy_fees: u64, // Bits of the f64 fee used before version 1, zero once migrated
     pub admin: Pubkey,
-    pub shares: u64,
+    pub legacy_shares: u64, // Fees accrued in this account before the protocol fee vault, zero once moved out
+    pub version: u8,      // Layout version, see `CurveConfiguration::VERSION`
+    pub fees: u16,        // Trading fee in basis points
+    pub pending_admin: Pubkey, // Admin proposed by `propose_admin` waiting for `accept_admin`, unset if default
+    pub min_virtual_sol: u64,   // Bounds for the virtual SOL reserve chosen at `create_pool`
+    pub max_virtual_sol: u64,
+    pub min_virtual_token: u64, // Bounds for the virtual token reserve chosen at `create_pool`
+    pub max_virtual_token: u64,
+    pub graduation_threshold: u64, // SOL reserve at which a pool stops trading and can be migrated
+    pub amm_program: Pubkey,       // AMM the graduated pools are migrated to, unset until the admin picks one
+    pub allow_mint_authorities: bool, // Lets `create_pool` accept mints that can still be minted or frozen
+    pub creator_fees: u16,            // Part of the trading fee paid to the pool creator, in basis points of the fee
+    pub referral_fees: u16,           // Part of the trading fee paid to the trade's referrer, in basis points of the fee
+    pub guardian: Pubkey,             // Key that may pause alongside the admin but not unpause, unset if default
+    pub paused: PauseFlags,           // Actions halted on every pool
+    pub liquidity_lock_period: i64,   // Shortest time in seconds a pool may lock its providers' seed for
+    pub min_initial_price: u64,       // Bounds for the initial price of a linear or exponential curve,
+    pub max_initial_price: u64,       // both families are closed while the maximum is zero
+    pub max_price_increment: u64,     // Largest price increment of a linear curve
+    pub min_increment_supply: u64,    // Fewest tokens a linear curve may take to raise its price
+    pub max_growth_bps: u16,          // Largest growth per step of an exponential curve, in basis points
+    pub min_step_supply: u64,         // Fewest tokens an exponential curve may take to step its price
+    pub max_launch_fee: u16,          // Highest starting fee of a pool's launch fee, in basis points
 }
 
 impl CurveConfiguration {
     pub const SEED: &'static str = "CurveConfiguration";
+    pub const VERSION: u8 = 12;
+
+    // Discriminator (8) + legacy fees (8) + Pubkey (32) + legacy shares (8)
+    pub const LEGACY_ACCOUNT_SIZE: usize = 8 + 8 + 32 + 8;
+
+    // Legacy layout + version (1) + fees (2) + pending admin (32)
+    // + virtual reserve bounds (4 * 8) + graduation threshold (8) + AMM program (32)
+    // + allow mint authorities (1) + creator fees (2) + referral fees (2) + guardian (32) + pause flags
+    // + liquidity lock period (8) + initial price bounds (2 * 8) + linear bounds (2 * 8)
+    // + exponential bounds (2 + 8) + max launch fee (2)
+    pub const ACCOUNT_SIZE: usize = Self::LEGACY_ACCOUNT_SIZE + 1 + 2 + 32 + 32 + 8 + 32 + 1 + 2 + 2 + 32
+        + PauseFlags::SIZE
+        + 8
+        + 16
+        + 16
+        + 10
+        + 2;
+
+    pub fn new(fees: u16, admin: Pubkey) -> Self {
+        Self {
+            legacy_fees: 0,
+            admin,
+            legacy_shares: 0,
+            version: Self::VERSION,
+            fees,
+            pending_admin: Pubkey::default(),
+            min_virtual_sol: V_SOL_AMOUNT,
+            max_virtual_sol: V_SOL_AMOUNT,
+            min_virtual_token: V_TOKEN_AMOUNT,
+            max_virtual_token: V_TOKEN_AMOUNT,
+            graduation_threshold: GRADUATION_THRESHOLD,
+            amm_program: Pubkey::default(),
+            allow_mint_authorities: false,
+            creator_fees: 0,
+            referral_fees: 0,
+            guardian: Pubkey::default(),
+            paused: PauseFlags::default(),
+            liquidity_lock_period: 0,
+            min_initial_price: 0,
+            max_initial_price: 0,
+            max_price_increment: 0,
+            min_increment_supply: 0,
+            max_growth_bps: 0,
+            min_step_supply: 0,
+            max_launch_fee: 0,
+        }
+    }
+
+    // The admin may set any pause flags, the guardian may only raise more of them
+    pub fn check_pause_authority(&self, signer: Pubkey, current: PauseFlags, requested: PauseFlags) -> Result<()> {
+        if signer == self.admin {
+            return Ok(());
+        }
+        if self.guardian == Pubkey::default() || signer != self.guardian {
+            return err!(CustomError::InvalidAdmin);
+        }
+        if !requested.contains(current) {
+            return err!(CustomError::GuardianCannotUnpause);
+        }
+        Ok(())
+    }
+
+    // The creator and referral shares are both taken out of the trading fee, so together
+    // they may not exceed it
+    pub fn check_fee_split(&self) -> Result<()> {
+        if self.creator_fees as u64 + self.referral_fees as u64 > FEE_DENOMINATOR {
+            return err!(CustomError::InvalidFeeSplit);
+        }
+        Ok(())
+    }
+
+    // Shares a trading fee out between the creator, the referrer if the trade has one,
+    // and the protocol, which keeps the rest
+    pub fn split_fee(&self, fee: u64, referred: bool) -> Result<FeeSplit> {
+        let creator = curve::fee_on(fee, self.creator_fees)?;
+        let referral = if referred { curve::fee_on(fee, self.referral_fees)? } else { 0 };
+        let protocol = fee
+            .checked_sub(creator)
+            .and_then(|rest| rest.checked_sub(referral))
+            .ok_or(CustomError::InvalidFeeSplit)?;
+
+        Ok(FeeSplit { protocol, creator, referral })
+    }
+
+    // A pool graduates once its SOL reserve reaches the threshold, but only if there is
+    // an AMM to migrate it to, otherwise it would be stuck with trading halted
+    pub fn graduates_at(&self, reserve_sol: u64) -> bool {
+        self.amm_program != Pubkey::default() && reserve_sol >= self.graduation_threshold
+    }
+
+    pub fn check_curve(&self, curve: &CurveKind) -> Result<()> {
+        match *curve {
+            CurveKind::VirtualConstantProduct { virtual_sol, virtual_token } => {
+                if !(self.min_virtual_sol..=self.max_virtual_sol).contains(&virtual_sol)
+                    || !(self.min_virtual_token..=self.max_virtual_token).contains(&virtual_token)
+                {
+                    return err!(CustomError::InvalidVirtualReserves);
+                }
+            }
+            CurveKind::Linear { initial_price, price_increment, increment_supply } => {
+                if !(self.min_initial_price..=self.max_initial_price).contains(&initial_price)
+                    || price_increment > self.max_price_increment
+                    || increment_supply < self.min_increment_supply
+                {
+                    return err!(CustomError::CurveOutOfBounds);
+                }
+            }
+            CurveKind::Exponential { initial_price, growth_bps, step_supply } => {
+                if !(self.min_initial_price..=self.max_initial_price).contains(&initial_price)
+                    || growth_bps > self.max_growth_bps
+                    || step_supply < self.min_step_supply
+                {
+                    return err!(CustomError::CurveOutOfBounds);
+                }
+            }
+            CurveKind::ConstantProduct => {}
+        }
+        // Building the curve validates what the bounds leave open
+        curve.at(0, 0, 0)?;
+        Ok(())
+    }
 
-    // Discriminator (8) + f64 (8)
-    pub const ACCOUNT_SIZE: usize = 8 + 8 + 32 + 8;
+    // Brings an account read from an older layout up to `VERSION`
+    pub fn migrate(&mut self) -> Result<()> {
+        if self.version == 0 {
+            let legacy_fees = f64::from_bits(self.legacy_fees).round();
+            if !(0.0..=FEE_DENOMINATOR as f64).contains(&legacy_fees) {
+                return err!(CustomError::InvalidFee);
+            }
+
+            self.fees = legacy_fees as u16;
+            self.legacy_fees = 0;
+            self.version = 1;
+        }
+
+        if self.version < 3 {
+            // Only the curve every existing pool was created with, until the admin widens it
+            self.min_virtual_sol = V_SOL_AMOUNT;
+            self.max_virtual_sol = V_SOL_AMOUNT;
+            self.min_virtual_token = V_TOKEN_AMOUNT;
+            self.max_virtual_token = V_TOKEN_AMOUNT;
+        }
 
-    pub fn new(fees: f64, admin: Pubkey, shares: u64) -> Self {
-        Self { fees, admin, shares }
+        if self.version < 4 {
+            self.graduation_threshold = GRADUATION_THRESHOLD;
+        }
+
+        // Every field is fixed size, so the account always serializes to `ACCOUNT_SIZE`. Any
+        // other field added since sits in the bytes the resize zero-filled and reads as its default
+        self.version = Self::VERSION;
+
+        Ok(())
     }
 }
 
 #[account]
 pub struct LiquidityProvider {
-    pub shares: u64, // The number of shares this provider holds in the liquidity pool ( didnt add to contract now )
+    pub shares: u64, // Pool shares minted to this provider by `add_liquidity` and not yet burned
 }
 
 impl LiquidityProvider {
     pub const SEED_PREFIX: &'static str = "LiqudityProvider"; // Prefix for generating PDAs
 
-    // Discriminator (8) + f64 (8)
+    // Discriminator (8) + shares (8)
     pub const ACCOUNT_SIZE: usize = 8 + 8;
 }
 
+/// Actions halted on every pool by an emergency pause
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PauseFlags {
+    pub buy: bool,
+    pub sell: bool,
+    pub create_pool: bool,
+    pub remove_liquidity: bool,
+}
+
+/// Actions halted on one pool, on top of the global flags. This is the only way to pause
+/// a pool, its status is left alone.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolPauseFlags {
+    pub buy: bool,
+    pub sell: bool,
+    pub remove_liquidity: bool,
+}
+
+impl PoolPauseFlags {
+    pub const SIZE: usize = 3;
+}
+
+impl From<PoolPauseFlags> for PauseFlags {
+    fn from(paused: PoolPauseFlags) -> Self {
+        PauseFlags {
+            buy: paused.buy,
+            sell: paused.sell,
+            create_pool: false,
+            remove_liquidity: paused.remove_liquidity,
+        }
+    }
+}
+
+/// Action checked against the pause flags
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseScope {
+    Buy,
+    Sell,
+    CreatePool,
+    RemoveLiquidity,
+}
+
+impl PauseFlags {
+    pub const SIZE: usize = 4;
+
+    // Flags halted by either set
+    pub fn union(self, other: PauseFlags) -> PauseFlags {
+        PauseFlags {
+            buy: self.buy || other.buy,
+            sell: self.sell || other.sell,
+            create_pool: self.create_pool || other.create_pool,
+            remove_liquidity: self.remove_liquidity || other.remove_liquidity,
+        }
+    }
+
+    // Whether every flag raised in `other` is raised here too
+    pub fn contains(self, other: PauseFlags) -> bool {
+        self.union(other) == self
+    }
+
+    pub fn check(self, scope: PauseScope) -> Result<()> {
+        match scope {
+            PauseScope::Buy if self.buy => err!(CustomError::BuysPaused),
+            PauseScope::Sell if self.sell => err!(CustomError::SellsPaused),
+            PauseScope::CreatePool if self.create_pool => err!(CustomError::PoolCreationPaused),
+            PauseScope::RemoveLiquidity if self.remove_liquidity => err!(CustomError::LiquidityRemovalPaused),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Most a wallet may buy during a launch's protected window
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuyCap {
+    Tokens(u64), // Tokens received
+    Sol(u64),    // Lamports paid, fee excluded
+}
+
+/// Anti-sniper rules for the start of trading
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LaunchProtection {
+    pub start_time: i64, // Unix time before which buys fail
+    pub window: i64,     // Seconds after `start_time` during which each wallet's buys are capped
+    pub cap: BuyCap,
+}
+
+impl LaunchProtection {
+    // Start time (8) + window (8) + cap (1 + 8)
+    pub const SIZE: usize = 8 + 8 + 1 + 8;
+}
+
+/// Launch fee charged instead of the protocol's flat fee when trading opens, easing
+/// linearly down to it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LaunchFee {
+    pub start_fee: u16,   // Fee in basis points in the slot trading opens
+    pub decay_slots: u64, // Slots after which the flat fee applies again
+}
+
+impl LaunchFee {
+    // Start fee (2) + decay slots (8)
+    pub const SIZE: usize = 2 + 8;
+
+    // Fee in basis points `elapsed` slots after trading opened
+    pub fn fee_bps(&self, base_fee: u16, elapsed: u64) -> u16 {
+        if elapsed >= self.decay_slots || self.start_fee <= base_fee {
+            return base_fee;
+        }
+
+        // elapsed < decay_slots, so the decayed part is below start_fee - base_fee
+        let decayed = (self.start_fee - base_fee) as u128 * elapsed as u128 / self.decay_slots as u128;
+        self.start_fee - decayed as u16
+    }
+}
+
+#[account]
+pub struct WalletPurchases {
+    pub tokens: u64,         // Tokens bought by the wallet during the pool's protected window
+    pub sol: u64,            // Lamports it paid for them, fee excluded
+    pub presale_tokens: u64, // Tokens bought by the wallet during the presale with an allowlist proof
+}
+
+impl WalletPurchases {
+    pub const SEED_PREFIX: &'static str = "wallet_purchases";
+
+    // Discriminator (8) + tokens (8) + sol (8) + presale tokens (8)
+    pub const ACCOUNT_SIZE: usize = 8 + 8 + 8 + 8;
+}
+
+// What was bought in a pool's presale on the strength of one collection NFT, by whichever
+// wallets held it, so the NFT cannot be passed around to buy its allocation again
+#[account]
+pub struct NftPurchases {
+    pub presale_tokens: u64, // Tokens bought with this NFT during the presale
+}
+
+impl NftPurchases {
+    pub const SEED_PREFIX: &'static str = "nft_purchases";
+
+    // Discriminator (8) + presale tokens (8)
+    pub const ACCOUNT_SIZE: usize = 8 + 8;
+}
+
+/// Who may buy during a pool's presale
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PresaleAccess {
+    pub merkle_root: [u8; 32],      // Root of the `allowlist_leaf`s of allowed wallets, all zero if unused
+    pub collection: Pubkey,         // Token-2022 group whose members' holders may buy, unset if default
+    pub collection_allocation: u64, // Tokens each collection holder may buy
+}
+
+/// What a buyer shows to take part in a presale
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum PresaleProof {
+    // The wallet's allocation and the Merkle proof of its leaf
+    Allowlist { allocation: u64, proof: Vec<[u8; 32]> },
+    // The wallet holds a member of the collection, passed as the NFT accounts of `buy`
+    Collection,
+}
+
+impl PresaleAccess {
+    // Merkle root (32) + collection (32) + collection allocation (8)
+    pub const SIZE: usize = 32 + 32 + 8;
+
+    pub fn check(&self) -> Result<()> {
+        let allowlist = self.merkle_root != [0; 32];
+        let collection = self.collection != Pubkey::default();
+        if !(allowlist || collection) || (collection && self.collection_allocation == 0) {
+            return err!(CustomError::InvalidPresale);
+        }
+        Ok(())
+    }
+
+    // Tokens `wallet` may buy in the presale. A collection NFT counts for whichever wallet
+    // holds it at the time of the buy, its allocation is tracked per NFT by `NftPurchases`.
+    pub fn allocation_for(
+        &self,
+        wallet: &Pubkey,
+        proof: &PresaleProof,
+        nft: Option<(&InterfaceAccount<TokenAccount>, &AccountInfo)>,
+    ) -> Result<u64> {
+        let eligible = match proof {
+            PresaleProof::Allowlist { allocation, proof } => {
+                self.merkle_root != [0; 32]
+                    && verify_merkle_proof(&self.merkle_root, allowlist_leaf(wallet, *allocation), proof)
+            }
+            PresaleProof::Collection => match nft {
+                Some((token_account, mint)) => {
+                    self.collection != Pubkey::default()
+                        && token_account.owner == *wallet
+                        && token_account.mint == *mint.key
+                        && token_account.amount > 0
+                        && token_group_of(mint)? == Some(self.collection)
+                }
+                None => false,
+            },
+        };
+
+        if !eligible {
+            return err!(CustomError::NotOnPresaleAllowlist);
+        }
+
+        Ok(match proof {
+            PresaleProof::Allowlist { allocation, .. } => *allocation,
+            PresaleProof::Collection => self.collection_allocation,
+        })
+    }
+}
+
+/// Parts of a trading fee, adding up to the whole fee
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeSplit {
+    pub protocol: u64, // Accrues to the `ProtocolFeeVault`, withdrawn by the admin
+    pub creator: u64,  // Accrues to the pool's `CreatorFeeVault`
+    pub referral: u64, // Accrues to the trade's `Referrer`
+}
+
+#[account]
+pub struct ProtocolFeeVault {
+    pub fees: u64, // Lamports accrued to the protocol and not yet withdrawn, the vault's rent excluded
+}
+
+impl ProtocolFeeVault {
+    pub const SEED: &'static str = "protocol_fee_vault";
+
+    // Discriminator (8) + fees (8)
+    pub const ACCOUNT_SIZE: usize = 8 + 8;
+}
+
+#[account]
+pub struct CreatorFeeVault {
+    pub fees: u64, // Lamports accrued to the pool creator and not yet claimed
+}
+
+impl CreatorFeeVault {
+    pub const SEED_PREFIX: &'static str = "creator_fee_vault";
+
+    // Discriminator (8) + fees (8)
+    pub const ACCOUNT_SIZE: usize = 8 + 8;
+}
+
+#[account]
+pub struct Referrer {
+    pub authority: Pubkey, // Wallet that registered the referrer and claims its fees
+    pub fees: u64,         // Lamports accrued from referred trades and not yet claimed
+}
+
+impl Referrer {
+    pub const SEED_PREFIX: &'static str = "referrer";
+
+    // Discriminator (8) + Pubkey (32) + fees (8)
+    pub const ACCOUNT_SIZE: usize = 8 + 32 + 8;
+}
+
+/// Lifecycle of a pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PoolStatus {
+    /// Created, waiting for `add_liquidity`
+    #[default]
+    Created,
+    /// Seeded with liquidity, waiting for the creator to open trading
+    Funded,
+    /// Open for buys and sells on the bonding curve
+    Trading,
+    /// Reserve crossed the graduation threshold, waiting for `migrate`
+    Graduated,
+    /// Reserves migrated to the AMM or taken back by the creator, final
+    Closed,
+    /// Open for buys by presale wallets only, waiting for the creator to open trading
+    Presale,
+}
+
+/// Moves between pool statuses, each made by one instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolTransition {
+    Fund,            // add_liquidity
+    OpenTrading,     // open_trading
+    Graduate,        // the buy crossing the graduation threshold
+    Migrate,         // migrate
+    RemoveLiquidity, // remove_liquidity
+    StartPresale,    // start_presale
+}
+
+impl PoolStatus {
+    /// Status reached by `transition`, or the transition's own error if it is illegal from here
+    pub fn next(self, transition: PoolTransition) -> Result<PoolStatus> {
+        use PoolStatus::*;
+        use PoolTransition::*;
+
+        Ok(match (self, transition) {
+            (Created, Fund) => Funded,
+            // More providers may join until trading opens
+            (Funded, Fund) => Funded,
+            (Funded, OpenTrading) => Trading,
+            (Funded, StartPresale) => Presale,
+            (Presale, OpenTrading) => Trading,
+            (Presale, Graduate) => Graduated,
+            (Trading, Graduate) => Graduated,
+            (Graduated, Migrate) => Closed,
+            // Only the last provider out makes it, earlier ones leave the pool funded
+            (Funded, RemoveLiquidity) => Closed,
+            // Once trading opens providers only take back the SOL they seeded, which leaves
+            // the status alone. `quote_withdrawal` holds it until the pool's unlock time.
+            (Trading, RemoveLiquidity) => Trading,
+            (Graduated, RemoveLiquidity) => Graduated,
+            (Closed, RemoveLiquidity) => Closed,
+            (_, Fund) => return err!(CustomError::PoolAlreadyFunded),
+            (_, OpenTrading) => return err!(CustomError::PoolNotFunded),
+            (_, Graduate) => return err!(CustomError::PoolCannotGraduate),
+            (_, Migrate) => return err!(CustomError::PoolNotGraduated),
+            (_, RemoveLiquidity) => return err!(CustomError::LiquidityLocked),
+            (_, StartPresale) => return err!(CustomError::PresaleNotAllowed),
+        })
+    }
+}
+
+// Layout of `LiquidityPool` before curves and lifecycles, read by `migrate_pool`
+#[derive(AnchorDeserialize, Clone, Copy, Debug)]
+pub struct LegacyLiquidityPool {
+    pub creator: Pubkey,
+    pub token: Pubkey,
+    pub total_supply: u64, // Always 10^18, unrelated to the mint
+    pub reserve_token: u64,
+    pub reserve_sol: u64,
+    pub bump: u8,
+}
+
 #[account]
 pub struct LiquidityPool {
     pub creator: Pubkey,    // Public key of the pool creator
     pub token: Pubkey,      // Public key of the token in the liquidity pool
-    pub total_supply: u64,  // Total supply of liquidity tokens
+    pub total_supply: u64,  // Tokens the pool was funded with, always reserve_token + tokens_sold
     pub reserve_token: u64, // Reserve amount of token in the pool
     pub reserve_sol: u64,   // Reserve amount of sol_token in the pool
     pub bump: u8,           // Nonce for the program-derived address
+    pub curve: CurveKind,   // Pricing model, fixed at creation
+    pub tokens_sold: u64,   // Tokens bought out of the pool and not sold back, the curve's supply
+    pub status: PoolStatus, // Where the pool is in its lifecycle
+    pub total_shares: u64,  // Shares held by all liquidity providers
+    pub paused: PoolPauseFlags, // Actions halted on this pool on top of the global flags
+    pub launch_protection: Option<LaunchProtection>, // Anti-sniper rules for the first buys, set by the creator
+    pub presale: Option<PresaleAccess>, // Who may buy before trading opens, set by `start_presale`
+    pub launch_fee: Option<LaunchFee>,  // Decaying fee for the first slots of trading, set by the creator
+    pub trading_opened_slot: u64,       // Slot trading opened in, zero before
+    pub seed_sol: u64,    // Part of `reserve_sol` deposited by the providers and not yet withdrawn
+    pub unlock_at: i64,   // Unix time from which providers may withdraw their seed while trading
 }
 
 impl LiquidityPool {
@@ -53,10 +563,16 @@ impl LiquidityPool {
 
     // Discriminator (8) + Pubkey (32) + Pubkey (32) + totalsupply (8)
     // + reserve one (8) + reserve two (8) + Bump (1)
-    pub const ACCOUNT_SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
+    pub const LEGACY_ACCOUNT_SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
+
+    // Legacy layout + curve kind + tokens sold (8) + status (1)
+    // + total shares (8) + pause flags + launch protection (1 + size) + presale (1 + size)
+    // + launch fee (1 + size) + trading opened slot (8) + seed SOL (8) + unlock time (8)
+    pub const ACCOUNT_SIZE: usize = Self::LEGACY_ACCOUNT_SIZE + CurveKind::SIZE + 8 + 1 + 8
+        + PoolPauseFlags::SIZE + 1 + LaunchProtection::SIZE + 1 + PresaleAccess::SIZE + 1 + LaunchFee::SIZE + 8 + 8 + 8;
 
     // Constructor to initialize a LiquidityPool with two tokens and a bump for the PDA
-    pub fn new(creator: Pubkey, token: Pubkey, bump: u8) -> Self {
+    pub fn new(creator: Pubkey, token: Pubkey, bump: u8, curve: CurveKind) -> Self {
         Self {
             creator,
             token,
@@ -64,102 +580,445 @@ impl LiquidityPool {
             reserve_token: 0_u64,
             reserve_sol: 0_u64,
             bump,
+            curve,
+            tokens_sold: 0_u64,
+            status: PoolStatus::Created,
+            total_shares: 0_u64,
+            paused: PoolPauseFlags::default(),
+            launch_protection: None,
+            presale: None,
+            launch_fee: None,
+            trading_opened_slot: 0,
+            seed_sol: 0,
+            unlock_at: 0,
+        }
+    }
+
+    // A pool in the legacy layout, which traded on the virtual reserves from the start and
+    // held the whole `mint_supply` when funded. What it no longer holds was sold. Nobody
+    // held shares, so there is no seed to give back and the SOL reserve backs the sells.
+    pub fn from_legacy(legacy: LegacyLiquidityPool, mint_supply: u64, slot: u64) -> Result<Self> {
+        let mut pool = Self::new(
+            legacy.creator,
+            legacy.token,
+            legacy.bump,
+            CurveKind::VirtualConstantProduct { virtual_sol: V_SOL_AMOUNT, virtual_token: V_TOKEN_AMOUNT },
+        );
+        pool.reserve_token = legacy.reserve_token;
+        pool.reserve_sol = legacy.reserve_sol;
+        pool.tokens_sold = mint_supply
+            .checked_sub(legacy.reserve_token)
+            .ok_or(CustomError::SupplyMismatch)?;
+        pool.total_supply = mint_supply;
+        pool.status = PoolStatus::Trading;
+        pool.trading_opened_slot = slot;
+        Ok(pool)
+    }
+
+    // Launch fees are capped by the admin, and closed to creators while the cap is zero
+    pub fn set_launch_fee(&mut self, launch_fee: Option<LaunchFee>, max_launch_fee: u16) -> Result<()> {
+        if let Some(launch_fee) = launch_fee {
+            // `amount_with_fee` needs a fee below 100%
+            if launch_fee.start_fee as u64 >= FEE_DENOMINATOR
+                || launch_fee.start_fee > max_launch_fee
+                || launch_fee.decay_slots == 0
+            {
+                return err!(CustomError::InvalidLaunchFee);
+            }
+        }
+
+        self.launch_fee = launch_fee;
+        Ok(())
+    }
+
+    // Opens trading in `slot`, starting the launch fee's decay and locking the providers' seed
+    // until `unlock_at`
+    pub fn open_trading(&mut self, slot: u64, unlock_at: i64) -> Result<()> {
+        self.transition(PoolTransition::OpenTrading)?;
+        self.trading_opened_slot = slot;
+        self.unlock_at = unlock_at;
+        Ok(())
+    }
+
+    // Trading fee in basis points in `slot`. The launch fee only applies once trading is
+    // open, presale buyers pay the flat fee.
+    pub fn fee_bps(&self, config: &CurveConfiguration, slot: u64) -> u16 {
+        match self.launch_fee {
+            Some(launch_fee) if self.trading_opened_slot != 0 => {
+                launch_fee.fee_bps(config.fees, slot.saturating_sub(self.trading_opened_slot))
+            }
+            _ => config.fees,
         }
     }
+
+    // Adds `trade` to what was bought in the presale under one allocation, the wallet's or
+    // the NFT's, and holds it to that allocation
+    pub fn record_presale_buy(&self, presale_tokens: &mut u64, allocation: u64, trade: &TradeResult) -> Result<()> {
+        *presale_tokens = presale_tokens
+            .checked_add(trade.token_amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+        if *presale_tokens > allocation {
+            return err!(CustomError::PresaleAllocationExceeded);
+        }
+        Ok(())
+    }
+
+    pub fn set_launch_protection(&mut self, launch_protection: Option<LaunchProtection>) -> Result<()> {
+        if let Some(protection) = launch_protection {
+            let cap = match protection.cap {
+                BuyCap::Tokens(max) | BuyCap::Sol(max) => max,
+            };
+            if protection.window < 0 || cap == 0 {
+                return err!(CustomError::InvalidLaunchProtection);
+            }
+        }
+
+        self.launch_protection = launch_protection;
+        Ok(())
+    }
+
+    // Whether buys at unix time `now` are held to a per-wallet limit, the presale allocation
+    // or the launch protection cap, so the buyer's `WalletPurchases` has to come along
+    pub fn limits_purchases(&self, now: i64) -> bool {
+        self.status == PoolStatus::Presale
+            || self
+                .launch_protection
+                .is_some_and(|protection| now < protection.start_time.saturating_add(protection.window))
+    }
+
+    // Refuses buys before the launch starts and, during its protected window, adds `trade`
+    // to what the wallet bought and holds it to the cap
+    pub fn record_protected_buy(&self, now: i64, purchases: &mut WalletPurchases, trade: &TradeResult) -> Result<()> {
+        let Some(protection) = self.launch_protection else {
+            return Ok(());
+        };
+
+        if now < protection.start_time {
+            return err!(CustomError::LaunchNotStarted);
+        }
+        if now >= protection.start_time.saturating_add(protection.window) {
+            return Ok(());
+        }
+
+        purchases.tokens = purchases
+            .tokens
+            .checked_add(trade.token_amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        purchases.sol = purchases
+            .sol
+            .checked_add(trade.sol_amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+        let within_cap = match protection.cap {
+            BuyCap::Tokens(max) => purchases.tokens <= max,
+            BuyCap::Sol(max) => purchases.sol <= max,
+        };
+        if !within_cap {
+            return err!(CustomError::BuyCapExceeded);
+        }
+
+        Ok(())
+    }
+
+    // Fails if `scope` is paused on this pool or on every pool
+    pub fn check_not_paused(&self, config: &CurveConfiguration, scope: PauseScope) -> Result<()> {
+        config.paused.union(self.paused.into()).check(scope)
+    }
+
+    pub fn transition(&mut self, transition: PoolTransition) -> Result<()> {
+        let status = self.status.next(transition)?;
+        debug_msg!("Pool status: {:?} -> {:?}", self.status, status);
+        self.status = status;
+        Ok(())
+    }
+
+    pub fn check_trading(&self) -> Result<()> {
+        if self.status != PoolStatus::Trading {
+            return err!(CustomError::PoolNotTrading);
+        }
+        Ok(())
+    }
+
+    // Buys are also taken during the presale, from the wallets the `buy` handler lets through
+    pub fn check_buying(&self) -> Result<()> {
+        if !matches!(self.status, PoolStatus::Trading | PoolStatus::Presale) {
+            return err!(CustomError::PoolNotTrading);
+        }
+        Ok(())
+    }
+
+    // Fails unless the pool was funded with the mint's whole supply, so nobody is left holding
+    // tokens to sell into the curve once buyers have paid in. `withheld` are the transfer fees
+    // kept back from the deposits. Only checked while funded, as the supply is compared with
+    // the reserves, which presale buys move tokens out of.
+    pub fn check_whole_supply(&self, mint_supply: u64, withheld: u64) -> Result<()> {
+        if self.status != PoolStatus::Funded {
+            return Ok(());
+        }
+        if self.reserve_token.checked_add(withheld) != Some(mint_supply) {
+            return err!(CustomError::SupplyNotInPool);
+        }
+        Ok(())
+    }
+
+    // Lamports keeping the SOL vault rent exempt. They are paid in when the vault is funded
+    // and never count towards the reserve, so sells can take the whole reserve out.
+    pub fn sol_vault_rent() -> Result<u64> {
+        Ok(Rent::get()?.minimum_balance(0))
+    }
+
+    // Fails the instruction if the reserves are not backed by the vaults or no longer add
+    // up to the pool's supply. Balances may exceed the reserves, since anyone can send
+    // tokens or lamports to the vaults, and `sync_reserves` lets the admin absorb them.
+    // The SOL vault's rent is not part of the reserve.
+    pub fn check_invariants(
+        &self,
+        pool_token_account: &mut InterfaceAccount<TokenAccount>,
+        pool_sol_vault: &AccountInfo,
+    ) -> Result<()> {
+        if self.status == PoolStatus::Closed {
+            return Ok(());
+        }
+
+        pool_token_account.reload()?;
+        let sol_available = pool_sol_vault
+            .lamports()
+            .saturating_sub(LiquidityPool::sol_vault_rent()?);
+        if self.reserve_token > pool_token_account.amount || self.reserve_sol > sol_available {
+            return err!(CustomError::ReserveMismatch);
+        }
+
+        let supply = self
+            .reserve_token
+            .checked_add(self.tokens_sold)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        if supply != self.total_supply {
+            return err!(CustomError::SupplyMismatch);
+        }
+
+        Ok(())
+    }
+
+    // The pool's curve positioned at its current reserves and supply
+    pub fn bonding_curve(&self) -> Result<Box<dyn BondingCurve>> {
+        self.curve.at(self.reserve_sol, self.reserve_token, self.tokens_sold)
+    }
+
+    // Shares minted and lamports owed for depositing `tokens`. The first deposit mints one
+    // share per token and brings the initial SOL, later ones must match the reserve ratio,
+    // rounded in favour of the providers already in
+    pub fn quote_deposit(&self, tokens: u64) -> Result<(u64, u64)> {
+        if self.total_shares == 0 {
+            return Ok((tokens, INITIAL_LAMPORTS_FOR_POOL));
+        }
+
+        let shares = mul_div(tokens, self.total_shares, self.reserve_token, false)?;
+        let sol = mul_div(tokens, self.reserve_sol, self.reserve_token, true)?;
+
+        Ok((shares, sol))
+    }
+
+    // Tokens and lamports paid out for burning `shares` at unix time `now`, rounded down.
+    // Until trading opens providers take their share of both reserves. After that the
+    // tokens back the curve and the SOL paid in by buyers backs their sells, so providers
+    // only get their share of the seed back, once the lock runs out or the pool graduates.
+    pub fn quote_withdrawal(&self, shares: u64, now: i64) -> Result<(u64, u64)> {
+        if shares > self.total_shares {
+            return err!(CustomError::InsufficientShares);
+        }
+
+        match self.status {
+            PoolStatus::Funded => {
+                let tokens = mul_div(self.reserve_token, shares, self.total_shares, false)?;
+                let sol = mul_div(self.reserve_sol, shares, self.total_shares, false)?;
+                Ok((tokens, sol))
+            }
+            PoolStatus::Trading if now < self.unlock_at => err!(CustomError::LiquidityStillLocked),
+            PoolStatus::Trading | PoolStatus::Graduated | PoolStatus::Closed => {
+                Ok((0, mul_div(self.seed_sol, shares, self.total_shares, false)?))
+            }
+            _ => err!(CustomError::LiquidityLocked),
+        }
+    }
+
+    // Lamports (sent to the pool, taken as fee) needed to receive exactly `token_amount` tokens
+    pub fn quote_buy_exact_out(&self, fee_bps: u16, token_amount: u64) -> Result<(u64, u64)> {
+        let amount_in = self.bonding_curve()?.cost_to_buy(token_amount)?;
+        let gross = curve::amount_with_fee(amount_in, fee_bps)?;
+
+        Ok((amount_in, gross - amount_in))
+    }
+}
+
+// `a * b / c` without intermediate overflow
+fn mul_div(a: u64, b: u64, c: u64, round_up: bool) -> Result<u64> {
+    if c == 0 {
+        return err!(CustomError::OverflowOrUnderflowOccurred);
+    }
+
+    let product = a as u128 * b as u128;
+    let mut quotient = product / c as u128;
+    if round_up && !product.is_multiple_of(c as u128) {
+        quotient += 1;
+    }
+
+    u64::try_from(quotient).map_err(|_| error!(CustomError::OverflowOrUnderflowOccurred))
+}
+
+/// Amounts actually settled by a buy or a sell
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TradeResult {
+    pub sol_amount: u64,   // Lamports paid by the buyer / received by the seller, fee excluded
+    pub token_amount: u64, // Tokens received by the buyer / received by the pool from the seller
+    pub fee: u64,          // Lamports taken as fee
+    pub creator_fee: u64,  // Part of `fee` paid to the pool creator
+    pub referral_fee: u64, // Part of `fee` paid to the referrer
+    pub price: u64,        // Realised price in lamports per whole token
+}
+
+impl TradeResult {
+    pub fn new(sol_amount: u64, token_amount: u64, fee: u64) -> Result<Self> {
+        let price = if token_amount == 0 {
+            0
+        } else {
+            let price = (sol_amount as u128)
+                .checked_mul(TOKEN_UNIT as u128)
+                .ok_or(CustomError::OverflowOrUnderflowOccurred)?
+                / token_amount as u128;
+            u64::try_from(price).map_err(|_| error!(CustomError::OverflowOrUnderflowOccurred))?
+        };
+
+        Ok(Self { sol_amount, token_amount, fee, creator_fee: 0, referral_fee: 0, price })
+    }
+}
+
+/// Accounts a pool instruction moves tokens and lamports between, on behalf of `user`
+pub struct PoolAccounts<'a, 'info> {
+    pub token_mint: &'a InterfaceAccount<'info, Mint>,
+    pub pool_token_account: &'a mut InterfaceAccount<'info, TokenAccount>,
+    pub user_token_account: &'a mut InterfaceAccount<'info, TokenAccount>,
+    pub pool_sol_vault: &'a AccountInfo<'info>,
+    pub pool_sol_vault_bump: u8, // Signs for the SOL vault when it pays out
+    pub user: &'a Signer<'info>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+    pub system_program: &'a Program<'info, System>,
+}
+
+/// Where the parts of a trading fee accrue
+pub struct FeeAccounts<'a, 'info> {
+    pub protocol_fee_vault: &'a mut Account<'info, ProtocolFeeVault>,
+    pub creator_fee_vault: &'a mut Account<'info, CreatorFeeVault>,
+    pub referrer: Option<&'a mut Account<'info, Referrer>>, // Set when the trade was referred
 }
 
 pub trait LiquidityPoolAccount<'info> {
     // Updates the token reserves in the liquidity pool
     fn update_reserves(&mut self, reserve_token: u64, reserve_sol: u64) -> Result<()>;
 
-    // Allows adding liquidity by depositing an amount of two tokens and getting back pool shares
+    // Allows adding liquidity by depositing tokens and SOL and getting back pool shares.
+    // Returns the shares minted to the provider
     fn add_liquidity(
         &mut self,
-        token_accounts: (
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-        ),
-        pool_sol_vault: &mut AccountInfo<'info>,
-        authority: &Signer<'info>,
-        token_program: &Program<'info, Token>,
-        system_program: &Program<'info, System>,
-    ) -> Result<()>;
-
-    // Allows removing liquidity by burning pool shares and receiving back a proportionate amount of tokens
+        provider: &mut Account<'info, LiquidityProvider>,
+        accounts: PoolAccounts<'_, 'info>,
+        token_amount: u64,
+        max_sol_amount: u64,
+    ) -> Result<u64>;
+
+    // Allows removing liquidity by burning pool shares and receiving back a proportionate amount of tokens.
+    // Returns the tokens and lamports paid out
     fn remove_liquidity(
         &mut self,
-        token_accounts: (
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-        ),
-        pool_sol_account: &mut AccountInfo<'info>,
-        authority: &Signer<'info>,
-        bump: u8,
-        token_program: &Program<'info, Token>,
-        system_program: &Program<'info, System>,
-    ) -> Result<()>;
+        provider: &mut Account<'info, LiquidityProvider>,
+        accounts: PoolAccounts<'_, 'info>,
+        shares: u64,
+    ) -> Result<(u64, u64)>;
 
     fn buy(
         &mut self,
-        bonding_configuration_account: &mut Account<'info, CurveConfiguration>,
-        token_accounts: (
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-        ),
-        pool_sol_vault: &mut AccountInfo<'info>,
+        bonding_configuration_account: &Account<'info, CurveConfiguration>,
+        fee_accounts: FeeAccounts<'_, 'info>,
+        accounts: PoolAccounts<'_, 'info>,
         amount: u64,
-        authority: &Signer<'info>,
-        token_program: &Program<'info, Token>,
-        system_program: &Program<'info, System>,
-    ) -> Result<()>;
+    ) -> Result<TradeResult>;
+
+    fn buy_exact_out(
+        &mut self,
+        bonding_configuration_account: &Account<'info, CurveConfiguration>,
+        fee_accounts: FeeAccounts<'_, 'info>,
+        accounts: PoolAccounts<'_, 'info>,
+        token_amount: u64,
+    ) -> Result<TradeResult>;
+
+    // Moves the SOL and fee in, the tokens out, and updates the reserves for a priced buy
+    fn settle_buy(
+        &mut self,
+        bonding_configuration_account: &Account<'info, CurveConfiguration>,
+        fee_accounts: FeeAccounts<'_, 'info>,
+        accounts: PoolAccounts<'_, 'info>,
+        trade: TradeResult,
+    ) -> Result<TradeResult>;
 
     fn sell(
         &mut self,
-        bonding_configuration_account: &mut Account<'info, CurveConfiguration>,
-        token_accounts: (
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-        ),
-        pool_sol_vault: &mut AccountInfo<'info>,
+        bonding_configuration_account: &Account<'info, CurveConfiguration>,
+        fee_accounts: FeeAccounts<'_, 'info>,
+        accounts: PoolAccounts<'_, 'info>,
         amount: u64,
-        bump: u8,
-        authority: &Signer<'info>,
-        token_program: &Program<'info, Token>,
+    ) -> Result<TradeResult>;
+
+    // Sends each part of a trading fee to where it accrues. The fee is paid by `from`,
+    // which is the pool's SOL vault, signed for with `vault_bump`, when the pool pays it
+    fn pay_fees(
+        &self,
+        fee_accounts: FeeAccounts<'_, 'info>,
+        split: FeeSplit,
+        from: &AccountInfo<'info>,
+        vault_bump: Option<u8>,
         system_program: &Program<'info, System>,
     ) -> Result<()>;
 
+    // Returns the amount `to` actually received, less any transfer fee
     fn transfer_token_from_pool(
         &self,
-        from: &Account<'info, TokenAccount>,
-        to: &Account<'info, TokenAccount>,
+        from: &InterfaceAccount<'info, TokenAccount>,
+        to: &mut InterfaceAccount<'info, TokenAccount>,
+        mint: &InterfaceAccount<'info, Mint>,
         amount: u64,
-        token_program: &Program<'info, Token>,
-    ) -> Result<()>;
+        token_program: &Interface<'info, TokenInterface>,
+    ) -> Result<u64>;
 
+    // Returns the amount the pool actually received, less any transfer fee
     fn transfer_token_to_pool(
         &self,
-        from: &Account<'info, TokenAccount>,
-        to: &Account<'info, TokenAccount>,
+        from: &InterfaceAccount<'info, TokenAccount>,
+        to: &mut InterfaceAccount<'info, TokenAccount>,
+        mint: &InterfaceAccount<'info, Mint>,
         amount: u64,
         authority: &Signer<'info>,
-        token_program: &Program<'info, Token>,
-    ) -> Result<()>;
+        token_program: &Interface<'info, TokenInterface>,
+    ) -> Result<u64>;
 
     fn transfer_sol_to_pool(
         &self,
         from: &Signer<'info>,
-        to: &mut AccountInfo<'info>,
+        to: &AccountInfo<'info>,
         amount: u64,
         system_program: &Program<'info, System>,
     ) -> Result<()>;
 
+    // Pays whatever the SOL vault lacks of its rent, before the first lamports go in
+    fn fund_sol_vault_rent(
+        &self,
+        from: &Signer<'info>,
+        to: &AccountInfo<'info>,
+        system_program: &Program<'info, System>,
+    ) -> Result<()>;
+
     fn transfer_sol_from_pool(
         &self,
-        from: &mut AccountInfo<'info>,
+        from: &AccountInfo<'info>,
         to: &AccountInfo<'info>,
         amount: u64,
         bump: u8,
@@ -176,339 +1035,410 @@ impl<'info> LiquidityPoolAccount<'info> for Account<'info, LiquidityPool> {
 
     fn add_liquidity(
         &mut self,
-        token_accounts: (
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-        ),
-        pool_sol_vault: &mut AccountInfo<'info>,
-        authority: &Signer<'info>,
-        token_program: &Program<'info, Token>,
-        system_program: &Program<'info, System>,
-    ) -> Result<()> {
-        // Check current balances in the pool accounts
-        let current_pool_tokens = token_accounts.1.amount;
-        let current_pool_sol = pool_sol_vault.lamports();
-        
-        // If pool already has liquidity, just update the reserves
-        if current_pool_tokens > 0 && current_pool_sol > 0 {
-            // Pool already has liquidity, update reserves to match actual balances
-            self.update_reserves(current_pool_tokens, current_pool_sol)?;
-            self.total_supply = 1_000_000_000_000_000_000;
-        } else {
-            // Initial liquidity provision - transfer tokens and SOL
-            self.transfer_token_to_pool(
-                token_accounts.2,
-                token_accounts.1,
-                token_accounts.0.supply,
-                authority,
-                token_program,
-            )?;
+        provider: &mut Account<'info, LiquidityProvider>,
+        accounts: PoolAccounts<'_, 'info>,
+        token_amount: u64,
+        max_sol_amount: u64,
+    ) -> Result<u64> {
+        if token_amount == 0 {
+            return err!(CustomError::InvalidAmount);
+        }
 
-            self.transfer_sol_to_pool(
-                authority,
-                pool_sol_vault,
-                INITIAL_LAMPORTS_FOR_POOL,
-                system_program,
-            )?;
-            
-            self.total_supply = 1_000_000_000_000_000_000;
-            self.update_reserves(token_accounts.0.supply, INITIAL_LAMPORTS_FOR_POOL)?;
+        // Shares are priced on what reached the pool, less any transfer fee
+        let received = self.transfer_token_to_pool(
+            accounts.user_token_account,
+            accounts.pool_token_account,
+            accounts.token_mint,
+            token_amount,
+            accounts.user,
+            accounts.token_program,
+        )?;
+
+        let (shares, sol_amount) = self.quote_deposit(received)?;
+        if shares == 0 {
+            return err!(CustomError::InvalidAmount);
+        }
+        if sol_amount > max_sol_amount {
+            return err!(CustomError::SlippageExceeded);
         }
 
-        Ok(())
+        self.fund_sol_vault_rent(accounts.user, accounts.pool_sol_vault, accounts.system_program)?;
+        self.transfer_sol_to_pool(accounts.user, accounts.pool_sol_vault, sol_amount, accounts.system_program)?;
+
+        debug_msg!("Liquidity added: {} tokens, {} lamports, {} shares", received, sol_amount, shares);
+
+        self.reserve_token = self
+            .reserve_token
+            .checked_add(received)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        self.reserve_sol = self
+            .reserve_sol
+            .checked_add(sol_amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        self.seed_sol = self
+            .seed_sol
+            .checked_add(sol_amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        self.total_supply = self
+            .total_supply
+            .checked_add(received)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        self.total_shares = self
+            .total_shares
+            .checked_add(shares)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        provider.shares = provider
+            .shares
+            .checked_add(shares)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+        Ok(shares)
     }
 
     fn remove_liquidity(
         &mut self,
-        token_accounts: (
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-        ),
-        pool_sol_vault: &mut AccountInfo<'info>,
-        authority: &Signer<'info>,
-        bump: u8,
-        token_program: &Program<'info, Token>,
-        system_program: &Program<'info, System>,
-    ) -> Result<()> {
-        self.transfer_token_from_pool(
-            token_accounts.1,
-            token_accounts.2,
-            token_accounts.1.amount as u64,
-            token_program,
+        provider: &mut Account<'info, LiquidityProvider>,
+        accounts: PoolAccounts<'_, 'info>,
+        shares: u64,
+    ) -> Result<(u64, u64)> {
+        if shares == 0 {
+            return err!(CustomError::InvalidAmount);
+        }
+        if shares > provider.shares {
+            return err!(CustomError::InsufficientShares);
+        }
+
+        let (token_amount, sol_amount) = if self.status == PoolStatus::Funded && shares == self.total_shares {
+            // The last provider out of a pool that never traded empties the vaults,
+            // including anything sent to them
+            let amounts = (accounts.pool_token_account.amount, accounts.pool_sol_vault.lamports());
+            self.update_reserves(0, 0)?;
+            self.total_supply = 0;
+            self.seed_sol = 0;
+            amounts
+        } else {
+            let (token_amount, sol_amount) = self.quote_withdrawal(shares, Clock::get()?.unix_timestamp)?;
+            self.reserve_token -= token_amount;
+            self.reserve_sol = self
+                .reserve_sol
+                .checked_sub(sol_amount)
+                .ok_or(CustomError::NotEnoughSolInVault)?;
+            self.seed_sol = if self.status == PoolStatus::Funded {
+                // Before trading opens the whole reserve is seed
+                self.reserve_sol
+            } else {
+                self.seed_sol
+                    .checked_sub(sol_amount)
+                    .ok_or(CustomError::OverflowOrUnderflowOccurred)?
+            };
+            self.total_supply = self
+                .total_supply
+                .checked_sub(token_amount)
+                .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+            (token_amount, sol_amount)
+        };
+
+        self.total_shares -= shares;
+        provider.shares -= shares;
+
+        debug_msg!("Liquidity removed: {} tokens, {} lamports, {} shares", token_amount, sol_amount, shares);
+
+        if token_amount > 0 {
+            self.transfer_token_from_pool(
+                accounts.pool_token_account,
+                accounts.user_token_account,
+                accounts.token_mint,
+                token_amount,
+                accounts.token_program,
+            )?;
+        }
+        self.transfer_sol_from_pool(
+            accounts.pool_sol_vault,
+            accounts.user,
+            sol_amount,
+            accounts.pool_sol_vault_bump,
+            accounts.system_program,
         )?;
-        // let amount = self.to_account_info().lamports() - self.get_lamports();
-        let amount = pool_sol_vault.to_account_info().lamports() as u64;
-        self.transfer_sol_from_pool(pool_sol_vault, authority, amount, bump, system_program)?;
 
-        Ok(())
+        Ok((token_amount, sol_amount))
     }
 
-    ///////////////////////////////////////////////////////////////
-    ///////////////////////////////////////////////////////////////
-    //
-    //              Linear bonding curve swap
-    //
-    /////////////////////////////////////////////////////////////
-    /////////////////////////////////////////////////////////////
-    //
-    //  Linear bonding curve : S = T * P ( here, p is constant that show initial price )
-    //  SOL amount => S
-    //  Token amount => T
-    //  Initial Price => P
-    //
-    //  SOL amount to buy Token a => S_a = ((T_a  + 1) * T_a / 2) * P
-    //  SOL amount to buy Token b => S_b = ((T_b + 1) * T_b / 2) * P
-
     fn buy(
         &mut self,
-        bonding_configuration_account: &mut Account<'info, CurveConfiguration>,
-        token_accounts: (
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-        ),
-        pool_sol_vault: &mut AccountInfo<'info>,
+        bonding_configuration_account: &Account<'info, CurveConfiguration>,
+        fee_accounts: FeeAccounts<'_, 'info>,
+        accounts: PoolAccounts<'_, 'info>,
         amount: u64,
-        authority: &Signer<'info>,
-        token_program: &Program<'info, Token>,
-        system_program: &Program<'info, System>,
-    ) -> Result<()> {
+    ) -> Result<TradeResult> {
+        self.check_buying()?;
+        self.check_not_paused(bonding_configuration_account, PauseScope::Buy)?;
+
         if amount == 0 {
             return err!(CustomError::InvalidAmount);
         }
 
-        let fee_percent = bonding_configuration_account.fees;
-        let fee_amount = (amount as f64) * fee_percent / 10000.0;
-
-        // For small test pools, use simple constant product formula
-        // For production pools with large reserves, use virtual AMM
-        let amount_out = if self.reserve_token < 1_000_000_000_000_000 { // Less than 1M tokens
-            // Simple constant product AMM: x * y = k
-            // amount_out = (amount_in * reserve_out) / (reserve_in + amount_in)
-            let amount_in_after_fee = amount as f64 - fee_amount;
-            let reserve_in_f64 = self.reserve_sol as f64;
-            let reserve_out_f64 = self.reserve_token as f64;
-            
-            let calculated_out = (amount_in_after_fee * reserve_out_f64) / (reserve_in_f64 + amount_in_after_fee);
-            
-            msg!("Using simple AMM for test pool:");
-            msg!("  Amount in (lamports): {}", amount);
-            msg!("  Amount in after fee: {}", amount_in_after_fee);
-            msg!("  Reserve SOL: {}", self.reserve_sol);
-            msg!("  Reserve Token: {}", self.reserve_token);
-            msg!("  Amount out: {}", calculated_out);
-            
-            calculated_out.round() as u64
-        } else {
-            // Production virtual AMM formula
-            let new_virtual_reserve_in = (self.reserve_sol as f64) / ONE_BILLION + V_SOL_AMOUNT;
-            let new_virtual_reserve_out = (self.reserve_token as f64) / ONE_BILLION + V_TOKEN_AMOUNT;
-
-            msg!("Using virtual AMM for production pool:");
-            msg!("  Amount in (lamports): {}", amount);
-            msg!("  Reserve SOL: {}", self.reserve_sol);
-            msg!("  Reserve Token: {}", self.reserve_token);
-            msg!("  Virtual Reserve In: {}", new_virtual_reserve_in);
-            msg!("  Virtual Reserve Out: {}", new_virtual_reserve_out);
-
-            let v_out = (new_virtual_reserve_in * new_virtual_reserve_out)
-                / (new_virtual_reserve_in + (amount as f64 - fee_amount) / ONE_BILLION);
-
-            let calculated_out = ((new_virtual_reserve_out - v_out) * ONE_BILLION).round() as u64;
-            
-            msg!("  V_out: {}", v_out);
-            msg!("  Amount out: {}", calculated_out);
-            
-            calculated_out
-        };
-
-        // msg!("Trying to buy from the pool");
-
-        // let bought_amount =
-        //     (self.total_supply as f64 - self.reserve_token as f64) / 1_000_000.0 / 1_000_000_000.0;
-        // msg!("bought_amount {}", bought_amount);
-
-        // let root_val = (PROPORTION as f64 * amount as f64 / 1_000_000_000.0
-        //     + bought_amount * bought_amount)
-        //     .sqrt();
-        // msg!("root_val {}", root_val);
+        let fee_bps = self.fee_bps(bonding_configuration_account, Clock::get()?.slot);
+        let fee_amount = curve::fee_on(amount, fee_bps)?;
+        let amount_in = amount
+            .checked_sub(fee_amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+        let amount_out = self.bonding_curve()?.tokens_for_sol(amount_in)?;
+
+        debug_msg!("Buy on bonding curve:");
+        debug_msg!("  Amount in (lamports): {}", amount);
+        debug_msg!("  Amount in after fee: {}", amount_in);
+        debug_msg!("  Reserve SOL: {}", self.reserve_sol);
+        debug_msg!("  Reserve Token: {}", self.reserve_token);
+        debug_msg!("  Curve: {:?}", self.curve);
+        debug_msg!("  Amount out: {}", amount_out);
+
+        self.settle_buy(
+            bonding_configuration_account,
+            fee_accounts,
+            accounts,
+            TradeResult::new(amount_in, amount_out, fee_amount)?,
+        )
+    }
 
-        // let amount_out_f64 = (root_val - bought_amount as f64) * 1_000_000.0 * 1_000_000_000.0;
-        // msg!("amount_out_f64 {}", amount_out_f64);
+    fn buy_exact_out(
+        &mut self,
+        bonding_configuration_account: &Account<'info, CurveConfiguration>,
+        fee_accounts: FeeAccounts<'_, 'info>,
+        accounts: PoolAccounts<'_, 'info>,
+        token_amount: u64,
+    ) -> Result<TradeResult> {
+        self.check_buying()?;
+        self.check_not_paused(bonding_configuration_account, PauseScope::Buy)?;
+
+        if token_amount == 0 {
+            return err!(CustomError::InvalidAmount);
+        }
 
-        // let amount_out = amount_out_f64.round() as u64;
-        // msg!("amount_out {}", amount_out);
+        // The pool sends enough for `token_amount` to arrive after any transfer fee
+        let amount_out = amount_before_transfer_fee(&accounts.token_mint.to_account_info(), token_amount)?;
+        let fee_bps = self.fee_bps(bonding_configuration_account, Clock::get()?.slot);
+        let (amount_in, fee_amount) = self.quote_buy_exact_out(fee_bps, amount_out)?;
+
+        debug_msg!("Exact output buy on bonding curve:");
+        debug_msg!("  Amount out (tokens): {}", token_amount);
+        debug_msg!("  Amount sent (tokens): {}", amount_out);
+        debug_msg!("  Amount in (lamports): {}", amount_in);
+        debug_msg!("  Fee: {}", fee_amount);
+
+        let trade = self.settle_buy(
+            bonding_configuration_account,
+            fee_accounts,
+            accounts,
+            TradeResult::new(amount_in, amount_out, fee_amount)?,
+        )?;
+        if trade.token_amount < token_amount {
+            return err!(CustomError::SlippageExceeded);
+        }
+        Ok(trade)
+    }
 
-        if amount_out > self.reserve_token {
+    fn settle_buy(
+        &mut self,
+        bonding_configuration_account: &Account<'info, CurveConfiguration>,
+        fee_accounts: FeeAccounts<'_, 'info>,
+        accounts: PoolAccounts<'_, 'info>,
+        trade: TradeResult,
+    ) -> Result<TradeResult> {
+        if trade.token_amount > self.reserve_token {
             return err!(CustomError::NotEnoughTokenInVault);
         }
 
-        self.reserve_sol += amount;
-        self.reserve_token -= amount_out;
+        self.reserve_sol = self
+            .reserve_sol
+            .checked_add(trade.sol_amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        self.reserve_token -= trade.token_amount;
+        self.tokens_sold = self
+            .tokens_sold
+            .checked_add(trade.token_amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+        // The buy crossing the threshold still settles, every trade after it is refused
+        if bonding_configuration_account.graduates_at(self.reserve_sol) {
+            self.transition(PoolTransition::Graduate)?;
+            debug_msg!("Pool graduated with {} lamports in reserve", self.reserve_sol);
+        }
 
-        self.transfer_sol_to_pool(authority, pool_sol_vault, amount - fee_amount.round() as u64, system_program)?;
+        self.transfer_sol_to_pool(accounts.user, accounts.pool_sol_vault, trade.sol_amount, accounts.system_program)?;
 
-        system_program::transfer(
-            CpiContext::new(
-                system_program.to_account_info(),
-                system_program::Transfer {
-                    from: authority.to_account_info(),
-                    to: bonding_configuration_account.to_account_info(),
-                },
-            ),
-            fee_amount.round() as u64,
+        let split = bonding_configuration_account.split_fee(trade.fee, fee_accounts.referrer.is_some())?;
+        self.pay_fees(
+            fee_accounts,
+            split,
+            &accounts.user.to_account_info(),
+            None,
+            accounts.system_program,
         )?;
 
-        bonding_configuration_account.shares += fee_amount.round() as u64;
-
-        self.transfer_token_from_pool(
-            token_accounts.1,
-            token_accounts.2,
-            amount_out,
-            token_program,
+        // Reserves follow what left the pool, the buyer is quoted what arrived
+        let received = self.transfer_token_from_pool(
+            accounts.pool_token_account,
+            accounts.user_token_account,
+            accounts.token_mint,
+            trade.token_amount,
+            accounts.token_program,
         )?;
 
-        Ok(())
+        Ok(TradeResult {
+            creator_fee: split.creator,
+            referral_fee: split.referral,
+            ..TradeResult::new(trade.sol_amount, received, trade.fee)?
+        })
     }
 
     fn sell(
         &mut self,
-        bonding_configuration_account: &mut Account<'info, CurveConfiguration>,
-        token_accounts: (
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-        ),
-        pool_sol_vault: &mut AccountInfo<'info>,
+        bonding_configuration_account: &Account<'info, CurveConfiguration>,
+        fee_accounts: FeeAccounts<'_, 'info>,
+        accounts: PoolAccounts<'_, 'info>,
         amount: u64,
-        bump: u8,
-        authority: &Signer<'info>,
-        token_program: &Program<'info, Token>,
-        system_program: &Program<'info, System>,
-    ) -> Result<()> {
+    ) -> Result<TradeResult> {
+        self.check_trading()?;
+        self.check_not_paused(bonding_configuration_account, PauseScope::Sell)?;
+
         if amount == 0 {
             return err!(CustomError::InvalidAmount);
         }
 
-        if self.reserve_token < amount {
-            return err!(CustomError::TokenAmountToSellTooBig);
-        }
-
-        let fee_percent = bonding_configuration_account.fees;
-
-        // For small test pools, use simple constant product formula
-        // For production pools with large reserves, use virtual AMM
-        let (amount_out, fee_amount) = if self.reserve_token < 1_000_000_000_000_000 { // Less than 1M tokens
-            // Simple constant product AMM: x * y = k
-            // amount_out = (amount_in * reserve_out) / (reserve_in + amount_in)
-            let reserve_in_f64 = self.reserve_token as f64;
-            let reserve_out_f64 = self.reserve_sol as f64;
-            
-            let calculated_out = (amount as f64 * reserve_out_f64) / (reserve_in_f64 + amount as f64);
-            let fee = ((calculated_out as f64) * fee_percent / 10000.0).round() as u64;
-            
-            msg!("Using simple AMM for test pool sell:");
-            msg!("  Amount in (tokens): {}", amount);
-            msg!("  Reserve Token: {}", self.reserve_token);
-            msg!("  Reserve SOL: {}", self.reserve_sol);
-            msg!("  Amount out: {}", calculated_out);
-            msg!("  Fee: {}", fee);
-            
-            (calculated_out.round() as u64, fee)
-        } else {
-            // Production virtual AMM formula
-            let new_virtual_reserve_out = (self.reserve_sol as f64) / ONE_BILLION + V_SOL_AMOUNT;
-            let new_virtual_reserve_in = (self.reserve_token as f64) / ONE_BILLION + V_TOKEN_AMOUNT;
-
-            let v_out = (new_virtual_reserve_in * new_virtual_reserve_out)
-                / (new_virtual_reserve_in + (amount as f64) / ONE_BILLION);
-
-            let calculated_out = ((new_virtual_reserve_out - v_out) * ONE_BILLION).round() as u64;
-            let fee = ((calculated_out as f64) * fee_percent / 10000.0).round() as u64;
-            
-            msg!("Using virtual AMM for production pool sell:");
-            msg!("  Amount in (tokens): {}", amount);
-            msg!("  Reserve Token: {}", self.reserve_token);
-            msg!("  Reserve SOL: {}", self.reserve_sol);
-            msg!("  Virtual Reserve In: {}", new_virtual_reserve_in);
-            msg!("  Virtual Reserve Out: {}", new_virtual_reserve_out);
-            msg!("  V_out: {}", v_out);
-            msg!("  Amount out: {}", calculated_out);
-            msg!("  Fee: {}", fee);
-            
-            (calculated_out, fee)
-        };
-
+        let fee_bps = self.fee_bps(bonding_configuration_account, Clock::get()?.slot);
 
-        // let bought_amount =
-        //     (self.total_supply as f64 - self.reserve_token as f64) / 1_000_000.0 / 1_000_000_000.0;
-        // msg!("bought_amount: {}", bought_amount);
+        // Take the tokens first and price what reached the pool, which is less than
+        // `amount` for mints with a transfer fee
+        let received = self.transfer_token_to_pool(
+            accounts.user_token_account,
+            accounts.pool_token_account,
+            accounts.token_mint,
+            amount,
+            accounts.user,
+            accounts.token_program,
+        )?;
 
-        // let result_amount = (self.total_supply as f64 - self.reserve_token as f64 - amount as f64)
-        //     / 1_000_000.0
-        //     / 1_000_000_000.0;
-        // msg!("result_amount: {}", result_amount);
+        // Only tokens bought out of the curve can be sold back into it
+        if received > self.tokens_sold {
+            return err!(CustomError::TokenAmountToSellTooBig);
+        }
 
-        // let amount_out_f64 = (bought_amount * bought_amount - result_amount * result_amount)
-        //     / PROPORTION as f64
-        //     * 1_000_000_000.0;
-        // msg!("amount_out_f64: {}", amount_out_f64);
+        let amount_out = self.bonding_curve()?.proceeds_from_sell(received)?;
+        let fee_amount = curve::fee_on(amount_out, fee_bps)?;
 
-        // let amount_out = amount_out_f64.round() as u64;
-        // msg!("amount_out: {}", amount_out);
+        debug_msg!("Sell on bonding curve:");
+        debug_msg!("  Amount in (tokens): {}", amount);
+        debug_msg!("  Amount received (tokens): {}", received);
+        debug_msg!("  Reserve Token: {}", self.reserve_token);
+        debug_msg!("  Reserve SOL: {}", self.reserve_sol);
+        debug_msg!("  Curve: {:?}", self.curve);
+        debug_msg!("  Amount out: {}", amount_out);
+        debug_msg!("  Fee: {}", fee_amount);
 
         if self.reserve_sol < amount_out {
             return err!(CustomError::NotEnoughSolInVault);
         }
 
-        self.transfer_token_to_pool(
-            token_accounts.2,
-            token_accounts.1,
-            amount as u64,
-            authority,
-            token_program,
+        self.reserve_token = self
+            .reserve_token
+            .checked_add(received)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        self.reserve_sol -= amount_out;
+        self.tokens_sold = self
+            .tokens_sold
+            .checked_sub(received)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+        self.transfer_sol_from_pool(
+            accounts.pool_sol_vault,
+            accounts.user,
+            amount_out - fee_amount,
+            accounts.pool_sol_vault_bump,
+            accounts.system_program,
         )?;
 
-        self.reserve_token += amount;
-        self.reserve_sol -= amount_out;
+        let split = bonding_configuration_account.split_fee(fee_amount, fee_accounts.referrer.is_some())?;
+        self.pay_fees(
+            fee_accounts,
+            split,
+            accounts.pool_sol_vault,
+            Some(accounts.pool_sol_vault_bump),
+            accounts.system_program,
+        )?;
 
-        self.transfer_sol_from_pool(pool_sol_vault, authority, amount_out - fee_amount, bump, system_program)?;
+        Ok(TradeResult {
+            creator_fee: split.creator,
+            referral_fee: split.referral,
+            ..TradeResult::new(amount_out - fee_amount, received, fee_amount)?
+        })
+    }
 
-        system_program::transfer(
-            CpiContext::new_with_signer(
-                system_program.to_account_info(),
-                system_program::Transfer {
-                    from: pool_sol_vault.clone(),
-                    to: bonding_configuration_account.to_account_info().clone(),
-                },
-                &[&[
-                    LiquidityPool::SOL_VAULT_PREFIX.as_bytes(),
-                    self.token.key().as_ref(),
-                    &[bump],
-                ]],
-            ),
-            fee_amount,
-        )?;
+    fn pay_fees(
+        &self,
+        fee_accounts: FeeAccounts<'_, 'info>,
+        split: FeeSplit,
+        from: &AccountInfo<'info>,
+        vault_bump: Option<u8>,
+        system_program: &Program<'info, System>,
+    ) -> Result<()> {
+        let bump = [vault_bump.unwrap_or_default()];
+        let vault_seeds: &[&[u8]] = &[LiquidityPool::SOL_VAULT_PREFIX.as_bytes(), self.token.as_ref(), &bump];
+        let vault_signer = [vault_seeds];
+        let signer_seeds: &[&[&[u8]]] = if vault_bump.is_some() { &vault_signer } else { &[] };
+
+        let pay = |to: AccountInfo<'info>, amount: u64| {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    system_program.to_account_info(),
+                    system_program::Transfer { from: from.clone(), to },
+                    signer_seeds,
+                ),
+                amount,
+            )
+        };
 
-        bonding_configuration_account.shares += fee_amount;
+        let FeeAccounts { protocol_fee_vault, creator_fee_vault, referrer } = fee_accounts;
+
+        pay(protocol_fee_vault.to_account_info(), split.protocol)?;
+        protocol_fee_vault.fees = protocol_fee_vault
+            .fees
+            .checked_add(split.protocol)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+        pay(creator_fee_vault.to_account_info(), split.creator)?;
+        creator_fee_vault.fees = creator_fee_vault
+            .fees
+            .checked_add(split.creator)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+        if let Some(referrer) = referrer {
+            pay(referrer.to_account_info(), split.referral)?;
+            referrer.fees = referrer
+                .fees
+                .checked_add(split.referral)
+                .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        }
 
         Ok(())
     }
 
     fn transfer_token_from_pool(
         &self,
-        from: &Account<'info, TokenAccount>,
-        to: &Account<'info, TokenAccount>,
+        from: &InterfaceAccount<'info, TokenAccount>,
+        to: &mut InterfaceAccount<'info, TokenAccount>,
+        mint: &InterfaceAccount<'info, Mint>,
         amount: u64,
-        token_program: &Program<'info, Token>,
-    ) -> Result<()> {
-        token::transfer(
+        token_program: &Interface<'info, TokenInterface>,
+    ) -> Result<u64> {
+        let balance_before = to.amount;
+
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
                 token_program.to_account_info(),
-                token::Transfer {
+                token_interface::TransferChecked {
                     from: from.to_account_info(),
+                    mint: mint.to_account_info(),
                     to: to.to_account_info(),
                     authority: self.to_account_info(),
                 },
@@ -519,35 +1449,49 @@ impl<'info> LiquidityPoolAccount<'info> for Account<'info, LiquidityPool> {
                 ]],
             ),
             amount,
+            mint.decimals,
         )?;
-        Ok(())
+
+        to.reload()?;
+        to.amount
+            .checked_sub(balance_before)
+            .ok_or(error!(CustomError::OverflowOrUnderflowOccurred))
     }
 
     fn transfer_token_to_pool(
         &self,
-        from: &Account<'info, TokenAccount>,
-        to: &Account<'info, TokenAccount>,
+        from: &InterfaceAccount<'info, TokenAccount>,
+        to: &mut InterfaceAccount<'info, TokenAccount>,
+        mint: &InterfaceAccount<'info, Mint>,
         amount: u64,
         authority: &Signer<'info>,
-        token_program: &Program<'info, Token>,
-    ) -> Result<()> {
-        token::transfer(
+        token_program: &Interface<'info, TokenInterface>,
+    ) -> Result<u64> {
+        let balance_before = to.amount;
+
+        token_interface::transfer_checked(
             CpiContext::new(
                 token_program.to_account_info(),
-                token::Transfer {
+                token_interface::TransferChecked {
                     from: from.to_account_info(),
+                    mint: mint.to_account_info(),
                     to: to.to_account_info(),
                     authority: authority.to_account_info(),
                 },
             ),
             amount,
+            mint.decimals,
         )?;
-        Ok(())
+
+        to.reload()?;
+        to.amount
+            .checked_sub(balance_before)
+            .ok_or(error!(CustomError::OverflowOrUnderflowOccurred))
     }
 
     fn transfer_sol_from_pool(
         &self,
-        from: &mut AccountInfo<'info>,
+        from: &AccountInfo<'info>,
         to: &AccountInfo<'info>,
         amount: u64,
         bump: u8,
@@ -578,7 +1522,7 @@ impl<'info> LiquidityPoolAccount<'info> for Account<'info, LiquidityPool> {
     fn transfer_sol_to_pool(
         &self,
         from: &Signer<'info>,
-        to: &mut AccountInfo<'info>,
+        to: &AccountInfo<'info>,
         amount: u64,
         system_program: &Program<'info, System>,
     ) -> Result<()> {
@@ -596,4 +1540,472 @@ impl<'info> LiquidityPoolAccount<'info> for Account<'info, LiquidityPool> {
         )?;
         Ok(())
     }
+
+    fn fund_sol_vault_rent(
+        &self,
+        from: &Signer<'info>,
+        to: &AccountInfo<'info>,
+        system_program: &Program<'info, System>,
+    ) -> Result<()> {
+        let top_up = LiquidityPool::sol_vault_rent()?.saturating_sub(to.lamports());
+        if top_up > 0 {
+            self.transfer_sol_to_pool(from, to, top_up, system_program)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PoolStatus::*;
+    use PoolTransition::*;
+
+    const STATUSES: [PoolStatus; 6] = [Created, Funded, Trading, Graduated, Closed, Presale];
+    const TRANSITIONS: [PoolTransition; 6] = [Fund, OpenTrading, Graduate, Migrate, RemoveLiquidity, StartPresale];
+
+    #[test]
+    fn pool_lifecycle_follows_the_happy_path() {
+        let mut status = Created;
+        for transition in [Fund, OpenTrading, Graduate, Migrate] {
+            status = status.next(transition).unwrap();
+        }
+        assert_eq!(status, Closed);
+    }
+
+    #[test]
+    fn only_listed_transitions_are_legal() {
+        let legal = [
+            (Created, Fund),
+            (Funded, Fund),
+            (Funded, OpenTrading),
+            (Funded, StartPresale),
+            (Presale, OpenTrading),
+            (Presale, Graduate),
+            (Trading, Graduate),
+            (Graduated, Migrate),
+            (Funded, RemoveLiquidity),
+            (Trading, RemoveLiquidity),
+            (Graduated, RemoveLiquidity),
+            (Closed, RemoveLiquidity),
+        ];
+
+        for status in STATUSES {
+            for transition in TRANSITIONS {
+                assert_eq!(
+                    status.next(transition).is_ok(),
+                    legal.contains(&(status, transition)),
+                    "{:?} -> {:?}",
+                    status,
+                    transition
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn presales_only_start_on_funded_pools() {
+        for status in [Created, Trading, Graduated, Closed, Presale] {
+            assert_eq!(
+                status.next(StartPresale).unwrap_err(),
+                error!(CustomError::PresaleNotAllowed)
+            );
+        }
+    }
+
+    #[test]
+    fn closed_is_final() {
+        for transition in TRANSITIONS {
+            if let Ok(status) = Closed.next(transition) {
+                assert_eq!(status, Closed);
+            }
+        }
+    }
+
+    #[test]
+    fn fee_split_adds_up_to_the_fee() {
+        let mut config = CurveConfiguration::new(100, Pubkey::default());
+        assert_eq!(
+            config.split_fee(1_000, true).unwrap(),
+            FeeSplit { protocol: 1_000, creator: 0, referral: 0 }
+        );
+
+        config.creator_fees = 2_500;
+        config.referral_fees = 1_000;
+        assert_eq!(
+            config.split_fee(1_000, true).unwrap(),
+            FeeSplit { protocol: 650, creator: 250, referral: 100 }
+        );
+        // Without a referrer its share stays with the protocol
+        assert_eq!(
+            config.split_fee(1_000, false).unwrap(),
+            FeeSplit { protocol: 750, creator: 250, referral: 0 }
+        );
+        // Rounding never pays out more than the fee
+        assert_eq!(config.split_fee(3, true).unwrap(), FeeSplit { protocol: 3, creator: 0, referral: 0 });
+    }
+
+    #[test]
+    fn creator_and_referral_shares_are_capped_by_the_fee() {
+        let mut config = CurveConfiguration::new(100, Pubkey::default());
+        config.creator_fees = 6_000;
+        config.referral_fees = 4_000;
+        assert!(config.check_fee_split().is_ok());
+
+        config.referral_fees = 4_001;
+        assert!(config.check_fee_split().is_err());
+    }
+
+    #[test]
+    fn configuration_layout_survives_an_admin_handover() {
+        let admin = Pubkey::new_unique();
+        let new_admin = Pubkey::new_unique();
+        let mut config = CurveConfiguration::new(100, admin);
+        config.liquidity_lock_period = 42;
+        let mut data = vec![0_u8; CurveConfiguration::ACCOUNT_SIZE];
+
+        config.pending_admin = new_admin;
+        config.try_serialize(&mut &mut data[..]).unwrap();
+        config.admin = new_admin;
+        config.pending_admin = Pubkey::default();
+        config.try_serialize(&mut &mut data[..]).unwrap();
+
+        // Clearing the pending admin leaves no stale bytes behind
+        let mut handed_over = Vec::new();
+        config.try_serialize(&mut handed_over).unwrap();
+        assert_eq!(handed_over, data);
+
+        // Growing the account for a later version only appends zeros
+        data.resize(CurveConfiguration::ACCOUNT_SIZE + 64, 0);
+        let mut migrated = CurveConfiguration::try_deserialize(&mut &data[..]).unwrap();
+        migrated.migrate().unwrap();
+        assert_eq!(migrated.admin, new_admin);
+        assert_eq!(migrated.pending_admin, Pubkey::default());
+        assert_eq!(migrated.liquidity_lock_period, 42);
+
+        let mut reserialized = Vec::new();
+        migrated.try_serialize(&mut reserialized).unwrap();
+        assert_eq!(reserialized, handed_over);
+    }
+
+    #[test]
+    fn pools_only_graduate_once_there_is_an_amm() {
+        let mut config = CurveConfiguration::new(100, Pubkey::default());
+        assert!(!config.graduates_at(GRADUATION_THRESHOLD));
+
+        config.amm_program = Pubkey::new_unique();
+        assert!(!config.graduates_at(GRADUATION_THRESHOLD - 1));
+        assert!(config.graduates_at(GRADUATION_THRESHOLD));
+    }
+
+    #[test]
+    fn curves_are_held_to_the_configured_bounds() {
+        let linear = CurveKind::Linear { initial_price: 28, price_increment: 1, increment_supply: 1_000 };
+        let exponential = CurveKind::Exponential { initial_price: 28, growth_bps: 100, step_supply: 1_000 };
+
+        // Closed until the admin sets bounds
+        let mut config = CurveConfiguration::new(100, Pubkey::default());
+        assert!(config.check_curve(&linear).is_err());
+        assert!(config.check_curve(&exponential).is_err());
+        assert!(config.check_curve(&CurveKind::ConstantProduct).is_ok());
+
+        config.max_initial_price = 28;
+        config.max_price_increment = 1;
+        config.min_increment_supply = 1_000;
+        config.max_growth_bps = 100;
+        config.min_step_supply = 1_000;
+        assert!(config.check_curve(&linear).is_ok());
+        assert!(config.check_curve(&exponential).is_ok());
+
+        config.min_increment_supply = 1_001;
+        config.max_growth_bps = 99;
+        assert!(config.check_curve(&linear).is_err());
+        assert!(config.check_curve(&exponential).is_err());
+    }
+
+    #[test]
+    fn pool_and_global_pause_flags_add_up() {
+        let mut config = CurveConfiguration::new(100, Pubkey::default());
+        let mut pool = funded_pool(0, 0, 0);
+        assert!(pool.check_not_paused(&config, PauseScope::Buy).is_ok());
+
+        config.paused.sell = true;
+        pool.paused.buy = true;
+        assert!(pool.check_not_paused(&config, PauseScope::Buy).is_err());
+        assert!(pool.check_not_paused(&config, PauseScope::Sell).is_err());
+        assert!(pool.check_not_paused(&config, PauseScope::CreatePool).is_ok());
+        assert!(pool.check_not_paused(&config, PauseScope::RemoveLiquidity).is_ok());
+    }
+
+    #[test]
+    fn guardian_can_pause_but_not_unpause() {
+        let admin = Pubkey::new_unique();
+        let guardian = Pubkey::new_unique();
+        let mut config = CurveConfiguration::new(100, admin);
+        let none = PauseFlags::default();
+        let buys = PauseFlags { buy: true, ..none };
+
+        // Without a guardian only the admin may pause
+        assert!(config.check_pause_authority(guardian, none, buys).is_err());
+
+        config.guardian = guardian;
+        assert!(config.check_pause_authority(guardian, none, buys).is_ok());
+        assert!(config.check_pause_authority(guardian, buys, none).is_err());
+        assert!(config.check_pause_authority(admin, buys, none).is_ok());
+        assert!(config.check_pause_authority(Pubkey::new_unique(), none, buys).is_err());
+    }
+
+    #[test]
+    fn launch_protection_caps_buys_in_its_window() {
+        let mut pool = funded_pool(0, 0, 0);
+        let mut purchases = WalletPurchases { tokens: 0, sol: 0, presale_tokens: 0 };
+        let trade = TradeResult::new(400, 1_000, 4).unwrap();
+
+        // Unprotected pools take every buy
+        assert!(pool.record_protected_buy(0, &mut purchases, &trade).is_ok());
+        assert_eq!(purchases.tokens, 0);
+
+        pool.launch_protection =
+            Some(LaunchProtection { start_time: 100, window: 60, cap: BuyCap::Sol(1_000) });
+        assert!(pool.record_protected_buy(99, &mut purchases, &trade).is_err());
+
+        assert!(pool.record_protected_buy(100, &mut purchases, &trade).is_ok());
+        assert!(pool.record_protected_buy(159, &mut purchases, &trade).is_ok());
+        assert_eq!(purchases.sol, 800);
+        assert!(pool.record_protected_buy(159, &mut purchases, &trade).is_err());
+
+        // The cap lifts once the window closes
+        assert!(pool.record_protected_buy(160, &mut purchases, &trade).is_ok());
+    }
+
+    #[test]
+    fn purchases_are_only_tracked_while_a_limit_applies() {
+        let mut pool = funded_pool(0, 0, 0);
+        pool.status = Trading;
+        assert!(!pool.limits_purchases(0));
+
+        pool.launch_protection =
+            Some(LaunchProtection { start_time: 100, window: 60, cap: BuyCap::Sol(1_000) });
+        assert!(pool.limits_purchases(99));
+        assert!(pool.limits_purchases(159));
+        assert!(!pool.limits_purchases(160));
+
+        pool.status = Presale;
+        assert!(pool.limits_purchases(160));
+    }
+
+    #[test]
+    fn launch_fee_decays_to_the_flat_fee() {
+        let mut pool = funded_pool(0, 0, 0);
+        let config = CurveConfiguration::new(100, Pubkey::default());
+
+        assert!(pool.set_launch_fee(Some(LaunchFee { start_fee: 10_000, decay_slots: 100 }), u16::MAX).is_err());
+        assert!(pool.set_launch_fee(Some(LaunchFee { start_fee: 2_100, decay_slots: 0 }), u16::MAX).is_err());
+        assert!(pool.set_launch_fee(Some(LaunchFee { start_fee: 2_100, decay_slots: 100 }), 0).is_err());
+        assert!(pool.set_launch_fee(Some(LaunchFee { start_fee: 2_100, decay_slots: 100 }), 2_000).is_err());
+        pool.set_launch_fee(None, 0).unwrap();
+        pool.set_launch_fee(Some(LaunchFee { start_fee: 2_100, decay_slots: 100 }), 2_100).unwrap();
+
+        // Not charged until trading opens
+        assert_eq!(pool.fee_bps(&config, 500), 100);
+
+        pool.trading_opened_slot = 1_000;
+        assert_eq!(pool.fee_bps(&config, 1_000), 2_100);
+        assert_eq!(pool.fee_bps(&config, 1_025), 1_600);
+        assert_eq!(pool.fee_bps(&config, 1_099), 120);
+        assert_eq!(pool.fee_bps(&config, 1_100), 100);
+        assert_eq!(pool.fee_bps(&config, 5_000), 100);
+
+        // Never below the flat fee
+        pool.launch_fee = Some(LaunchFee { start_fee: 50, decay_slots: 100 });
+        assert_eq!(pool.fee_bps(&config, 1_000), 100);
+    }
+
+    #[test]
+    fn presale_allowlist_grants_the_allocation_in_the_leaf() {
+        let wallet = Pubkey::new_unique();
+        let other = allowlist_leaf(&Pubkey::new_unique(), 1);
+        let leaf = allowlist_leaf(&wallet, 500);
+        let (left, right) = if leaf <= other { (leaf, other) } else { (other, leaf) };
+        let root = anchor_lang::solana_program::hash::hashv(&[&[1], &left, &right]).to_bytes();
+
+        let presale = PresaleAccess { merkle_root: root, collection: Pubkey::default(), collection_allocation: 0 };
+        assert!(presale.check().is_ok());
+
+        let proof = PresaleProof::Allowlist { allocation: 500, proof: vec![other] };
+        assert_eq!(presale.allocation_for(&wallet, &proof, None).unwrap(), 500);
+
+        let inflated = PresaleProof::Allowlist { allocation: 5_000, proof: vec![other] };
+        assert!(presale.allocation_for(&wallet, &inflated, None).is_err());
+        assert!(presale.allocation_for(&Pubkey::new_unique(), &proof, None).is_err());
+        // Collection holders are not let in without a collection
+        assert!(presale.allocation_for(&wallet, &PresaleProof::Collection, None).is_err());
+    }
+
+    #[test]
+    fn presale_buys_are_held_to_the_allocation() {
+        let pool = funded_pool(0, 0, 0);
+        let mut purchases = WalletPurchases { tokens: 0, sol: 0, presale_tokens: 0 };
+        let trade = TradeResult::new(400, 300, 4).unwrap();
+
+        assert!(pool.record_presale_buy(&mut purchases.presale_tokens, 600, &trade).is_ok());
+        assert!(pool.record_presale_buy(&mut purchases.presale_tokens, 600, &trade).is_ok());
+        assert!(pool.record_presale_buy(&mut purchases.presale_tokens, 600, &trade).is_err());
+    }
+
+    #[test]
+    fn presale_needs_someone_to_let_in() {
+        let empty = PresaleAccess { merkle_root: [0; 32], collection: Pubkey::default(), collection_allocation: 0 };
+        assert!(empty.check().is_err());
+
+        let collection = PresaleAccess { collection: Pubkey::new_unique(), ..empty };
+        assert!(collection.check().is_err());
+        assert!(PresaleAccess { collection_allocation: 1, ..collection }.check().is_ok());
+    }
+
+    fn funded_pool(reserve_token: u64, reserve_sol: u64, total_shares: u64) -> LiquidityPool {
+        let mut pool = LiquidityPool::new(
+            Pubkey::default(),
+            Pubkey::default(),
+            0,
+            CurveKind::VirtualConstantProduct { virtual_sol: V_SOL_AMOUNT, virtual_token: V_TOKEN_AMOUNT },
+        );
+        pool.reserve_token = reserve_token;
+        pool.reserve_sol = reserve_sol;
+        pool.total_supply = reserve_token;
+        pool.total_shares = total_shares;
+        pool
+    }
+
+    #[test]
+    fn first_deposit_mints_one_share_per_token() {
+        let pool = funded_pool(0, 0, 0);
+        assert_eq!(pool.quote_deposit(1_000).unwrap(), (1_000, INITIAL_LAMPORTS_FOR_POOL));
+    }
+
+    #[test]
+    fn later_deposits_match_the_reserve_ratio() {
+        let pool = funded_pool(1_000, 10_000_000, 1_000);
+
+        // Half the reserve mints half the shares and owes half the SOL
+        assert_eq!(pool.quote_deposit(500).unwrap(), (500, 5_000_000));
+
+        // Rounding favours the providers already in
+        let pool = funded_pool(3, 10, 2);
+        assert_eq!(pool.quote_deposit(1).unwrap(), (0, 4));
+    }
+
+    #[test]
+    fn withdrawals_pay_out_a_fair_share() {
+        let mut pool = funded_pool(1_000, 10_000_000, 400);
+        pool.status = Funded;
+
+        assert_eq!(pool.quote_withdrawal(100, 0).unwrap(), (250, 2_500_000));
+        assert_eq!(pool.quote_withdrawal(400, 0).unwrap(), (1_000, 10_000_000));
+        assert!(pool.quote_withdrawal(401, 0).is_err());
+    }
+
+    #[test]
+    fn only_the_seed_comes_back_once_trading_opens() {
+        let mut pool = funded_pool(1_000, 30_000_000, 400);
+        pool.seed_sol = 10_000_000;
+        pool.status = Trading;
+        pool.unlock_at = 100;
+
+        // Locked until the unlock time, then only the seed is shared out
+        assert!(pool.quote_withdrawal(100, 99).is_err());
+        assert_eq!(pool.quote_withdrawal(100, 100).unwrap(), (0, 2_500_000));
+        assert_eq!(pool.quote_withdrawal(400, 100).unwrap(), (0, 10_000_000));
+
+        // Graduation releases it early, and it stays behind for the providers after migration
+        for status in [Graduated, Closed] {
+            pool.status = status;
+            assert_eq!(pool.quote_withdrawal(400, 0).unwrap(), (0, 10_000_000));
+        }
+
+        for status in [Created, Presale] {
+            pool.status = status;
+            assert!(pool.quote_withdrawal(100, 100).is_err());
+        }
+    }
+
+    #[test]
+    fn presale_buys_do_not_block_opening_trading() {
+        let mut pool = funded_pool(800_000_000 * TOKEN_UNIT, INITIAL_LAMPORTS_FOR_POOL, 1);
+        pool.status = Funded;
+        let supply = 800_000_000 * TOKEN_UNIT;
+
+        // Tokens held outside the pool, or only withheld fees making up the rest
+        assert!(pool.check_whole_supply(supply + 1, 0).is_err());
+        assert!(pool.check_whole_supply(supply + 1, 1).is_ok());
+        assert!(pool.check_whole_supply(supply, 0).is_ok());
+        pool.transition(PoolTransition::StartPresale).unwrap();
+
+        let bought = pool.bonding_curve().unwrap().tokens_for_sol(1_000_000_000).unwrap();
+        pool.reserve_sol += 1_000_000_000;
+        pool.reserve_token -= bought;
+        pool.tokens_sold += bought;
+
+        assert!(pool.check_whole_supply(supply, 0).is_ok());
+        assert!(pool.open_trading(1, 0).is_ok());
+    }
+
+    #[test]
+    fn legacy_pools_resume_trading_on_the_virtual_reserves() {
+        let legacy = LegacyLiquidityPool {
+            creator: Pubkey::new_unique(),
+            token: Pubkey::new_unique(),
+            total_supply: 1_000_000_000_000_000_000,
+            reserve_token: 900_000_000 * TOKEN_UNIT,
+            reserve_sol: 3_000_000_000,
+            bump: 254,
+        };
+        let pool = LiquidityPool::from_legacy(legacy, 1_000_000_000 * TOKEN_UNIT, 7).unwrap();
+
+        assert_eq!((pool.creator, pool.token, pool.bump), (legacy.creator, legacy.token, legacy.bump));
+        assert_eq!(pool.status, Trading);
+        assert_eq!(pool.tokens_sold, 100_000_000 * TOKEN_UNIT);
+        assert_eq!(pool.total_supply, pool.reserve_token + pool.tokens_sold);
+        assert_eq!((pool.total_shares, pool.seed_sol), (0, 0));
+        assert!(pool.check_trading().is_ok());
+
+        // More in the pool than was ever minted cannot be priced
+        assert!(LiquidityPool::from_legacy(legacy, 800_000_000 * TOKEN_UNIT, 7).is_err());
+    }
+
+    #[test]
+    fn selling_everything_back_leaves_the_vault_rent_exempt() {
+        let seed = INITIAL_LAMPORTS_FOR_POOL;
+        let rent = Rent::default().minimum_balance(0);
+        let mut pool = funded_pool(800_000_000 * TOKEN_UNIT, seed, 1);
+        let mut vault = rent + seed;
+
+        let bought = pool.bonding_curve().unwrap().tokens_for_sol(1_000_000_000).unwrap();
+        pool.reserve_sol += 1_000_000_000;
+        pool.reserve_token -= bought;
+        pool.tokens_sold += bought;
+        vault += 1_000_000_000;
+
+        pool.reserve_sol -= seed;
+        vault -= seed;
+
+        // The rounding left behind is far below the rent, which is kept out of the reserve
+        let proceeds = pool.bonding_curve().unwrap().proceeds_from_sell(bought).unwrap();
+        assert!(pool.reserve_sol - proceeds < rent);
+        assert!(proceeds <= vault.saturating_sub(rent));
+    }
+
+    #[test]
+    fn withdrawing_the_seed_keeps_sells_covered() {
+        let seed = INITIAL_LAMPORTS_FOR_POOL;
+        let mut pool = funded_pool(800_000_000 * TOKEN_UNIT, seed, 1);
+
+        let bought = pool.bonding_curve().unwrap().tokens_for_sol(5_000_000_000).unwrap();
+        pool.reserve_sol += 5_000_000_000;
+        pool.reserve_token -= bought;
+        pool.tokens_sold += bought;
+
+        pool.reserve_sol -= seed;
+        assert!(pool.bonding_curve().unwrap().proceeds_from_sell(bought).unwrap() <= pool.reserve_sol);
+    }
 }
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+// Leaf of a presale allowlist, committing to a wallet and the tokens it may buy
+pub fn allowlist_leaf(wallet: &Pubkey, allocation: u64) -> [u8; 32] {
+    hashv(&[&[0], wallet.as_ref(), &allocation.to_le_bytes()]).to_bytes()
+}
+
+// Whether `proof` links `leaf` to `root`. Pairs are hashed in sorted order so proofs
+// need no left/right flags, and leaves and nodes are hashed with different prefixes
+// so an inner node can never pass for a leaf.
+pub fn verify_merkle_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    let node = proof.iter().fold(leaf, |node, sibling| {
+        let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        hashv(&[&[1], &left, &right]).to_bytes()
+    });
+    node == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        hashv(&[&[1], &left, &right]).to_bytes()
+    }
+
+    #[test]
+    fn proofs_link_every_leaf_to_the_root() {
+        let wallets = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let leaves = [
+            allowlist_leaf(&wallets[0], 100),
+            allowlist_leaf(&wallets[1], 200),
+            allowlist_leaf(&wallets[2], 300),
+        ];
+        let pair = node(leaves[0], leaves[1]);
+        let root = node(pair, leaves[2]);
+
+        assert!(verify_merkle_proof(&root, leaves[0], &[leaves[1], leaves[2]]));
+        assert!(verify_merkle_proof(&root, leaves[1], &[leaves[0], leaves[2]]));
+        assert!(verify_merkle_proof(&root, leaves[2], &[pair]));
+
+        // Another allocation, another wallet, or the wrong siblings are all rejected
+        assert!(!verify_merkle_proof(&root, allowlist_leaf(&wallets[0], 101), &[leaves[1], leaves[2]]));
+        assert!(!verify_merkle_proof(&root, allowlist_leaf(&wallets[2], 100), &[leaves[1], leaves[2]]));
+        assert!(!verify_merkle_proof(&root, leaves[2], &[leaves[0]]));
+    }
+}
@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{
+    self,
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        instruction::AuthorityType,
+    },
+    spl_token_metadata_interface::state::TokenMetadata,
+    SetAuthority, TokenMetadataInitialize, TokenMetadataUpdateAuthority,
+};
+use spl_token_group_interface::state::TokenGroupMember;
+use crate::errors::CustomError;
+
+// Token-2022 mint extensions a pool can hold. Anything that lets a third party move,
+// freeze or hook into the pool's tokens (permanent delegate, transfer hook, default
+// frozen state, non transferable, confidential transfers...) is left out.
+pub const SUPPORTED_MINT_EXTENSIONS: [ExtensionType; 7] = [
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::TransferFeeConfig, // Reserves follow the received amounts, authorities must be revoked
+    ExtensionType::GroupPointer,
+    ExtensionType::GroupMemberPointer,
+    ExtensionType::TokenGroup,
+    ExtensionType::TokenGroupMember,
+];
+
+pub fn check_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    // Legacy SPL mints have no extensions
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    for extension in state.get_extension_types()? {
+        if !SUPPORTED_MINT_EXTENSIONS.contains(&extension) {
+            msg!("Unsupported mint extension: {:?}", extension);
+            return err!(CustomError::UnsupportedMintExtension);
+        }
+    }
+
+    // A fee authority could raise the fee on the pool's own transfers, and a withdraw
+    // authority could take the withheld fees back out as supply the pool does not hold
+    if let Ok(transfer_fee) = state.get_extension::<TransferFeeConfig>() {
+        if Option::<Pubkey>::from(transfer_fee.transfer_fee_config_authority).is_some()
+            || Option::<Pubkey>::from(transfer_fee.withdraw_withheld_authority).is_some()
+        {
+            return err!(CustomError::TransferFeeAuthoritySet);
+        }
+    }
+    Ok(())
+}
+
+// Group (collection) a Token-2022 mint is a member of. Only membership recorded in the mint
+// itself counts, the group's update authority signed it in, so it cannot be forged.
+pub fn token_group_of(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(state
+        .get_extension::<TokenGroupMember>()
+        .ok()
+        .filter(|member| member.mint == *mint.key)
+        .map(|member| member.group))
+}
+
+// Tokens to send so that at least `amount` arrives once the mint's transfer fee is withheld
+pub fn amount_before_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(amount);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let Ok(transfer_fee) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(amount);
+    };
+    let fee = transfer_fee
+        .calculate_inverse_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    amount
+        .checked_add(fee)
+        .ok_or(error!(CustomError::OverflowOrUnderflowOccurred))
+}
+
+// Transfer fees withheld in `token_account` or already harvested to `mint`. Harvesting is
+// open to anyone, so both are counted to keep the total from moving under the pool.
+pub fn withheld_transfer_fees(mint: &AccountInfo, token_account: &AccountInfo) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+
+    let harvested = {
+        let data = mint.try_borrow_data()?;
+        let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+        state
+            .get_extension::<TransferFeeConfig>()
+            .map_or(0, |config| u64::from(config.withheld_amount))
+    };
+    let data = token_account.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    let withheld = state
+        .get_extension::<TransferFeeAmount>()
+        .map_or(0, |fees| u64::from(fees.withheld_amount));
+
+    harvested
+        .checked_add(withheld)
+        .ok_or(error!(CustomError::OverflowOrUnderflowOccurred))
+}
+
+// Drops the mint and freeze authorities so the supply is fixed and holders cannot be frozen
+pub fn revoke_mint_authorities<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+) -> Result<()> {
+    for authority_type in [AuthorityType::MintTokens, AuthorityType::FreezeAccount] {
+        token_interface::set_authority(
+            CpiContext::new(
+                token_program.clone(),
+                SetAuthority {
+                    current_authority: authority.clone(),
+                    account_or_mint: mint.clone(),
+                },
+            ),
+            authority_type,
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+// Writes immutable name / symbol / uri metadata into a Token-2022 mint created with a
+// metadata pointer to itself. `authority` must be the mint authority and pays the rent
+// for the metadata the token program appends to the mint.
+pub fn initialize_token_metadata<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    let metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey(authority.key()),
+        mint: mint.key(),
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        additional_metadata: vec![],
+    };
+    let size = mint.data_len() + metadata.tlv_size_of()?;
+    let top_up = Rent::get()?.minimum_balance(size).saturating_sub(mint.lamports());
+
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                system_program::Transfer {
+                    from: authority.clone(),
+                    to: mint.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    token_interface::token_metadata_initialize(
+        CpiContext::new(
+            token_program.clone(),
+            TokenMetadataInitialize {
+                program_id: token_program.clone(),
+                metadata: mint.clone(),
+                update_authority: authority.clone(),
+                mint_authority: authority.clone(),
+                mint: mint.clone(),
+            },
+        ),
+        name,
+        symbol,
+        uri,
+    )?;
+
+    // Same as the non mutable Metaplex metadata used before
+    token_interface::token_metadata_update_authority(
+        CpiContext::new(
+            token_program.clone(),
+            TokenMetadataUpdateAuthority {
+                program_id: token_program,
+                metadata: mint,
+                current_authority: authority.clone(),
+                new_authority: authority,
+            },
+        ),
+        OptionalNonZeroPubkey::default(),
+    )?;
+
+    Ok(())
+}
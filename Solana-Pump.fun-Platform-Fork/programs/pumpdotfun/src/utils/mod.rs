@@ -1,2 +1,6 @@
 pub mod calc;
+pub mod merkle;
+pub mod mint;
 pub use calc::*;
+pub use merkle::*;
+pub use mint::*;
@@ -1,5 +1,5 @@
-import { Connection, Keypair, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAccount } from "@solana/spl-token";
+import { Connection, Keypair, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getAssociatedTokenAddressSync, getAccount } from "@solana/spl-token";
 import * as fs from "fs";
 
 // Program ID from deployment
@@ -43,20 +43,21 @@ async function main() {
           console.log("Account data length:", configAccount.data.length);
           
           // Try to parse basic data
-          if (configAccount.data.length >= 56) {
+          if (configAccount.data.length >= 59) {
             const dataView = new DataView(
               configAccount.data.buffer,
               configAccount.data.byteOffset,
               configAccount.data.byteLength
             );
             
-            // Skip 8-byte discriminator
-            const fees = dataView.getFloat64(8, true);
+            // Skip 8-byte discriminator and the legacy f64 fee
             const adminBytes = configAccount.data.slice(16, 48);
             const admin = new PublicKey(adminBytes);
+            // After legacy_shares (8) and version (1), in basis points
+            const fees = dataView.getUint16(57, true);
             
             console.log("\nConfiguration Details:");
-            console.log("- Fees:", fees);
+            console.log("- Fees:", fees / 100, "%");
             console.log("- Admin:", admin.toString());
           }
         } else {
@@ -133,8 +134,9 @@ async function main() {
           
           // Check user balance
           try {
-            const userATA = await getAssociatedTokenAddress(mintAddress, wallet.publicKey);
-            const tokenAccount = await getAccount(connection, userATA);
+            // The mint's owner is its token program, SPL Token or Token-2022
+            const userATA = getAssociatedTokenAddressSync(mintAddress, wallet.publicKey, false, mintInfo.owner);
+            const tokenAccount = await getAccount(connection, userATA, undefined, mintInfo.owner);
             console.log("Your balance:", Number(tokenAccount.amount) / 1e9, "tokens");
           } catch {
             console.log("You don't have a token account for this mint yet");
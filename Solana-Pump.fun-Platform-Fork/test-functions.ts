@@ -1,106 +1,13 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
-import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import * as fs from "fs";
+import { Pumpdotfun } from "./target/types/pumpdotfun";
+import * as client from "./client/client";
 
 // Program ID from deployment
 const PROGRAM_ID = new PublicKey("BhHzxiE9vYDM6d16DxAtqUvbxj6JZdxY7JsBxpjNfK14");
 
-// Token Metadata Program
-const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-
-// IDL - Interface Description Language (copy from target/idl/pumpdotfun.json if exists)
-const IDL = {
-  "version": "0.1.0",
-  "name": "pumpdotfun",
-  "instructions": [
-    {
-      "name": "initialize",
-      "accounts": [
-        { "name": "dexConfigurationAccount", "isMut": true, "isSigner": false },
-        { "name": "admin", "isMut": true, "isSigner": true },
-        { "name": "rent", "isMut": false, "isSigner": false },
-        { "name": "systemProgram", "isMut": false, "isSigner": false }
-      ],
-      "args": [
-        { "name": "fee", "type": "f64" }
-      ]
-    },
-    {
-      "name": "createToken",
-      "accounts": [
-        { "name": "payer", "isMut": true, "isSigner": true },
-        { "name": "mintAccount", "isMut": true, "isSigner": true },
-        { "name": "metadataAccount", "isMut": true, "isSigner": false },
-        { "name": "tokenAccount", "isMut": true, "isSigner": false },
-        { "name": "tokenMetadataProgram", "isMut": false, "isSigner": false },
-        { "name": "associatedTokenProgram", "isMut": false, "isSigner": false },
-        { "name": "tokenProgram", "isMut": false, "isSigner": false },
-        { "name": "systemProgram", "isMut": false, "isSigner": false },
-        { "name": "rent", "isMut": false, "isSigner": false }
-      ],
-      "args": [
-        { "name": "name", "type": "string" },
-        { "name": "symbol", "type": "string" },
-        { "name": "uri", "type": "string" },
-        { "name": "totalSupply", "type": "u64" }
-      ]
-    },
-    {
-      "name": "createPool",
-      "accounts": [
-        { "name": "dexConfigurationAccount", "isMut": true, "isSigner": false },
-        { "name": "pool", "isMut": true, "isSigner": false },
-        { "name": "tokenMint", "isMut": false, "isSigner": false },
-        { "name": "poolTokenAccount", "isMut": true, "isSigner": false },
-        { "name": "poolSolVault", "isMut": true, "isSigner": false },
-        { "name": "payer", "isMut": true, "isSigner": true },
-        { "name": "tokenProgram", "isMut": false, "isSigner": false },
-        { "name": "associatedTokenProgram", "isMut": false, "isSigner": false },
-        { "name": "rent", "isMut": false, "isSigner": false },
-        { "name": "systemProgram", "isMut": false, "isSigner": false }
-      ],
-      "args": []
-    },
-    {
-      "name": "buy",
-      "accounts": [
-        { "name": "dexConfigurationAccount", "isMut": true, "isSigner": false },
-        { "name": "pool", "isMut": true, "isSigner": false },
-        { "name": "tokenMint", "isMut": true, "isSigner": false },
-        { "name": "poolTokenAccount", "isMut": true, "isSigner": false },
-        { "name": "poolSolVault", "isMut": true, "isSigner": false },
-        { "name": "userTokenAccount", "isMut": true, "isSigner": false },
-        { "name": "user", "isMut": true, "isSigner": true },
-        { "name": "rent", "isMut": false, "isSigner": false },
-        { "name": "systemProgram", "isMut": false, "isSigner": false },
-        { "name": "tokenProgram", "isMut": false, "isSigner": false },
-        { "name": "associatedTokenProgram", "isMut": false, "isSigner": false }
-      ],
-      "args": [
-        { "name": "amount", "type": "u64" }
-      ]
-    },
-    {
-      "name": "sell",
-      "accounts": [
-        { "name": "dexConfigurationAccount", "isMut": true, "isSigner": false },
-        { "name": "pool", "isMut": true, "isSigner": false },
-        { "name": "tokenMint", "isMut": false, "isSigner": false },
-        { "name": "poolTokenAccount", "isMut": true, "isSigner": false },
-        { "name": "poolSolVault", "isMut": true, "isSigner": false },
-        { "name": "userTokenAccount", "isMut": true, "isSigner": false },
-        { "name": "user", "isMut": true, "isSigner": true },
-        { "name": "systemProgram", "isMut": false, "isSigner": false },
-        { "name": "tokenProgram", "isMut": false, "isSigner": false }
-      ],
-      "args": [
-        { "name": "amount", "type": "u64" }
-      ]
-    }
-  ]
-};
-
 async function main() {
   console.log("=== Pump.fun Fork Test Script ===\n");
 
@@ -119,8 +26,10 @@ async function main() {
   });
   anchor.setProvider(provider);
 
-  // Create program interface
-  const program = new Program(IDL as any, PROGRAM_ID, provider);
+  // Create program interface from the IDL generated by `anchor build`
+  const idl = JSON.parse(fs.readFileSync("./target/idl/pumpdotfun.json", "utf8"));
+  idl.address = PROGRAM_ID.toString();
+  const program = new Program(idl, provider) as Program<Pumpdotfun>;
 
   console.log("Wallet Address:", wallet.publicKey.toString());
   console.log("Program ID:", PROGRAM_ID.toString());
@@ -132,24 +41,15 @@ async function main() {
   try {
     // 1. Initialize System
     console.log("1. Initializing System...");
-    const [configPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("CurveConfiguration")],
-      PROGRAM_ID
-    );
+    const configPDA = client.configAddress(PROGRAM_ID);
 
     try {
       const configAccount = await program.account.curveConfiguration.fetch(configPDA);
       console.log("System already initialized. Config:", configAccount);
     } catch {
       console.log("Initializing new system...");
-      const initTx = await program.methods
-        .initialize(0.03) // 3% fee
-        .accounts({
-          dexConfigurationAccount: configPDA,
-          admin: wallet.publicKey,
-          rent: SYSVAR_RENT_PUBKEY,
-          systemProgram: SystemProgram.programId,
-        })
+      const initTx = await client
+        .initialize(program, wallet.publicKey, 300) // 3% fee
         .rpc();
       console.log("Initialize TX:", initTx);
       await new Promise(resolve => setTimeout(resolve, 2000));
@@ -160,42 +60,20 @@ async function main() {
     const mintKeypair = Keypair.generate();
     const mintAddress = mintKeypair.publicKey;
     
-    const [metadataAccount] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("metadata"),
-        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-        mintAddress.toBuffer(),
-      ],
-      TOKEN_METADATA_PROGRAM_ID
-    );
-
-    const tokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      wallet.publicKey
-    );
+    const tokenAccount = client.userTokenAccount(mintAddress, wallet.publicKey);
+    const totalSupply = new BN("1000000000000000"); // 1M tokens with 9 decimals
 
     console.log("Mint Address:", mintAddress.toString());
     console.log("Creating token: TestToken (TEST)");
 
-    const createTokenTx = await program.methods
+    const createTokenTx = await client
       .createToken(
-        "TestToken",
-        "TEST",
-        "https://example.com/metadata.json",
-        new BN("1000000000000000") // 1M tokens with 9 decimals
+        program,
+        wallet.publicKey,
+        mintKeypair,
+        { name: "TestToken", symbol: "TEST", uri: "https://example.com/metadata.json" },
+        totalSupply
       )
-      .accounts({
-        payer: wallet.publicKey,
-        mintAccount: mintAddress,
-        metadataAccount: metadataAccount,
-        tokenAccount: tokenAccount,
-        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-        rent: SYSVAR_RENT_PUBKEY,
-      })
-      .signers([mintKeypair])
       .rpc();
     
     console.log("Create Token TX:", createTokenTx);
@@ -204,60 +82,35 @@ async function main() {
 
     // 3. Create Pool
     console.log("\n3. Creating Liquidity Pool...");
-    const [poolPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_pool"), mintAddress.toBuffer()],
-      PROGRAM_ID
-    );
+    const createPoolTx = await client
+      .createPool(program, wallet.publicKey, mintAddress)
+      .rpc();
 
-    const poolTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      poolPDA,
-      true // allowOwnerOffCurve
-    );
+    console.log("Create Pool TX:", createPoolTx);
+    await new Promise(resolve => setTimeout(resolve, 2000));
 
-    const [poolSolVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("sol_vault"), mintAddress.toBuffer()],
-      PROGRAM_ID
-    );
+    // The whole supply goes in, trading cannot open with tokens held outside the pool
+    const addLiquidityTx = await client
+      .addLiquidity(program, wallet.publicKey, mintAddress, totalSupply, new BN("10000000")) // 0.01 SOL seed
+      .rpc();
 
-    const createPoolTx = await program.methods
-      .createPool()
-      .accounts({
-        dexConfigurationAccount: configPDA,
-        pool: poolPDA,
-        tokenMint: mintAddress,
-        poolTokenAccount: poolTokenAccount,
-        poolSolVault: poolSolVault,
-        payer: wallet.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        rent: SYSVAR_RENT_PUBKEY,
-        systemProgram: SystemProgram.programId,
-      })
+    console.log("Add Liquidity TX:", addLiquidityTx);
+    await new Promise(resolve => setTimeout(resolve, 2000));
+
+    const config = await program.account.curveConfiguration.fetch(configPDA);
+    const openTradingTx = await client
+      .openTrading(program, wallet.publicKey, mintAddress, config.liquidityLockPeriod)
       .rpc();
 
-    console.log("Create Pool TX:", createPoolTx);
+    console.log("Open Trading TX:", openTradingTx);
     await new Promise(resolve => setTimeout(resolve, 2000));
 
     // 4. Buy Tokens
     console.log("\n4. Buying Tokens...");
     const buyAmount = new BN("100000000"); // 0.1 SOL in lamports
 
-    const buyTx = await program.methods
-      .buy(buyAmount)
-      .accounts({
-        dexConfigurationAccount: configPDA,
-        pool: poolPDA,
-        tokenMint: mintAddress,
-        poolTokenAccount: poolTokenAccount,
-        poolSolVault: poolSolVault,
-        userTokenAccount: tokenAccount,
-        user: wallet.publicKey,
-        rent: SYSVAR_RENT_PUBKEY,
-        systemProgram: SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-      })
+    const buyTx = await client
+      .buy(program, wallet.publicKey, mintAddress, buyAmount, new BN(0))
       .rpc();
 
     console.log("Buy TX:", buyTx);
@@ -272,19 +125,8 @@ async function main() {
     console.log("\n5. Selling Tokens...");
     const sellAmount = new BN("1000000000"); // 1 TEST token with 9 decimals
 
-    const sellTx = await program.methods
-      .sell(sellAmount)
-      .accounts({
-        dexConfigurationAccount: configPDA,
-        pool: poolPDA,
-        tokenMint: mintAddress,
-        poolTokenAccount: poolTokenAccount,
-        poolSolVault: poolSolVault,
-        userTokenAccount: tokenAccount,
-        user: wallet.publicKey,
-        systemProgram: SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      })
+    const sellTx = await client
+      .sell(program, wallet.publicKey, mintAddress, sellAmount, new BN(0))
       .rpc();
 
     console.log("Sell TX:", sellTx);
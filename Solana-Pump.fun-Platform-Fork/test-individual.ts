@@ -1,33 +1,14 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
-import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import * as fs from "fs";
+import { Pumpdotfun } from "./target/types/pumpdotfun";
+import * as client from "./client/client";
 
 // Program ID from deployment
 const PROGRAM_ID = new PublicKey("BhHzxiE9vYDM6d16DxAtqUvbxj6JZdxY7JsBxpjNfK14");
 
-// Token Metadata Program
-const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-
-// Simple IDL
-const IDL = {
-  "version": "0.1.0",
-  "name": "pumpdotfun",
-  "accounts": [
-    {
-      "name": "curveConfiguration",
-      "type": {
-        "kind": "struct",
-        "fields": [
-          { "name": "fees", "type": "f64" },
-          { "name": "admin", "type": "publicKey" },
-          { "name": "shares", "type": "u64" }
-        ]
-      }
-    }
-  ]
-};
-
 async function main() {
   const args = process.argv.slice(2);
   const command = args[0];
@@ -49,8 +30,10 @@ async function main() {
   });
   anchor.setProvider(provider);
 
-  // Create program interface
-  const program = new Program(IDL as any, PROGRAM_ID, provider);
+  // Create program interface from the IDL generated by `anchor build`
+  const idl = JSON.parse(fs.readFileSync("./target/idl/pumpdotfun.json", "utf8"));
+  idl.address = PROGRAM_ID.toString();
+  const program = new Program(idl, provider) as Program<Pumpdotfun>;
 
   console.log("Wallet Address:", wallet.publicKey.toString());
   console.log("Program ID:", PROGRAM_ID.toString());
@@ -63,10 +46,7 @@ async function main() {
     switch(command) {
       case "check":
         console.log("Checking system status...");
-        const [configPDA] = PublicKey.findProgramAddressSync(
-          [Buffer.from("CurveConfiguration")],
-          PROGRAM_ID
-        );
+        const configPDA = client.configAddress(PROGRAM_ID);
         
         try {
           const configAccount = await connection.getAccountInfo(configPDA);
@@ -75,17 +55,15 @@ async function main() {
             console.log("Config PDA:", configPDA.toString());
             console.log("Account data length:", configAccount.data.length);
             
-            // Parse the data manually
-            const dataView = new DataView(configAccount.data.buffer, configAccount.data.byteOffset, configAccount.data.byteLength);
-            const discriminator = configAccount.data.slice(0, 8);
-            const fees = dataView.getFloat64(8, true); // little-endian
-            const admin = new PublicKey(configAccount.data.slice(16, 48));
-            const shares = dataView.getBigUint64(48, true);
+            const config = await program.account.curveConfiguration.fetch(configPDA);
+            const feeVault = await program.account.protocolFeeVault.fetch(
+              client.protocolFeeVaultAddress(PROGRAM_ID)
+            );
             
             console.log("\nConfiguration:");
-            console.log("- Fees:", fees);
-            console.log("- Admin:", admin.toString());
-            console.log("- Shares:", shares.toString());
+            console.log("- Fees:", config.fees / 100, "%");
+            console.log("- Admin:", config.admin.toString());
+            console.log("- Accrued protocol fees:", feeVault.fees.toString());
           } else {
             console.log("System not initialized yet");
           }
@@ -104,36 +82,15 @@ async function main() {
         console.log("New Mint Address:", mintAddress.toString());
         console.log("Save this address for pool creation!");
         
-        // Create instruction manually
-        const createTokenIx = await program.instruction.createToken(
-          "TestToken",
-          "TEST",
-          "https://example.com/metadata.json",
-          new BN("1000000000000000"), // 1M tokens with 9 decimals
-          {
-            accounts: {
-              payer: wallet.publicKey,
-              mintAccount: mintAddress,
-              metadataAccount: PublicKey.findProgramAddressSync(
-                [
-                  Buffer.from("metadata"),
-                  TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-                  mintAddress.toBuffer(),
-                ],
-                TOKEN_METADATA_PROGRAM_ID
-              )[0],
-              tokenAccount: await getAssociatedTokenAddress(mintAddress, wallet.publicKey),
-              tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
-              associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-              tokenProgram: TOKEN_PROGRAM_ID,
-              systemProgram: SystemProgram.programId,
-              rent: SYSVAR_RENT_PUBKEY,
-            },
-          }
-        );
-
-        const tx = new anchor.web3.Transaction().add(createTokenIx);
-        const signature = await provider.sendAndConfirm(tx, [mintKeypair]);
+        const signature = await client
+          .createToken(
+            program,
+            wallet.publicKey,
+            mintKeypair,
+            { name: "TestToken", symbol: "TEST", uri: "https://example.com/metadata.json" },
+            new BN("1000000000000000") // 1M tokens with 9 decimals
+          )
+          .rpc();
         
         console.log("Token created successfully!");
         console.log("Transaction:", signature);
@@ -149,52 +106,46 @@ async function main() {
         const mintAddress2 = new PublicKey(args[1]);
         console.log("Creating pool for mint:", mintAddress2.toString());
         
-        const [poolPDA] = PublicKey.findProgramAddressSync(
-          [Buffer.from("liquidity_pool"), mintAddress2.toBuffer()],
-          PROGRAM_ID
-        );
-        
-        const [poolSolVault] = PublicKey.findProgramAddressSync(
-          [Buffer.from("sol_vault"), mintAddress2.toBuffer()],
-          PROGRAM_ID
-        );
-        
-        const [configPDA2] = PublicKey.findProgramAddressSync(
-          [Buffer.from("CurveConfiguration")],
-          PROGRAM_ID
-        );
-        
-        const poolTokenAccount = await getAssociatedTokenAddress(
-          mintAddress2,
-          poolPDA,
-          true // allowOwnerOffCurve
-        );
+        const tokenProgram2 = await client.mintTokenProgram(connection, mintAddress2);
+        const { pool: poolPDA, poolTokenAccount, poolSolVault } = client.poolAddresses(PROGRAM_ID, mintAddress2, tokenProgram2);
         
         console.log("Pool PDA:", poolPDA.toString());
         console.log("Pool Token Account:", poolTokenAccount.toString());
         console.log("Pool SOL Vault:", poolSolVault.toString());
         
-        // Create pool instruction
-        const createPoolIx = await program.instruction.createPool({
-          accounts: {
-            dexConfigurationAccount: configPDA2,
-            pool: poolPDA,
-            tokenMint: mintAddress2,
-            poolTokenAccount: poolTokenAccount,
-            poolSolVault: poolSolVault,
-            payer: wallet.publicKey,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-            rent: SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-          },
-        });
-        
-        const tx2 = new anchor.web3.Transaction().add(createPoolIx);
-        const signature2 = await provider.sendAndConfirm(tx2);
+        const signature2 = await client.createPool(program, wallet.publicKey, mintAddress2, client.DEFAULT_CURVE, tokenProgram2).rpc();
         
         console.log("Pool created successfully!");
         console.log("Transaction:", signature2);
+        
+        // Deposit the whole supply, trading cannot open with tokens held outside the pool
+        const creatorTokens = await getAccount(
+          connection,
+          client.userTokenAccount(mintAddress2, wallet.publicKey, tokenProgram2),
+          undefined,
+          tokenProgram2
+        );
+        const signature3 = await client
+          .addLiquidity(
+            program,
+            wallet.publicKey,
+            mintAddress2,
+            new BN(creatorTokens.amount.toString()),
+            new BN("10000000"), // 0.01 SOL seed
+            tokenProgram2
+          )
+          .rpc();
+        
+        console.log("Liquidity added!");
+        console.log("Transaction:", signature3);
+        
+        const config2 = await program.account.curveConfiguration.fetch(client.configAddress(PROGRAM_ID));
+        const signature4 = await client
+          .openTrading(program, wallet.publicKey, mintAddress2, config2.liquidityLockPeriod, tokenProgram2)
+          .rpc();
+        
+        console.log("Trading opened!");
+        console.log("Transaction:", signature4);
         break;
 
       case "buy":
@@ -206,48 +157,19 @@ async function main() {
         const mintForBuy = new PublicKey(args[1]);
         const solAmount = args[2] ? parseFloat(args[2]) : 0.1;
         const lamports = new BN(solAmount * 1e9);
+        const tokenProgramForBuy = await client.mintTokenProgram(connection, mintForBuy);
         
         console.log(`Buying tokens with ${solAmount} SOL...`);
         
-        const [poolForBuy] = PublicKey.findProgramAddressSync(
-          [Buffer.from("liquidity_pool"), mintForBuy.toBuffer()],
-          PROGRAM_ID
-        );
-        
-        const [configForBuy] = PublicKey.findProgramAddressSync(
-          [Buffer.from("CurveConfiguration")],
-          PROGRAM_ID
-        );
-        
-        const [solVaultForBuy] = PublicKey.findProgramAddressSync(
-          [Buffer.from("sol_vault"), mintForBuy.toBuffer()],
-          PROGRAM_ID
-        );
-        
-        const buyIx = await program.instruction.buy(lamports, {
-          accounts: {
-            dexConfigurationAccount: configForBuy,
-            pool: poolForBuy,
-            tokenMint: mintForBuy,
-            poolTokenAccount: await getAssociatedTokenAddress(mintForBuy, poolForBuy, true),
-            poolSolVault: solVaultForBuy,
-            userTokenAccount: await getAssociatedTokenAddress(mintForBuy, wallet.publicKey),
-            user: wallet.publicKey,
-            rent: SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          },
-        });
-        
-        const txBuy = new anchor.web3.Transaction().add(buyIx);
-        const sigBuy = await provider.sendAndConfirm(txBuy);
+        const sigBuy = await client
+          .buy(program, wallet.publicKey, mintForBuy, lamports, new BN(0), { tokenProgram: tokenProgramForBuy })
+          .rpc();
         
         console.log("Buy successful!");
         console.log("Transaction:", sigBuy);
         
         // Check balance
-        const userTokenAccount = await getAssociatedTokenAddress(mintForBuy, wallet.publicKey);
+        const userTokenAccount = client.userTokenAccount(mintForBuy, wallet.publicKey, tokenProgramForBuy);
         const tokenBalance = await connection.getTokenAccountBalance(userTokenAccount);
         console.log("Your token balance:", tokenBalance.value.uiAmount, "tokens");
         break;
@@ -256,7 +178,7 @@ async function main() {
         console.log("Available commands:");
         console.log("  check                     - Check system status");
         console.log("  create-token             - Create a new token");
-        console.log("  create-pool <MINT>       - Create pool for token");
+        console.log("  create-pool <MINT>       - Create pool for token, seed it and open trading");
         console.log("  buy <MINT> [SOL_AMOUNT]  - Buy tokens (default 0.1 SOL)");
         console.log("\nExample:");
         console.log("  npx ts-node test-individual.ts check");
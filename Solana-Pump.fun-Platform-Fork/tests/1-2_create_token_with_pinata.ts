@@ -2,13 +2,9 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { BN } from "bn.js";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
-import { PublicKey, SystemProgram, LAMPORTS_PER_SOL, Keypair } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID,
-  getAssociatedTokenAddress,
-  getAccount
-} from "@solana/spl-token";
+import * as client from "../client/client";
+import { LAMPORTS_PER_SOL, Keypair } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getAccount } from "@solana/spl-token";
 import * as fs from "fs";
 import * as os from "os";
 import * as path from "path";
@@ -110,42 +106,19 @@ async function createTokenWithPinata() {
     console.log("  ", mint.toString());
     
     // Derive PDAs
-    const [dexConfigPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("CurveConfiguration")],
-      program.programId
-    );
+    const dexConfigPDA = client.configAddress(program.programId);
     
-    const [pool] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_pool"), mint.toBuffer()],
-      program.programId
-    );
+    const { pool } = client.poolAddresses(program.programId, mint);
     
     // User's token account for the token being created
-    const creatorTokenAccount = await getAssociatedTokenAddress(
-      mint,
-      creator.publicKey
-    );
+    const creatorTokenAccount = client.userTokenAccount(mint, creator.publicKey);
     
-    const userTokenAccount = await getAssociatedTokenAddress(
-      mint,
-      creator.publicKey
-    );
+    const userTokenAccount = client.userTokenAccount(mint, creator.publicKey);
     
-    // Derive metadata PDA (Metaplex standard)
-    const metadataProgramId = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-    const [metadata] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("metadata"),
-        metadataProgramId.toBuffer(),
-        mint.toBuffer(),
-      ],
-      metadataProgramId
-    );
     
     console.log("\n📍 Derived Addresses:");
     console.log("  DEX Config:", dexConfigPDA.toString());
     console.log("  Pool (for future):", pool.toString());
-    console.log("  Metadata:", metadata.toString());
     
     // Check if DEX config is initialized
     try {
@@ -154,13 +127,8 @@ async function createTokenWithPinata() {
     } catch (e) {
       console.log("\n⚠️ DEX config not initialized, initializing now...");
       try {
-        const initTx = await program.methods
-          .initialize(0.01) // 1% fee
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            admin: creator.publicKey,
-            systemProgram: SystemProgram.programId,
-          } as any)
+        const initTx = await client
+          .initialize(program, creator.publicKey, 100) // 1% fee in basis points
           .rpc();
         console.log("✅ DEX config initialized. Tx:", initTx);
       } catch (initError) {
@@ -175,20 +143,8 @@ async function createTokenWithPinata() {
     // Use string to avoid JavaScript number precision issues
     const TOTAL_SUPPLY = new BN("1000000000000000000"); // 1 billion tokens with 9 decimals (1e18)
     
-    const tx = await program.methods
-      .createToken(tokenName, tokenSymbol, tokenUri, TOTAL_SUPPLY)
-      .accounts({
-        payer: creator.publicKey,
-        mintAccount: mint,
-        metadataAccount: metadata,
-        tokenAccount: creatorTokenAccount,
-        tokenMetadataProgram: metadataProgramId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-      } as any)
-      .signers([mintKeypair])
+    const tx = await client
+      .createToken(program, creator.publicKey, mintKeypair, { name: tokenName, symbol: tokenSymbol, uri: tokenUri }, TOTAL_SUPPLY)
       .rpc();
     
     // Display results
@@ -221,7 +177,7 @@ async function createTokenWithPinata() {
     
     // Check creator's token balance
     try {
-      const creatorTokenAccountInfo = await getAccount(connection as any, userTokenAccount);
+      const creatorTokenAccountInfo = await getAccount(connection as any, userTokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
       const creatorBalance = Number(creatorTokenAccountInfo.amount);
       console.log("\n👤 Creator Token Balance:");
       console.log("  ", creatorBalance / Math.pow(10, 9), "tokens");
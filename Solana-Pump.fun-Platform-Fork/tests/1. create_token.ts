@@ -2,13 +2,9 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { BN } from "bn.js";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
-import { PublicKey, SystemProgram, LAMPORTS_PER_SOL, Keypair } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID,
-  getAssociatedTokenAddress,
-  getAccount
-} from "@solana/spl-token";
+import * as client from "../client/client";
+import { LAMPORTS_PER_SOL, Keypair } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getAccount } from "@solana/spl-token";
 import * as fs from "fs";
 import * as os from "os";
 import * as path from "path";
@@ -79,44 +75,21 @@ async function createToken() {
   console.log("  ", mint.toString());
   
   // Derive PDAs
-  const [dexConfigPDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from("CurveConfiguration")],
-    program.programId
-  );
+  const dexConfigPDA = client.configAddress(program.programId);
   
-  const [pool] = PublicKey.findProgramAddressSync(
-    [Buffer.from("liquidity_pool"), mint.toBuffer()],
-    program.programId
-  );
+  const { pool } = client.poolAddresses(program.programId, mint);
   
   // User's token account for the token being created
-  const creatorTokenAccount = await getAssociatedTokenAddress(
-    mint,
-    creator.publicKey
-  );
+  const creatorTokenAccount = client.userTokenAccount(mint, creator.publicKey);
   
-  const userTokenAccount = await getAssociatedTokenAddress(
-    mint,
-    creator.publicKey
-  );
+  const userTokenAccount = client.userTokenAccount(mint, creator.publicKey);
   
-  // Derive metadata PDA (Metaplex standard)
-  const metadataProgramId = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-  const [metadata] = PublicKey.findProgramAddressSync(
-    [
-      Buffer.from("metadata"),
-      metadataProgramId.toBuffer(),
-      mint.toBuffer(),
-    ],
-    metadataProgramId
-  );
   
   console.log("\n📍 Derived Addresses:");
   console.log("  DEX Config:", dexConfigPDA.toString());
   console.log("  Pool (for future):", pool.toString());
   console.log("  Creator Token Account:", creatorTokenAccount.toString());
   console.log("  User Token Account:", userTokenAccount.toString());
-  console.log("  Metadata:", metadata.toString());
   
   // Check if DEX config is initialized
   try {
@@ -125,13 +98,8 @@ async function createToken() {
   } catch (e) {
     console.log("\n⚠️ DEX config not initialized, initializing now...");
     try {
-      const initTx = await program.methods
-        .initialize(0.01) // 1% fee
-        .accounts({
-          dexConfigurationAccount: dexConfigPDA,
-          admin: creator.publicKey,
-          systemProgram: SystemProgram.programId,
-        })
+      const initTx = await client
+        .initialize(program, creator.publicKey, 100) // 1% fee in basis points
         .rpc();
       console.log("✅ DEX config initialized. Tx:", initTx);
     } catch (initError) {
@@ -147,20 +115,8 @@ async function createToken() {
   const TOTAL_SUPPLY = new BN("1000000000000000000"); // 1 billion tokens with 9 decimals (1e18)
   
   try {
-    const tx = await program.methods
-      .createToken(tokenName, tokenSymbol, tokenUri, TOTAL_SUPPLY)
-      .accounts({
-        payer: creator.publicKey,
-        mintAccount: mint,
-        metadataAccount: metadata,
-        tokenAccount: creatorTokenAccount,
-        tokenMetadataProgram: metadataProgramId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-      })
-      .signers([mintKeypair])
+    const tx = await client
+      .createToken(program, creator.publicKey, mintKeypair, { name: tokenName, symbol: tokenSymbol, uri: tokenUri }, TOTAL_SUPPLY)
       .rpc();
     
     console.log("\n✅ Token created successfully!");
@@ -177,7 +133,7 @@ async function createToken() {
     
     // Check creator's token balance
     try {
-      const creatorTokenAccount = await getAccount(connection as any, userTokenAccount);
+      const creatorTokenAccount = await getAccount(connection as any, userTokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
       const creatorBalance = Number(creatorTokenAccount.amount);
       console.log("\n👤 Creator Token Balance:");
       console.log("  ", creatorBalance / Math.pow(10, 9), "tokens");
@@ -2,14 +2,9 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { BN } from "bn.js";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
-import { SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID, 
-  getAssociatedTokenAddress,
-  getAccount,
-  createTransferInstruction
-} from "@solana/spl-token";
+import * as client from "../client/client";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getAccount, getMint } from "@solana/spl-token";
 import * as fs from "fs";
 import * as os from "os";
 import * as path from "path";
@@ -60,9 +55,15 @@ async function createLiquidityPool() {
   // Token address to use
   const mintAddress = new PublicKey("H9tMusabLgceRCo8QJp1DxTbbnmccYTUHDUiGHuLvMuM");
   
-  // Liquidity settings
-  const TOKEN_AMOUNT = new BN("100000000000000"); // 100,000 tokens (9 decimals)
+  // Pools created before Token-2022 launches hold legacy SPL Token mints
+  const tokenProgram = await client.mintTokenProgram(connection, mintAddress);
+  
+  // Liquidity settings, the pool must hold the whole supply before trading opens
+  const mintInfo = await getMint(connection, mintAddress, undefined, tokenProgram);
+  const TOKEN_AMOUNT = new BN(mintInfo.supply.toString());
   const SOL_AMOUNT = new BN(1 * LAMPORTS_PER_SOL); // 1 SOL
+  // How long in seconds the seed stays locked once trading opens
+  const LOCK_PERIOD = new BN(7 * 24 * 60 * 60);
   
   console.log("Program ID:", program.programId.toString());
   console.log("Provider:", payer.publicKey.toString());
@@ -70,26 +71,8 @@ async function createLiquidityPool() {
   console.log("=====================================\n");
   
   // Derive PDA accounts
-  const [pool] = PublicKey.findProgramAddressSync(
-    [Buffer.from("liquidity_pool"), mintAddress.toBuffer()],
-    program.programId
-  );
-  
-  const poolTokenAccount = await getAssociatedTokenAddress(
-    mintAddress,
-    pool,
-    true // allowOwnerOffCurve
-  );
-  
-  const [poolSolVault] = PublicKey.findProgramAddressSync(
-    [Buffer.from("liquidity_sol_vault"), mintAddress.toBuffer()],
-    program.programId
-  );
-  
-  const userTokenAccount = await getAssociatedTokenAddress(
-    mintAddress,
-    payer.publicKey
-  );
+  const { pool, poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAddress, tokenProgram);
+  const userTokenAccount = client.userTokenAccount(mintAddress, payer.publicKey, tokenProgram);
   
   console.log("📍 Derived Accounts:");
   console.log("  Pool PDA:", pool.toString());
@@ -103,7 +86,7 @@ async function createLiquidityPool() {
   
   try {
     // Check user token balance
-    const userTokenAccountInfo = await getAccount(connection, userTokenAccount);
+    const userTokenAccountInfo = await getAccount(connection, userTokenAccount, undefined, tokenProgram);
     console.log("👤 User token balance:", userTokenAccountInfo.amount.toString());
     
     // Check user SOL balance
@@ -145,19 +128,8 @@ async function createLiquidityPool() {
     try {
       console.log("🚀 Creating liquidity pool...");
       
-      const tx = await program.methods
-        .createPool()
-        .accounts({
-          pool,
-          tokenMint: mintAddress,
-          poolTokenAccount,
-          payer: payer.publicKey,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-          systemProgram: SystemProgram.programId,
-        })
-        .rpc();
+      // The default configuration only accepts the original virtual reserves
+      const tx = await client.createPool(program, payer.publicKey, mintAddress, client.DEFAULT_CURVE, tokenProgram).rpc();
       
       console.log("✅ Transaction:", tx);
       console.log("🔍 View on Solana Explorer:");
@@ -194,87 +166,35 @@ async function createLiquidityPool() {
   // Provide initial liquidity
   console.log("\n========== PROVIDE INITIAL LIQUIDITY ==========");
   
-  // Transfer tokens to pool
-  console.log("\n💸 Transferring tokens to pool...");
-  console.log("  Amount:", TOKEN_AMOUNT.toString(), "(100,000 tokens)");
+  // add_liquidity moves the tokens and SOL into the pool itself
+  console.log("\n💸 Adding liquidity...");
+  console.log("  Tokens:", TOKEN_AMOUNT.toString(), "(the whole supply)");
+  console.log("  SOL:", SOL_AMOUNT.toNumber() / LAMPORTS_PER_SOL, "SOL");
   
   try {
-    // Create transfer instruction
-    const transferTokenIx = createTransferInstruction(
-      userTokenAccount,
-      poolTokenAccount,
-      payer.publicKey,
-      BigInt(TOKEN_AMOUNT.toString()),
-      [],
-      TOKEN_PROGRAM_ID
-    );
-    
-    // Send transaction
-    const tokenTx = await provider.sendAndConfirm(
-      new anchor.web3.Transaction().add(transferTokenIx)
-    );
-    
-    console.log("✅ Token transfer transaction:", tokenTx);
-    
-    // Verify transfer
-    const poolTokenAccountInfo = await getAccount(connection, poolTokenAccount);
-    console.log("📊 Pool token balance:", poolTokenAccountInfo.amount.toString());
-    
-  } catch (error) {
-    console.error("❌ Failed to transfer tokens:", error.message);
-  }
-  
-  // Transfer SOL to pool
-  console.log("\n💸 Transferring SOL to pool...");
-  console.log("  Amount:", SOL_AMOUNT.toNumber() / LAMPORTS_PER_SOL, "SOL");
-  
-  try {
-    // SOL transfer instruction
-    const transferSolIx = SystemProgram.transfer({
-      fromPubkey: payer.publicKey,
-      toPubkey: poolSolVault,
-      lamports: SOL_AMOUNT.toNumber(),
-    });
-    
-    const solTx = await provider.sendAndConfirm(
-      new anchor.web3.Transaction().add(transferSolIx)
-    );
-    
-    console.log("✅ SOL transfer transaction:", solTx);
+    const tx = await client
+      .addLiquidity(program, payer.publicKey, mintAddress, TOKEN_AMOUNT, SOL_AMOUNT, tokenProgram)
+      .rpc();
     
-    // Verify transfer
-    const poolSolBalance = await connection.getBalance(poolSolVault);
-    console.log("📊 Pool SOL balance:", poolSolBalance / LAMPORTS_PER_SOL, "SOL");
+    console.log("✅ Add liquidity transaction:", tx);
     
   } catch (error) {
-    console.error("❌ Failed to transfer SOL:", error.message);
+    console.error("❌ Failed to add liquidity:", error.message);
   }
   
-  // Register liquidity
-  console.log("\n📝 Calling add_liquidity to register liquidity...");
+  // Open trading
+  console.log("\n🔓 Opening trading...");
+  console.log("  Seed locked for:", LOCK_PERIOD.toString(), "seconds");
   
   try {
-    const tx = await program.methods
-      .addLiquidity()
-      .accounts({
-        pool,
-        tokenMint: mintAddress,
-        poolTokenAccount,
-        userTokenAccount,
-        poolSolVault,
-        user: payer.publicKey,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-        systemProgram: SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-      })
+    const tx = await client
+      .openTrading(program, payer.publicKey, mintAddress, LOCK_PERIOD, tokenProgram)
       .rpc();
     
-    console.log("✅ Liquidity registration transaction:", tx);
+    console.log("✅ Open trading transaction:", tx);
     
   } catch (error) {
-    console.log("⚠️ Note: Tokens and SOL have been transferred manually.");
-    console.log("The add_liquidity call might require specific conditions.");
+    console.error("❌ Failed to open trading:", error.message);
   }
   
   // Final state
@@ -283,7 +203,7 @@ async function createLiquidityPool() {
   try {
     // Check final pool balances
     const poolSolBalance = await connection.getBalance(poolSolVault);
-    const poolTokenAccountInfo = await getAccount(connection, poolTokenAccount);
+    const poolTokenAccountInfo = await getAccount(connection, poolTokenAccount, undefined, tokenProgram);
     
     console.log("🏊 Final Pool Balances:");
     console.log("  SOL:", poolSolBalance / LAMPORTS_PER_SOL, "SOL");
@@ -298,7 +218,7 @@ async function createLiquidityPool() {
     
     // User remaining balances
     const userSolBalance = await connection.getBalance(payer.publicKey);
-    const userTokenAccountInfo = await getAccount(connection, userTokenAccount);
+    const userTokenAccountInfo = await getAccount(connection, userTokenAccount, undefined, tokenProgram);
     
     console.log("\n👤 User Remaining Balances:");
     console.log("  SOL:", userSolBalance / LAMPORTS_PER_SOL, "SOL");
@@ -2,13 +2,9 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { BN } from "bn.js";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
-import { SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  getAssociatedTokenAddress,
-  getAccount,
-  getMint
-} from "@solana/spl-token";
+import * as client from "../client/client";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getAccount, getMint } from "@solana/spl-token";
 import * as fs from "fs";
 import * as os from "os";
 import * as path from "path";
@@ -78,10 +74,12 @@ async function swapTokensForSol() {
     
     // Get token mint address from pool
     const mintAddress = poolAccount.token;
+    // Pools created before Token-2022 launches hold legacy SPL Token mints
+    const tokenProgram = await client.mintTokenProgram(connection, mintAddress);
     
     // Fetch mint info to get decimals
     console.log("\n📊 Fetching token mint info...");
-    const mintInfo = await getMint(connection, mintAddress);
+    const mintInfo = await getMint(connection, mintAddress, undefined, tokenProgram);
     const TOKEN_DECIMALS = mintInfo.decimals;
     console.log("Token Decimals:", TOKEN_DECIMALS);
     
@@ -93,26 +91,9 @@ async function swapTokensForSol() {
     console.log("Sell Amount (decimal):", SELL_AMOUNT_TOKENS, "tokens");
     
     // Derive necessary PDAs
-    const [dexConfigPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("CurveConfiguration")],
-      program.programId
-    );
-    
-    const poolTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      poolAddress,
-      true // allowOwnerOffCurve
-    );
-    
-    const [poolSolVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_sol_vault"), mintAddress.toBuffer()],
-      program.programId
-    );
-    
-    const userTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      user.publicKey
-    );
+    const dexConfigPDA = client.configAddress(program.programId);
+    const { poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAddress, tokenProgram);
+    const userTokenAccount = client.userTokenAccount(mintAddress, user.publicKey, tokenProgram);
     
     console.log("\n📍 Derived Accounts:");
     console.log("  DEX Config:", dexConfigPDA.toString());
@@ -128,7 +109,7 @@ async function swapTokensForSol() {
     
     let userTokenBalance = BigInt(0);
     try {
-      const userTokenAccountInfo = await getAccount(connection, userTokenAccount);
+      const userTokenAccountInfo = await getAccount(connection, userTokenAccount, undefined, tokenProgram);
       userTokenBalance = userTokenAccountInfo.amount;
       console.log("Token Balance (raw):", userTokenBalance.toString());
       console.log("Token Balance (decimal):", Number(userTokenBalance) / Math.pow(10, TOKEN_DECIMALS), "tokens");
@@ -172,21 +153,9 @@ async function swapTokensForSol() {
     console.log("\n========== EXECUTING SWAP ==========");
     console.log("🔄 Selling", SELL_AMOUNT_TOKENS, "tokens for SOL...");
     
-    const tx = await program.methods
-      .sell(sellAmount)
-      .accounts({
-        dexConfigurationAccount: dexConfigPDA,
-        pool: poolAddress,
-        tokenMint: mintAddress,
-        poolTokenAccount: poolTokenAccount,
-        poolSolVault: poolSolVault,
-        userTokenAccount: userTokenAccount,
-        user: user.publicKey,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-        systemProgram: SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        // Note: associatedTokenProgram is not required for sell
-      })
+    // No minimum output, set one to guard against slippage
+    const tx = await client
+      .sell(program, user.publicKey, mintAddress, sellAmount, new BN(0), { tokenProgram })
       .rpc();
     
     console.log("✅ Transaction Signature:", tx);
@@ -209,7 +178,7 @@ async function swapTokensForSol() {
     const userSolBalanceAfter = await connection.getBalance(user.publicKey);
     console.log("SOL Balance:", userSolBalanceAfter / LAMPORTS_PER_SOL, "SOL");
     
-    const userTokenAccountInfoAfter = await getAccount(connection, userTokenAccount);
+    const userTokenAccountInfoAfter = await getAccount(connection, userTokenAccount, undefined, tokenProgram);
     const userTokenBalanceAfter = userTokenAccountInfoAfter.amount;
     console.log("Token Balance (raw):", userTokenBalanceAfter.toString());
     console.log("Token Balance (decimal):", Number(userTokenBalanceAfter) / Math.pow(10, TOKEN_DECIMALS), "tokens");
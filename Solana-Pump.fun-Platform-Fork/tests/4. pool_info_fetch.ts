@@ -2,11 +2,9 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { BN } from "bn.js";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
+import * as client from "../client/client";
 import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { 
-  getAssociatedTokenAddress,
-  getAccount as getTokenAccount
-} from "@solana/spl-token";
+import { getAccount as getTokenAccount } from "@solana/spl-token";
 import * as fs from "fs";
 import * as path from "path";
 import * as os from "os";
@@ -75,19 +73,11 @@ async function fetchPoolInfo() {
     
     // Get token mint address from pool
     const mintAddress = poolAccount.token;
+    // Pools created before Token-2022 launches hold legacy SPL Token mints
+    const tokenProgram = await client.mintTokenProgram(connection, mintAddress);
     
-    // Derive pool's token account
-    const poolTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      poolAddress,
-      true // allowOwnerOffCurve
-    );
-    
-    // Derive pool SOL vault
-    const [poolSolVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_sol_vault"), mintAddress.toBuffer()],
-      program.programId
-    );
+    // Derive pool's token account and SOL vault
+    const { poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAddress, tokenProgram);
     
     console.log("\n========== DERIVED ACCOUNTS ==========");
     console.log("=� Pool Token Account:", poolTokenAccount.toString());
@@ -98,7 +88,7 @@ async function fetchPoolInfo() {
     
     // Get actual token balance
     try {
-      const tokenAccountInfo = await getTokenAccount(connection as any, poolTokenAccount);
+      const tokenAccountInfo = await getTokenAccount(connection as any, poolTokenAccount, undefined, tokenProgram);
       const tokenBalance = tokenAccountInfo.amount;
       console.log(">� Actual Token Balance:", tokenBalance.toString());
       console.log("   (Formatted):", Number(tokenBalance) / Math.pow(10, 9), "tokens");
@@ -1,15 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
-import { SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID, 
-  getAssociatedTokenAddress,
-  getAccount,
-  transfer,
-  createTransferInstruction
-} from "@solana/spl-token";
+import * as client from "../client/client";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
 
 describe("Add Liquidity Test", () => {
   // Setup
@@ -26,6 +20,7 @@ describe("Add Liquidity Test", () => {
   let pool: PublicKey;
   let poolTokenAccount: PublicKey;
   let poolSolVault: PublicKey;
+  let tokenProgram: PublicKey;
   let userTokenAccount: PublicKey;
   
   console.log("=====================================");
@@ -37,30 +32,13 @@ describe("Add Liquidity Test", () => {
   console.log("=====================================\n");
   
   before(async () => {
-    // Derive pool PDA
-    [pool] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_pool"), mintAddress.toBuffer()],
-      program.programId
-    );
-    
-    // Get pool's associated token account
-    poolTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      pool,
-      true // allowOwnerOffCurve
-    );
-    
-    // Derive pool SOL vault with correct seed
-    [poolSolVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_sol_vault"), mintAddress.toBuffer()],
-      program.programId
-    );
+    // Derive the pool PDAs and its associated token account
+    // Pools created before Token-2022 launches hold legacy SPL Token mints
+    tokenProgram = await client.mintTokenProgram(provider.connection, mintAddress);
+    ({ pool, poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAddress, tokenProgram));
     
     // Get user's token account
-    userTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      payer.publicKey
-    );
+    userTokenAccount = client.userTokenAccount(mintAddress, payer.publicKey, tokenProgram);
     
     console.log("Derived Accounts:");
     console.log("  Pool PDA:", pool.toString());
@@ -76,7 +54,7 @@ describe("Add Liquidity Test", () => {
       
       try {
         // Check user's token balance
-        const userTokenAccountInfo = await getAccount(provider.connection, userTokenAccount);
+        const userTokenAccountInfo = await getAccount(provider.connection, userTokenAccount, undefined, tokenProgram);
         console.log("User's token balance:", userTokenAccountInfo.amount.toString());
         
         // Check user's SOL balance
@@ -87,7 +65,7 @@ describe("Add Liquidity Test", () => {
         const poolAccount = await program.account.liquidityPool.fetch(pool);
         console.log("\nPool Information:");
         console.log("  Creator:", poolAccount.creator.toString());
-        console.log("  Token Mint:", poolAccount.tokenMint.toString());
+        console.log("  Token Mint:", poolAccount.token.toString());
         
         // Check current pool balances
         try {
@@ -95,7 +73,7 @@ describe("Add Liquidity Test", () => {
           console.log("\nCurrent Pool Balances:");
           console.log("  Pool SOL balance:", poolSolBalance / LAMPORTS_PER_SOL, "SOL");
           
-          const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+          const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
           console.log("  Pool token balance:", poolTokenAccountInfo.amount.toString());
         } catch (e) {
           console.log("\nPool balances not yet initialized");
@@ -107,107 +85,33 @@ describe("Add Liquidity Test", () => {
     });
   });
   
-  describe("Transfer Tokens to Pool", () => {
-    it("Should transfer 10000 tokens to pool token account", async () => {
-      console.log("\n========== TRANSFER TOKENS TO POOL ==========");
-      
-      const transferAmount = new BN("10000000000000"); // 10000 tokens with 9 decimals
-      
-      console.log("Transferring tokens to pool...");
-      console.log("  Amount:", transferAmount.toString(), "(10000 tokens)");
-      
-      try {
-        // Create transfer instruction
-        const transferIx = createTransferInstruction(
-          userTokenAccount,
-          poolTokenAccount,
-          payer.publicKey,
-          BigInt(transferAmount.toString()),
-          [],
-          TOKEN_PROGRAM_ID
-        );
-        
-        // Send transaction
-        const tx = await provider.sendAndConfirm(
-          new anchor.web3.Transaction().add(transferIx)
-        );
-        
-        console.log("Transaction:", tx);
-        console.log("Transfer successful!");
-        
-        // Verify transfer
-        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
-        console.log("Pool token balance after transfer:", poolTokenAccountInfo.amount.toString());
-        
-      } catch (error) {
-        console.error("Error transferring tokens:", error);
-      }
-    });
-  });
-  
-  describe("Add SOL Liquidity", () => {
-    it("Should add 0.1 SOL to pool", async () => {
-      console.log("\n========== ADD SOL LIQUIDITY ==========");
+  describe("Add Liquidity", () => {
+    it("Should add 10000 tokens and up to 0.1 SOL to the pool", async () => {
+      console.log("\n========== ADD LIQUIDITY ==========");
       
-      const solAmount = new BN(0.1 * LAMPORTS_PER_SOL); // 0.1 SOL
-      
-      console.log("Adding SOL to pool...");
-      console.log("  Amount:", solAmount.toNumber() / LAMPORTS_PER_SOL, "SOL");
-      
-      try {
-        // Transfer SOL directly to pool SOL vault
-        const transferIx = SystemProgram.transfer({
-          fromPubkey: payer.publicKey,
-          toPubkey: poolSolVault,
-          lamports: solAmount.toNumber(),
-        });
-        
-        const tx = await provider.sendAndConfirm(
-          new anchor.web3.Transaction().add(transferIx)
-        );
-        
-        console.log("Transaction:", tx);
-        console.log("SOL transfer successful!");
-        
-        // Verify SOL transfer
-        const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-        console.log("Pool SOL balance after transfer:", poolSolBalance / LAMPORTS_PER_SOL, "SOL");
-        
-      } catch (error) {
-        console.error("Error adding SOL:", error);
-      }
-    });
-  });
-  
-  describe("Call Add Liquidity Instruction", () => {
-    it("Should call add_liquidity to officially register the liquidity", async () => {
-      console.log("\n========== CALL ADD LIQUIDITY ==========");
+      const tokenAmount = new BN("10000000000000"); // 10000 tokens with 9 decimals
+      const maxSolAmount = new BN(0.1 * LAMPORTS_PER_SOL); // 0.1 SOL
       
       console.log("Calling add_liquidity instruction...");
+      console.log("  Tokens:", tokenAmount.toString(), "(10000 tokens)");
+      console.log("  Max SOL:", maxSolAmount.toNumber() / LAMPORTS_PER_SOL, "SOL");
       
       try {
-        const tx = await program.methods
-          .addLiquidity()
-          .accounts({
-            pool,
-            tokenMint: mintAddress,
-            poolTokenAccount,
-            userTokenAccount,
-            poolSolVault,
-            user: payer.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          })
+        // The program moves the tokens and SOL into the pool and mints shares for them
+        const tx = await client
+          .addLiquidity(program, payer.publicKey, mintAddress, tokenAmount, maxSolAmount, tokenProgram)
           .rpc();
         
         console.log("Transaction:", tx);
         console.log("Add liquidity successful!");
         
+        const liquidityProvider = await program.account.liquidityProvider.fetch(
+          client.liquidityProviderAddress(program.programId, pool, payer.publicKey)
+        );
+        console.log("Shares held:", liquidityProvider.shares.toString());
+        
       } catch (error) {
-        console.log("Note: The manual transfers above have already added the liquidity");
-        console.log("The add_liquidity instruction might fail if it expects specific conditions");
+        console.error("Error adding liquidity:", error);
       }
     });
   });
@@ -219,7 +123,7 @@ describe("Add Liquidity Test", () => {
       try {
         // Check final pool balances
         const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         
         console.log("Final Pool Balances:");
         console.log("  SOL:", poolSolBalance / LAMPORTS_PER_SOL, "SOL");
@@ -227,7 +131,7 @@ describe("Add Liquidity Test", () => {
         
         // Check user's remaining balances
         const userSolBalance = await provider.connection.getBalance(payer.publicKey);
-        const userTokenAccountInfo = await getAccount(provider.connection, userTokenAccount);
+        const userTokenAccountInfo = await getAccount(provider.connection, userTokenAccount, undefined, tokenProgram);
         
         console.log("\nUser's Remaining Balances:");
         console.log("  SOL:", userSolBalance / LAMPORTS_PER_SOL, "SOL");
@@ -235,7 +139,7 @@ describe("Add Liquidity Test", () => {
         
         console.log("\n=====================================");
         console.log("LIQUIDITY SUCCESSFULLY ADDED!");
-        console.log("Pool is now ready for trading");
+        console.log("Trading opens once the creator calls open_trading");
         console.log("=====================================");
         
       } catch (error) {
@@ -1,13 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
+import * as client from "../client/client";
 import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID, 
-  getAssociatedTokenAddress,
-  getAccount
-} from "@solana/spl-token";
+import { getAccount } from "@solana/spl-token";
 
 describe("Buy and Sell Test", () => {
   // Setup
@@ -27,6 +23,7 @@ describe("Buy and Sell Test", () => {
   let pool: PublicKey;
   let poolTokenAccount: PublicKey;
   let poolSolVault: PublicKey;
+  let tokenProgram: PublicKey;
   let testUserTokenAccount: PublicKey;
   
   console.log("=====================================");
@@ -40,35 +37,15 @@ describe("Buy and Sell Test", () => {
   
   before(async () => {
     // Derive DEX config PDA
-    [dexConfigPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("CurveConfiguration")],
-      program.programId
-    );
+    dexConfigPDA = client.configAddress(program.programId);
     
     // Derive pool PDA
-    [pool] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_pool"), mintAddress.toBuffer()],
-      program.programId
-    );
-    
-    // Get pool's associated token account
-    poolTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      pool,
-      true
-    );
-    
-    // Derive pool SOL vault
-    [poolSolVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_sol_vault"), mintAddress.toBuffer()],
-      program.programId
-    );
+    // Pools created before Token-2022 launches hold legacy SPL Token mints
+    tokenProgram = await client.mintTokenProgram(provider.connection, mintAddress);
+    ({ pool, poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAddress, tokenProgram));
     
     // Get test user's token account
-    testUserTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      testUser.publicKey
-    );
+    testUserTokenAccount = client.userTokenAccount(mintAddress, testUser.publicKey, tokenProgram);
     
     console.log("Accounts:");
     console.log("  DEX Config:", dexConfigPDA.toString());
@@ -111,7 +88,7 @@ describe("Buy and Sell Test", () => {
       try {
         // Check pool balances
         const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         
         console.log("Pool Balances:");
         console.log("  SOL:", poolSolBalance / LAMPORTS_PER_SOL, "SOL");
@@ -152,21 +129,8 @@ describe("Buy and Sell Test", () => {
         console.log("  Initial SOL balance:", initialSol / LAMPORTS_PER_SOL, "SOL");
         
         // Execute buy
-        const tx = await program.methods
-          .buy(buyAmount)
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            pool,
-            tokenMint: mintAddress,
-            poolTokenAccount,
-            poolSolVault,
-            userTokenAccount: testUserTokenAccount,
-            user: testUser.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          })
+        const tx = await client
+          .buy(program, testUser.publicKey, mintAddress, buyAmount, new BN(0), { tokenProgram })
           .signers([testUser])
           .rpc();
         
@@ -175,7 +139,7 @@ describe("Buy and Sell Test", () => {
         
         // Get final balances
         const finalSol = await provider.connection.getBalance(testUser.publicKey);
-        const tokenAccountInfo = await getAccount(provider.connection, testUserTokenAccount);
+        const tokenAccountInfo = await getAccount(provider.connection, testUserTokenAccount, undefined, tokenProgram);
         
         console.log("\nResults:");
         console.log("  Final SOL balance:", finalSol / LAMPORTS_PER_SOL, "SOL");
@@ -185,7 +149,7 @@ describe("Buy and Sell Test", () => {
         
         // Check pool state after buy
         const poolSolAfter = await provider.connection.getBalance(poolSolVault);
-        const poolTokensAfter = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokensAfter = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         
         console.log("\nPool after buy:");
         console.log("  SOL:", poolSolAfter / LAMPORTS_PER_SOL, "SOL");
@@ -204,7 +168,7 @@ describe("Buy and Sell Test", () => {
       
       try {
         // Get current token balance
-        const tokenAccountInfo = await getAccount(provider.connection, testUserTokenAccount);
+        const tokenAccountInfo = await getAccount(provider.connection, testUserTokenAccount, undefined, tokenProgram);
         const currentBalance = tokenAccountInfo.amount;
         
         if (currentBalance === BigInt(0)) {
@@ -224,20 +188,8 @@ describe("Buy and Sell Test", () => {
         const initialSol = await provider.connection.getBalance(testUser.publicKey);
         
         // Execute sell
-        const tx = await program.methods
-          .sell(sellAmount)
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            pool,
-            tokenMint: mintAddress,
-            poolTokenAccount,
-            poolSolVault,
-            userTokenAccount: testUserTokenAccount,
-            user: testUser.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
+        const tx = await client
+          .sell(program, testUser.publicKey, mintAddress, sellAmount, new BN(0), { tokenProgram })
           .signers([testUser])
           .rpc();
         
@@ -246,7 +198,7 @@ describe("Buy and Sell Test", () => {
         
         // Get final balances
         const finalSol = await provider.connection.getBalance(testUser.publicKey);
-        const finalTokens = await getAccount(provider.connection, testUserTokenAccount);
+        const finalTokens = await getAccount(provider.connection, testUserTokenAccount, undefined, tokenProgram);
         
         console.log("\nResults:");
         console.log("  SOL received:", (finalSol - initialSol) / LAMPORTS_PER_SOL, "SOL");
@@ -255,7 +207,7 @@ describe("Buy and Sell Test", () => {
         
         // Check pool state after sell
         const poolSolAfter = await provider.connection.getBalance(poolSolVault);
-        const poolTokensAfter = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokensAfter = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         
         console.log("\nPool after sell:");
         console.log("  SOL:", poolSolAfter / LAMPORTS_PER_SOL, "SOL");
@@ -275,7 +227,7 @@ describe("Buy and Sell Test", () => {
       try {
         // Final pool state
         const poolSol = await provider.connection.getBalance(poolSolVault);
-        const poolTokens = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokens = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         
         console.log("Final Pool State:");
         console.log("  SOL:", poolSol / LAMPORTS_PER_SOL, "SOL");
@@ -292,7 +244,7 @@ describe("Buy and Sell Test", () => {
         
         // Test user final state
         const userSol = await provider.connection.getBalance(testUser.publicKey);
-        const userTokens = await getAccount(provider.connection, testUserTokenAccount);
+        const userTokens = await getAccount(provider.connection, testUserTokenAccount, undefined, tokenProgram);
         
         console.log("\nTest User Final State:");
         console.log("  SOL:", userSol / LAMPORTS_PER_SOL, "SOL");
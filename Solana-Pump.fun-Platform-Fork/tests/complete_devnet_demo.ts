@@ -1,13 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
+import * as client from "../client/client";
 import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID, 
-  getAssociatedTokenAddress,
-  getAccount
-} from "@solana/spl-token";
+import { getAccount } from "@solana/spl-token";
 
 describe("Complete Devnet Demo", () => {
   // Setup
@@ -29,6 +25,7 @@ describe("Complete Devnet Demo", () => {
   let pool: PublicKey;
   let poolTokenAccount: PublicKey;
   let poolSolVault: PublicKey;
+  let tokenProgram: PublicKey;
   
   console.log("=====================================");
   console.log("COMPLETE DEVNET DEMO");
@@ -40,26 +37,11 @@ describe("Complete Devnet Demo", () => {
   
   before(async () => {
     // Derive PDAs
-    [dexConfigPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("CurveConfiguration")],
-      program.programId
-    );
-    
-    [pool] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_pool"), mintAddress.toBuffer()],
-      program.programId
-    );
+    dexConfigPDA = client.configAddress(program.programId);
     
-    poolTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      pool,
-      true
-    );
-    
-    [poolSolVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_sol_vault"), mintAddress.toBuffer()],
-      program.programId
-    );
+    // Pools created before Token-2022 launches hold legacy SPL Token mints
+    tokenProgram = await client.mintTokenProgram(provider.connection, mintAddress);
+    ({ pool, poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAddress, tokenProgram));
     
     console.log("Pool Information:");
     console.log("  Pool PDA:", pool.toString());
@@ -98,7 +80,7 @@ describe("Complete Devnet Demo", () => {
       console.log("\n========== INITIAL POOL STATE ==========");
       
       const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
       
       console.log("Pool Liquidity:");
       console.log("  SOL:", poolSolBalance / LAMPORTS_PER_SOL, "SOL");
@@ -113,39 +95,23 @@ describe("Complete Devnet Demo", () => {
     it("Buyer 1 buys with 0.01 SOL", async () => {
       console.log("\n========== BUYER 1: 0.01 SOL ==========");
       
-      const buyerTokenAccount = await getAssociatedTokenAddress(
-        mintAddress,
-        buyer1.publicKey
-      );
+      const buyerTokenAccount = client.userTokenAccount(mintAddress, buyer1.publicKey, tokenProgram);
       
       const buyAmount = new BN(0.01 * LAMPORTS_PER_SOL);
       
-      const tx = await program.methods
-        .buy(buyAmount)
-        .accounts({
-          dexConfigurationAccount: dexConfigPDA,
-          pool,
-          tokenMint: mintAddress,
-          poolTokenAccount,
-          poolSolVault,
-          userTokenAccount: buyerTokenAccount,
-          user: buyer1.publicKey,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-          systemProgram: SystemProgram.programId,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        })
+      const tx = await client
+        .buy(program, buyer1.publicKey, mintAddress, buyAmount, new BN(0), { tokenProgram })
         .signers([buyer1])
         .rpc();
       
       console.log("Transaction:", tx);
       
-      const buyerTokenAccountInfo = await getAccount(provider.connection, buyerTokenAccount);
+      const buyerTokenAccountInfo = await getAccount(provider.connection, buyerTokenAccount, undefined, tokenProgram);
       console.log("Tokens bought:", Number(buyerTokenAccountInfo.amount) / 1e9);
       
       // Show new pool state
       const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
       console.log("\nPool after buy:");
       console.log("  SOL:", poolSolBalance / LAMPORTS_PER_SOL);
       console.log("  Tokens:", Number(poolTokenAccountInfo.amount) / 1e9);
@@ -156,39 +122,23 @@ describe("Complete Devnet Demo", () => {
     it("Buyer 2 buys with 0.05 SOL", async () => {
       console.log("\n========== BUYER 2: 0.05 SOL ==========");
       
-      const buyerTokenAccount = await getAssociatedTokenAddress(
-        mintAddress,
-        buyer2.publicKey
-      );
+      const buyerTokenAccount = client.userTokenAccount(mintAddress, buyer2.publicKey, tokenProgram);
       
       const buyAmount = new BN(0.05 * LAMPORTS_PER_SOL);
       
-      const tx = await program.methods
-        .buy(buyAmount)
-        .accounts({
-          dexConfigurationAccount: dexConfigPDA,
-          pool,
-          tokenMint: mintAddress,
-          poolTokenAccount,
-          poolSolVault,
-          userTokenAccount: buyerTokenAccount,
-          user: buyer2.publicKey,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-          systemProgram: SystemProgram.programId,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        })
+      const tx = await client
+        .buy(program, buyer2.publicKey, mintAddress, buyAmount, new BN(0), { tokenProgram })
         .signers([buyer2])
         .rpc();
       
       console.log("Transaction:", tx);
       
-      const buyerTokenAccountInfo = await getAccount(provider.connection, buyerTokenAccount);
+      const buyerTokenAccountInfo = await getAccount(provider.connection, buyerTokenAccount, undefined, tokenProgram);
       console.log("Tokens bought:", Number(buyerTokenAccountInfo.amount) / 1e9);
       
       // Show new pool state
       const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
       console.log("\nPool after buy:");
       console.log("  SOL:", poolSolBalance / LAMPORTS_PER_SOL);
       console.log("  Tokens:", Number(poolTokenAccountInfo.amount) / 1e9);
@@ -199,36 +149,20 @@ describe("Complete Devnet Demo", () => {
     it("Seller buys with 0.02 SOL then sells half", async () => {
       console.log("\n========== SELLER: BUY & SELL ==========");
       
-      const sellerTokenAccount = await getAssociatedTokenAddress(
-        mintAddress,
-        seller.publicKey
-      );
+      const sellerTokenAccount = client.userTokenAccount(mintAddress, seller.publicKey, tokenProgram);
       
       // First buy
       console.log("Buying with 0.02 SOL...");
       const buyAmount = new BN(0.02 * LAMPORTS_PER_SOL);
       
-      const buyTx = await program.methods
-        .buy(buyAmount)
-        .accounts({
-          dexConfigurationAccount: dexConfigPDA,
-          pool,
-          tokenMint: mintAddress,
-          poolTokenAccount,
-          poolSolVault,
-          userTokenAccount: sellerTokenAccount,
-          user: seller.publicKey,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-          systemProgram: SystemProgram.programId,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        })
+      const buyTx = await client
+        .buy(program, seller.publicKey, mintAddress, buyAmount, new BN(0), { tokenProgram })
         .signers([seller])
         .rpc();
       
       console.log("Buy transaction:", buyTx);
       
-      let sellerTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount);
+      let sellerTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount, undefined, tokenProgram);
       const tokensBought = sellerTokenAccountInfo.amount;
       console.log("Tokens bought:", Number(tokensBought) / 1e9);
       
@@ -236,30 +170,19 @@ describe("Complete Devnet Demo", () => {
       console.log("\nSelling half of tokens...");
       const sellAmount = new BN(tokensBought.toString()).div(new BN(2));
       
-      const sellTx = await program.methods
-        .sell(sellAmount)
-        .accounts({
-          dexConfigurationAccount: dexConfigPDA,
-          pool,
-          tokenMint: mintAddress,
-          poolTokenAccount,
-          poolSolVault,
-          userTokenAccount: sellerTokenAccount,
-          user: seller.publicKey,
-          systemProgram: SystemProgram.programId,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
+      const sellTx = await client
+        .sell(program, seller.publicKey, mintAddress, sellAmount, new BN(0), { tokenProgram })
         .signers([seller])
         .rpc();
       
       console.log("Sell transaction:", sellTx);
       
-      sellerTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount);
+      sellerTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount, undefined, tokenProgram);
       console.log("Tokens remaining:", Number(sellerTokenAccountInfo.amount) / 1e9);
       
       // Show final pool state
       const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
       console.log("\nPool after sell:");
       console.log("  SOL:", poolSolBalance / LAMPORTS_PER_SOL);
       console.log("  Tokens:", Number(poolTokenAccountInfo.amount) / 1e9);
@@ -272,7 +195,7 @@ describe("Complete Devnet Demo", () => {
       
       // Pool state
       const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
       
       console.log("Final Pool State:");
       console.log("  SOL:", poolSolBalance / LAMPORTS_PER_SOL, "SOL");
@@ -284,24 +207,24 @@ describe("Complete Devnet Demo", () => {
       console.log("\nUser Token Balances:");
       
       try {
-        const buyer1TokenAccount = await getAssociatedTokenAddress(mintAddress, buyer1.publicKey);
-        const buyer1Tokens = await getAccount(provider.connection, buyer1TokenAccount);
+        const buyer1TokenAccount = client.userTokenAccount(mintAddress, buyer1.publicKey, tokenProgram);
+        const buyer1Tokens = await getAccount(provider.connection, buyer1TokenAccount, undefined, tokenProgram);
         console.log("  Buyer 1:", Number(buyer1Tokens.amount) / 1e9, "tokens");
       } catch (e) {
         console.log("  Buyer 1: 0 tokens");
       }
       
       try {
-        const buyer2TokenAccount = await getAssociatedTokenAddress(mintAddress, buyer2.publicKey);
-        const buyer2Tokens = await getAccount(provider.connection, buyer2TokenAccount);
+        const buyer2TokenAccount = client.userTokenAccount(mintAddress, buyer2.publicKey, tokenProgram);
+        const buyer2Tokens = await getAccount(provider.connection, buyer2TokenAccount, undefined, tokenProgram);
         console.log("  Buyer 2:", Number(buyer2Tokens.amount) / 1e9, "tokens");
       } catch (e) {
         console.log("  Buyer 2: 0 tokens");
       }
       
       try {
-        const sellerTokenAccount = await getAssociatedTokenAddress(mintAddress, seller.publicKey);
-        const sellerTokens = await getAccount(provider.connection, sellerTokenAccount);
+        const sellerTokenAccount = client.userTokenAccount(mintAddress, seller.publicKey, tokenProgram);
+        const sellerTokens = await getAccount(provider.connection, sellerTokenAccount, undefined, tokenProgram);
         console.log("  Seller:", Number(sellerTokens.amount) / 1e9, "tokens");
       } catch (e) {
         console.log("  Seller: 0 tokens");
@@ -1,14 +1,10 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
-import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID, 
-  getAssociatedTokenAddress,
-  getAccount
-} from "@solana/spl-token";
+import { Keypair, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getAccount } from "@solana/spl-token";
 import { assert } from "chai";
+import * as client from "../client/client";
 
 describe("Complete Pumpdotfun Tests", () => {
   // Configure provider
@@ -18,9 +14,6 @@ describe("Complete Pumpdotfun Tests", () => {
   const program = anchor.workspace.Pumpdotfun as Program<Pumpdotfun>;
   const payer = provider.wallet;
   
-  // Token metadata program
-  const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-  
   // Test wallets
   const user1 = Keypair.generate();
   const user2 = Keypair.generate();
@@ -28,12 +21,12 @@ describe("Complete Pumpdotfun Tests", () => {
   // Accounts
   let mintKeypair: Keypair;
   let mintAccount: PublicKey;
-  let metadataAccount: PublicKey;
   let tokenAccount: PublicKey;
   let pool: PublicKey;
   let poolTokenAccount: PublicKey;
   let poolSolVault: PublicKey;
   let dexConfigPDA: PublicKey;
+  let totalSupply: BN;
   
   console.log("Program ID:", program.programId.toString());
   console.log("Payer:", payer.publicKey.toString());
@@ -55,10 +48,7 @@ describe("Complete Pumpdotfun Tests", () => {
     }
     
     // Derive DEX config PDA
-    [dexConfigPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("CurveConfiguration")],
-      program.programId
-    );
+    dexConfigPDA = client.configAddress(program.programId);
     
     console.log("DEX Config PDA:", dexConfigPDA.toString());
   });
@@ -70,15 +60,7 @@ describe("Complete Pumpdotfun Tests", () => {
       const fee = 250; // 2.5% fee (250 basis points)
       
       try {
-        const tx = await program.methods
-          .initialize(fee)
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            admin: payer.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-          })
-          .rpc();
+        const tx = await client.initialize(program, payer.publicKey, fee).rpc();
         
         console.log("  Transaction:", tx);
         console.log("  DEX configuration initialized with fee:", fee / 10000 * 100, "%");
@@ -101,33 +83,20 @@ describe("Complete Pumpdotfun Tests", () => {
   });
   
   describe("2. Create Token", () => {
-    it("Should create a new SPL token", async () => {
+    it("Should create a new Token-2022 token", async () => {
       console.log("\n=== Creating Token ===");
       
       // Generate mint keypair
       mintKeypair = Keypair.generate();
       mintAccount = mintKeypair.publicKey;
       
-      // Derive metadata PDA
-      [metadataAccount] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("metadata"),
-          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-          mintAccount.toBuffer(),
-        ],
-        TOKEN_METADATA_PROGRAM_ID
-      );
-      
       // Get associated token address
-      tokenAccount = await getAssociatedTokenAddress(
-        mintAccount,
-        payer.publicKey
-      );
+      tokenAccount = client.userTokenAccount(mintAccount, payer.publicKey);
       
       const name = "PumpToken";
       const symbol = "PUMP";
       const uri = "https://test.uri/metadata.json";
-      const totalSupply = new BN("1000000000000000000"); // 1 billion with 9 decimals
+      totalSupply = new BN("1000000000000000000"); // 1 billion with 9 decimals
       
       console.log("  Creating token...");
       console.log("    Name:", name);
@@ -136,37 +105,26 @@ describe("Complete Pumpdotfun Tests", () => {
       console.log("    Mint:", mintAccount.toString());
       
       try {
-        const tx = await program.methods
-          .createToken(name, symbol, uri, totalSupply)
-          .accounts({
-            payer: payer.publicKey,
-            mintAccount,
-            metadataAccount,
-            tokenAccount,
-            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            systemProgram: SystemProgram.programId,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-          })
-          .signers([mintKeypair])
+        // Metadata lives in the Token-2022 mint itself, no metadata program is involved
+        const tx = await client
+          .createToken(program, payer.publicKey, mintKeypair, { name, symbol, uri }, totalSupply)
           .rpc();
         
         console.log("    Transaction:", tx);
         console.log("    Token created successfully!");
         
         // Verify token was created
-        const tokenAccountInfo = await getAccount(provider.connection, tokenAccount);
+        const tokenAccountInfo = await getAccount(
+          provider.connection,
+          tokenAccount,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        );
         console.log("    Creator balance:", tokenAccountInfo.amount.toString());
         
       } catch (error) {
-        // On localnet, metadata program might not be deployed
-        if (error.toString().includes("InvalidProgramExecutable")) {
-          console.log("    Note: Token metadata program not deployed on localnet");
-          console.log("    Token creation would work on devnet/mainnet");
-        } else {
-          throw error;
-        }
+        console.log("    Token creation error:", error.message);
+        throw error;
       }
     });
   });
@@ -175,36 +133,15 @@ describe("Complete Pumpdotfun Tests", () => {
     it("Should create a liquidity pool for the token", async () => {
       console.log("\n=== Creating Liquidity Pool ===");
       
-      // Derive pool PDA
-      [pool] = PublicKey.findProgramAddressSync(
-        [Buffer.from("liquidity_pool"), mintAccount.toBuffer()],
-        program.programId
-      );
-      
-      // Get pool's associated token account
-      poolTokenAccount = await getAssociatedTokenAddress(
-        mintAccount,
-        pool,
-        true // allowOwnerOffCurve
-      );
+      // Derive the pool PDAs and its associated token account
+      ({ pool, poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAccount));
       
       console.log("  Pool PDA:", pool.toString());
       console.log("  Pool Token Account:", poolTokenAccount.toString());
       
       try {
-        const tx = await program.methods
-          .createPool()
-          .accounts({
-            pool,
-            tokenMint: mintAccount,
-            poolTokenAccount,
-            payer: payer.publicKey,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-          })
-          .rpc();
+        // The default configuration only accepts the original virtual reserves
+        const tx = await client.createPool(program, payer.publicKey, mintAccount, client.DEFAULT_CURVE).rpc();
         
         console.log("  Transaction:", tx);
         console.log("  Pool created successfully!");
@@ -212,7 +149,7 @@ describe("Complete Pumpdotfun Tests", () => {
         // Verify pool was created
         const poolAccount = await program.account.liquidityPool.fetch(pool);
         console.log("  Pool creator:", poolAccount.creator.toString());
-        console.log("  Pool mint:", poolAccount.tokenMint.toString());
+        console.log("  Pool mint:", poolAccount.token.toString());
         console.log("  Pool bump:", poolAccount.bump);
         
       } catch (error) {
@@ -225,29 +162,13 @@ describe("Complete Pumpdotfun Tests", () => {
     it("Should add liquidity to the pool", async () => {
       console.log("\n=== Adding Liquidity ===");
       
-      // Derive pool SOL vault
-      [poolSolVault] = PublicKey.findProgramAddressSync(
-        [Buffer.from("sol-vault"), pool.toBuffer()],
-        program.programId
-      );
-      
       console.log("  Pool SOL Vault:", poolSolVault.toString());
       
       try {
-        const tx = await program.methods
-          .addLiquidity()
-          .accounts({
-            pool,
-            tokenMint: mintAccount,
-            poolTokenAccount,
-            userTokenAccount: tokenAccount,
-            poolSolVault,
-            user: payer.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          })
+        // The whole supply goes in, trading cannot open with tokens held outside the pool
+        const seedSol = new BN(0.01 * LAMPORTS_PER_SOL);
+        const tx = await client
+          .addLiquidity(program, payer.publicKey, mintAccount, totalSupply, seedSol)
           .rpc();
         
         console.log("  Transaction:", tx);
@@ -261,6 +182,24 @@ describe("Complete Pumpdotfun Tests", () => {
         console.log("  Add liquidity error:", error.message);
       }
     });
+
+    it("Should open the pool for trading", async () => {
+      console.log("\n=== Opening Trading ===");
+      
+      try {
+        // Keep the seed locked for the shortest period the configuration allows
+        const dexConfig = await program.account.curveConfiguration.fetch(dexConfigPDA);
+        const tx = await client
+          .openTrading(program, payer.publicKey, mintAccount, dexConfig.liquidityLockPeriod)
+          .rpc();
+        
+        console.log("  Transaction:", tx);
+        console.log("  Trading opened!");
+        
+      } catch (error) {
+        console.log("  Open trading error:", error.message);
+      }
+    });
   });
   
   describe("5. Buy Tokens", () => {
@@ -270,10 +209,7 @@ describe("Complete Pumpdotfun Tests", () => {
       const solAmount = new BN(0.5 * LAMPORTS_PER_SOL); // Buy with 0.5 SOL
       
       // Get user1's token account
-      const user1TokenAccount = await getAssociatedTokenAddress(
-        mintAccount,
-        user1.publicKey
-      );
+      const user1TokenAccount = client.userTokenAccount(mintAccount, user1.publicKey);
       
       console.log("  User1 buying with", solAmount.toNumber() / LAMPORTS_PER_SOL, "SOL");
       
@@ -282,21 +218,9 @@ describe("Complete Pumpdotfun Tests", () => {
         const initialSol = await provider.connection.getBalance(user1.publicKey);
         console.log("  Initial SOL balance:", initialSol / LAMPORTS_PER_SOL);
         
-        const tx = await program.methods
-          .buy(solAmount)
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            pool,
-            tokenMint: mintAccount,
-            poolTokenAccount,
-            poolSolVault,
-            userTokenAccount: user1TokenAccount,
-            user: user1.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          })
+        // No minimum output, this test does not guard against slippage
+        const tx = await client
+          .buy(program, user1.publicKey, mintAccount, solAmount, new BN(0))
           .signers([user1])
           .rpc();
         
@@ -304,7 +228,12 @@ describe("Complete Pumpdotfun Tests", () => {
         
         // Get final balances
         const finalSol = await provider.connection.getBalance(user1.publicKey);
-        const tokenBalance = await getAccount(provider.connection, user1TokenAccount);
+        const tokenBalance = await getAccount(
+          provider.connection,
+          user1TokenAccount,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        );
         
         console.log("  Final SOL balance:", finalSol / LAMPORTS_PER_SOL);
         console.log("  Tokens received:", tokenBalance.amount.toString());
@@ -323,35 +252,25 @@ describe("Complete Pumpdotfun Tests", () => {
       const tokenAmount = new BN("1000000000"); // 1 token with 9 decimals
       
       // Get user1's token account
-      const user1TokenAccount = await getAssociatedTokenAddress(
-        mintAccount,
-        user1.publicKey
-      );
+      const user1TokenAccount = client.userTokenAccount(mintAccount, user1.publicKey);
       
       console.log("  User1 selling", tokenAmount.toString(), "tokens");
       
       try {
         // Get initial balances
         const initialSol = await provider.connection.getBalance(user1.publicKey);
-        const initialTokens = await getAccount(provider.connection, user1TokenAccount);
+        const initialTokens = await getAccount(
+          provider.connection,
+          user1TokenAccount,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        );
         
         console.log("  Initial SOL balance:", initialSol / LAMPORTS_PER_SOL);
         console.log("  Initial token balance:", initialTokens.amount.toString());
         
-        const tx = await program.methods
-          .sell(tokenAmount)
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            pool,
-            tokenMint: mintAccount,
-            poolTokenAccount,
-            poolSolVault,
-            userTokenAccount: user1TokenAccount,
-            user: user1.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
+        const tx = await client
+          .sell(program, user1.publicKey, mintAccount, tokenAmount, new BN(0))
           .signers([user1])
           .rpc();
         
@@ -359,7 +278,12 @@ describe("Complete Pumpdotfun Tests", () => {
         
         // Get final balances
         const finalSol = await provider.connection.getBalance(user1.publicKey);
-        const finalTokens = await getAccount(provider.connection, user1TokenAccount);
+        const finalTokens = await getAccount(
+          provider.connection,
+          user1TokenAccount,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        );
         
         console.log("  Final SOL balance:", finalSol / LAMPORTS_PER_SOL);
         console.log("  Final token balance:", finalTokens.amount.toString());
@@ -377,27 +301,17 @@ describe("Complete Pumpdotfun Tests", () => {
       console.log("\n=== Removing Liquidity ===");
       
       try {
-        // Get pool info to find bump
-        const poolAccount = await program.account.liquidityPool.fetch(pool);
-        const bump = poolAccount.bump;
-        
-        const tx = await program.methods
-          .removeLiquidity(bump)
-          .accounts({
-            pool,
-            tokenMint: mintAccount,
-            poolTokenAccount,
-            userTokenAccount: tokenAccount,
-            poolSolVault,
-            user: payer.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
+        // Burn every share, which only returns the seed now that trading is open
+        const liquidityProvider = await program.account.liquidityProvider.fetch(
+          client.liquidityProviderAddress(program.programId, pool, payer.publicKey)
+        );
+        
+        const tx = await client
+          .removeLiquidity(program, payer.publicKey, mintAccount, liquidityProvider.shares)
           .rpc();
         
         console.log("  Transaction:", tx);
-        console.log("  Liquidity removed successfully!");
+        console.log("  Seed withdrawn successfully!");
         
       } catch (error) {
         console.log("  Remove liquidity error:", error.message);
@@ -410,14 +324,12 @@ describe("Complete Pumpdotfun Tests", () => {
       console.log("\n=== Withdrawing Fees ===");
       
       try {
-        const tx = await program.methods
-          .withdraw()
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            admin: payer.publicKey,
-            systemProgram: SystemProgram.programId,
-          })
-          .rpc();
+        const feeVault = await program.account.protocolFeeVault.fetch(
+          client.protocolFeeVaultAddress(program.programId)
+        );
+        console.log("  Accrued fees:", feeVault.fees.toString(), "lamports");
+        
+        const tx = await client.withdraw(program, payer.publicKey, payer.publicKey, feeVault.fees).rpc();
         
         console.log("  Transaction:", tx);
         console.log("  Fees withdrawn successfully!");
@@ -441,7 +353,6 @@ describe("Complete Pumpdotfun Tests", () => {
       if (mintAccount) {
         console.log("\nToken Information:");
         console.log("  Mint:", mintAccount.toString());
-        console.log("  Metadata:", metadataAccount.toString());
         
         if (pool) {
           console.log("\nPool Information:");
@@ -453,9 +364,10 @@ describe("Complete Pumpdotfun Tests", () => {
       
       console.log("\nSupported Operations:");
       console.log("  ✅ initialize - Initialize DEX configuration");
-      console.log("  ✅ createToken - Create new SPL tokens");
+      console.log("  ✅ createToken - Create new Token-2022 tokens");
       console.log("  ✅ createPool - Create liquidity pools");
       console.log("  ✅ addLiquidity - Add liquidity to pools");
+      console.log("  ✅ openTrading - Open pools for trading");
       console.log("  ✅ buy - Buy tokens with SOL");
       console.log("  ✅ sell - Sell tokens for SOL");
       console.log("  ✅ removeLiquidity - Remove liquidity from pools");
@@ -1,13 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
-import { PublicKey, SystemProgram, LAMPORTS_PER_SOL, Keypair } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID,
-  getAssociatedTokenAddress,
-  getAccount
-} from "@solana/spl-token";
+import * as client from "../client/client";
+import { PublicKey, LAMPORTS_PER_SOL, Keypair } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getAccount } from "@solana/spl-token";
 import * as fs from "fs";
 import * as os from "os";
 import * as path from "path";
@@ -89,6 +85,7 @@ async function createTokenOnDevnet() {
   const tokenSymbol = "SDT123";
   const tokenUri = "https://apricot-selective-kangaroo-871.mypinata.cloud/ipfs/bafkreihf2pleocdw3t4egry46rbgctlwi53eggw34imhn3bvs5wjq6s5i4";
   const initialBuy = new anchor.BN(1 * LAMPORTS_PER_SOL);
+  const totalSupply = new anchor.BN("1000000000000000000"); // 1 billion tokens with 9 decimals
   
   console.log("\n📋 Token Parameters:");
   console.log("  Name:", tokenName);
@@ -105,68 +102,33 @@ async function createTokenOnDevnet() {
   console.log("  ", mint.toString());
   
   // Derive PDAs
-  const [globalState] = PublicKey.findProgramAddressSync(
-    [Buffer.from("global")],
-    program.programId
-  );
-  
-  const [bondingCurve] = PublicKey.findProgramAddressSync(
-    [Buffer.from("bonding_curve"), mint.toBuffer()],
-    program.programId
-  );
-  
-  const associatedBondingCurve = await getAssociatedTokenAddress(
-    mint,
-    bondingCurve,
-    true // allowOwnerOffCurve
-  );
-  
-  const associatedUser = await getAssociatedTokenAddress(
-    mint,
-    wallet.publicKey
-  );
-  
-  // Derive metadata PDA (Metaplex standard)
-  const metadataProgramId = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-  const [metadata] = PublicKey.findProgramAddressSync(
-    [
-      Buffer.from("metadata"),
-      metadataProgramId.toBuffer(),
-      mint.toBuffer(),
-    ],
-    metadataProgramId
-  );
+  const dexConfig = client.configAddress(program.programId);
+  const { pool, poolTokenAccount } = client.poolAddresses(program.programId, mint);
+  const associatedUser = client.userTokenAccount(mint, wallet.publicKey);
   
   console.log("\n📍 Derived Addresses:");
   console.log("  Program ID:", program.programId.toString());
-  console.log("  Global State:", globalState.toString());
-  console.log("  Bonding Curve:", bondingCurve.toString());
-  console.log("  Bonding Curve ATA:", associatedBondingCurve.toString());
+  console.log("  DEX Config:", dexConfig.toString());
+  console.log("  Pool:", pool.toString());
+  console.log("  Pool Token Account:", poolTokenAccount.toString());
   console.log("  Creator ATA:", associatedUser.toString());
-  console.log("  Metadata:", metadata.toString());
   
-  // Check if global state is initialized
+  // Check if the DEX config is initialized
   try {
-    const globalStateAccount = await connection.getAccountInfo(globalState);
-    if (globalStateAccount) {
-      console.log("\n✅ Global state already initialized");
+    const dexConfigAccount = await connection.getAccountInfo(dexConfig);
+    if (dexConfigAccount) {
+      console.log("\n✅ DEX config already initialized");
     } else {
-      throw new Error("Global state not found");
+      throw new Error("DEX config not found");
     }
   } catch (e) {
-    console.log("\n⚠️ Global state not initialized, initializing now...");
+    console.log("\n⚠️ DEX config not initialized, initializing now...");
     try {
-      const initTx = await program.methods
-        .initialize(0.01) // fee parameter (1%)
-        .accounts({
-          admin: wallet.publicKey,
-          // dexConfigurationAccount는 PDA이므로 자동으로 유도됩니다
-          systemProgram: SystemProgram.programId,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-        })
+      const initTx = await client
+        .initialize(program, wallet.publicKey, 100) // 1% fee in basis points
         .rpc();
       
-      console.log("✅ Global state initialized!");
+      console.log("✅ DEX config initialized!");
       console.log("📝 Transaction signature:", initTx);
       console.log("🔍 View on Solana Explorer:");
       console.log(`   https://explorer.solana.com/tx/${initTx}?cluster=devnet`);
@@ -180,32 +142,25 @@ async function createTokenOnDevnet() {
       }, "confirmed");
       console.log("✅ Transaction confirmed");
     } catch (initError) {
-      console.error("❌ Failed to initialize global state:", initError.message);
+      console.error("❌ Failed to initialize DEX config:", initError.message);
       return;
     }
   }
   
-  // Create the token
-  console.log("\n🚀 Creating token with bonding curve...");
+  // Create the token, its pool and the creator's initial buy in one transaction
+  console.log("\n🚀 Launching token with bonding curve...");
   
   try {
-    const tx = await program.methods
-      .create(tokenName, tokenSymbol, tokenUri, initialBuy)
-      .accounts({
-        creator: wallet.publicKey,
-        mint: mint,
-        bondingCurve: bondingCurve,
-        associatedBondingCurve: associatedBondingCurve,
-        associatedUser: associatedUser,
-        globalState: globalState,
-        metadata: metadata,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-        tokenMetadataProgram: metadataProgramId,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-      })
-      .signers([mintKeypair])
+    // No minimum output on the initial buy, nobody can trade ahead of it
+    const tx = await client
+      .launchToken(
+        program,
+        wallet.publicKey,
+        mintKeypair,
+        { name: tokenName, symbol: tokenSymbol, uri: tokenUri },
+        totalSupply,
+        { amount: initialBuy, minAmountOut: new anchor.BN(0) }
+      )
       .rpc();
     
     console.log("\n✅ Token created successfully!");
@@ -223,25 +178,20 @@ async function createTokenOnDevnet() {
     }, "confirmed");
     console.log("✅ Transaction confirmed");
     
-    // Fetch and display bonding curve information
-    const bondingCurveAccountInfo = await connection.getAccountInfo(bondingCurve);
-    if (!bondingCurveAccountInfo) {
-      console.error("❌ Failed to fetch bonding curve account");
-      return;
-    }
-    
-    // For now, we'll just display the basic info
-    // You may need to decode the account data based on your program's structure
+    // Fetch and display pool information
+    const poolAccount = await program.account.liquidityPool.fetch(pool);
     
-    console.log("\n📊 Bonding Curve Created:");
-    console.log("  Address:", bondingCurve.toString());
+    console.log("\n📊 Pool Created:");
+    console.log("  Address:", pool.toString());
+    console.log("  Reserve Token:", poolAccount.reserveToken.toString());
+    console.log("  Reserve SOL:", poolAccount.reserveSol.toString());
     console.log("  Token Name:", tokenName);
     console.log("  Token Symbol:", tokenSymbol);
     console.log("  Mint:", mint.toString());
     
     // Check creator's token balance from initial buy
     try {
-      const creatorTokenAccount = await getAccount(provider.connection, associatedUser);
+      const creatorTokenAccount = await getAccount(provider.connection, associatedUser, undefined, TOKEN_2022_PROGRAM_ID);
       const creatorBalance = Number(creatorTokenAccount.amount);
       console.log("\n👤 Creator Token Balance:");
       console.log("  ", creatorBalance, "tokens (from initial buy)");
@@ -263,16 +213,15 @@ async function createTokenOnDevnet() {
       timestamp: new Date().toISOString(),
       programId: program.programId.toString(),
       mint: mint.toString(),
-      bondingCurve: bondingCurve.toString(),
-      globalState: globalState.toString(),
+      pool: pool.toString(),
+      dexConfig: dexConfig.toString(),
       tokenName: tokenName,
       tokenSymbol: tokenSymbol,
       creator: wallet.publicKey.toString(),
-      metadata: metadata.toString(),
       tokenUri: tokenUri,
       explorerLinks: {
         mint: `https://explorer.solana.com/address/${mint}?cluster=devnet`,
-        bondingCurve: `https://explorer.solana.com/address/${bondingCurve}?cluster=devnet`,
+        pool: `https://explorer.solana.com/address/${pool}?cluster=devnet`,
         transaction: `https://explorer.solana.com/tx/${tx}?cluster=devnet`
       }
     };
@@ -283,12 +232,12 @@ async function createTokenOnDevnet() {
     console.log("\n💾 Token information saved to:", outputPath);
     console.log("\n📌 Important addresses for future operations:");
     console.log("  Mint:", mint.toString());
-    console.log("  Bonding Curve:", bondingCurve.toString());
-    console.log("  Global State:", globalState.toString());
+    console.log("  Pool:", pool.toString());
+    console.log("  DEX Config:", dexConfig.toString());
     
     console.log("\n🔗 View on Solana Explorer:");
     console.log("  Token:", `https://explorer.solana.com/address/${mint}?cluster=devnet`);
-    console.log("  Bonding Curve:", `https://explorer.solana.com/address/${bondingCurve}?cluster=devnet`);
+    console.log("  Pool:", `https://explorer.solana.com/address/${pool}?cluster=devnet`);
     
   } catch (error) {
     console.error("\n❌ Failed to create token:", error.message);
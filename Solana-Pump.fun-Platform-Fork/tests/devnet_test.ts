@@ -1,13 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
-import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID, 
-  getAssociatedTokenAddress,
-  getAccount
-} from "@solana/spl-token";
+import * as client from "../client/client";
+import { Keypair, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getAccount } from "@solana/spl-token";
 
 describe("Devnet Testing - Step by Step", () => {
   // Setup
@@ -17,18 +13,15 @@ describe("Devnet Testing - Step by Step", () => {
   const program = anchor.workspace.Pumpdotfun as Program<Pumpdotfun>;
   const payer = provider.wallet;
   
-  // Token metadata program
-  const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-  
   // Accounts to be used
   let dexConfigPDA: PublicKey;
   let mintKeypair: Keypair;
   let mintAccount: PublicKey;
-  let metadataAccount: PublicKey;
   let tokenAccount: PublicKey;
   let pool: PublicKey;
   let poolTokenAccount: PublicKey;
   let poolSolVault: PublicKey;
+  let totalSupply: BN;
   
   // Test user
   const testUser = Keypair.generate();
@@ -44,10 +37,7 @@ describe("Devnet Testing - Step by Step", () => {
   
   before(async () => {
     // Derive DEX config PDA
-    [dexConfigPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("CurveConfiguration")],
-      program.programId
-    );
+    dexConfigPDA = client.configAddress(program.programId);
     
     console.log("DEX Config PDA:", dexConfigPDA.toString());
     
@@ -86,14 +76,8 @@ describe("Devnet Testing - Step by Step", () => {
         console.log("Initializing DEX configuration...");
         console.log("  Fee:", fee / 100, "%");
         
-        const tx = await program.methods
-          .initialize(fee)
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            admin: payer.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-          })
+        const tx = await client
+          .initialize(program, payer.publicKey, fee)
           .rpc();
         
         console.log("Transaction:", tx);
@@ -120,26 +104,13 @@ describe("Devnet Testing - Step by Step", () => {
       mintKeypair = Keypair.generate();
       mintAccount = mintKeypair.publicKey;
       
-      // Derive metadata PDA
-      [metadataAccount] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("metadata"),
-          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-          mintAccount.toBuffer(),
-        ],
-        TOKEN_METADATA_PROGRAM_ID
-      );
-      
       // Get associated token address
-      tokenAccount = await getAssociatedTokenAddress(
-        mintAccount,
-        payer.publicKey
-      );
+      tokenAccount = client.userTokenAccount(mintAccount, payer.publicKey);
       
       const name = "PumpTest";
       const symbol = "PUMP";
       const uri = "https://arweave.net/test-metadata";
-      const totalSupply = new BN("1000000000000000000"); // 1 billion with 9 decimals
+      totalSupply = new BN("1000000000000000000"); // 1 billion with 9 decimals
       
       console.log("Creating token...");
       console.log("  Name:", name);
@@ -148,27 +119,15 @@ describe("Devnet Testing - Step by Step", () => {
       console.log("  Mint Address:", mintAccount.toString());
       
       try {
-        const tx = await program.methods
-          .createToken(name, symbol, uri, totalSupply)
-          .accounts({
-            payer: payer.publicKey,
-            mintAccount,
-            metadataAccount,
-            tokenAccount,
-            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            systemProgram: SystemProgram.programId,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-          })
-          .signers([mintKeypair])
+        const tx = await client
+          .createToken(program, payer.publicKey, mintKeypair, { name, symbol, uri }, totalSupply)
           .rpc();
         
         console.log("Transaction:", tx);
         console.log("Token creation successful!");
         
         // Verify token creation
-        const tokenAccountInfo = await getAccount(provider.connection, tokenAccount);
+        const tokenAccountInfo = await getAccount(provider.connection, tokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
         console.log("Creator's token balance:", tokenAccountInfo.amount.toString());
         
       } catch (error) {
@@ -182,36 +141,16 @@ describe("Devnet Testing - Step by Step", () => {
     it("Should create a liquidity pool", async () => {
       console.log("\n========== STEP 3: CREATE LIQUIDITY POOL ==========");
       
-      // Derive pool PDA
-      [pool] = PublicKey.findProgramAddressSync(
-        [Buffer.from("liquidity_pool"), mintAccount.toBuffer()],
-        program.programId
-      );
-      
-      // Get pool's associated token account
-      poolTokenAccount = await getAssociatedTokenAddress(
-        mintAccount,
-        pool,
-        true // allowOwnerOffCurve
-      );
+      // Derive pool PDAs
+      ({ pool, poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAccount));
       
       console.log("Creating liquidity pool...");
       console.log("  Pool PDA:", pool.toString());
       console.log("  Pool Token Account:", poolTokenAccount.toString());
       
       try {
-        const tx = await program.methods
-          .createPool()
-          .accounts({
-            pool,
-            tokenMint: mintAccount,
-            poolTokenAccount,
-            payer: payer.publicKey,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-          })
+        const tx = await client
+          .createPool(program, payer.publicKey, mintAccount)
           .rpc();
         
         console.log("Transaction:", tx);
@@ -221,7 +160,7 @@ describe("Devnet Testing - Step by Step", () => {
         const poolAccount = await program.account.liquidityPool.fetch(pool);
         console.log("Pool verification:");
         console.log("  Creator:", poolAccount.creator.toString());
-        console.log("  Token Mint:", poolAccount.tokenMint.toString());
+        console.log("  Token Mint:", poolAccount.token.toString());
         
       } catch (error) {
         console.error("Error creating pool:", error);
@@ -234,30 +173,14 @@ describe("Devnet Testing - Step by Step", () => {
     it("Should add initial liquidity", async () => {
       console.log("\n========== STEP 4: ADD LIQUIDITY ==========");
       
-      // Derive pool SOL vault
-      [poolSolVault] = PublicKey.findProgramAddressSync(
-        [Buffer.from("sol-vault"), pool.toBuffer()],
-        program.programId
-      );
-      
       console.log("Adding liquidity...");
       console.log("  Pool SOL Vault:", poolSolVault.toString());
       
       try {
-        const tx = await program.methods
-          .addLiquidity()
-          .accounts({
-            pool,
-            tokenMint: mintAccount,
-            poolTokenAccount,
-            userTokenAccount: tokenAccount,
-            poolSolVault,
-            user: payer.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          })
+        // The whole supply goes in, trading cannot open with tokens held outside the pool
+        const seedSol = new BN(0.01 * LAMPORTS_PER_SOL);
+        const tx = await client
+          .addLiquidity(program, payer.publicKey, mintAccount, totalSupply, seedSol)
           .rpc();
         
         console.log("Transaction:", tx);
@@ -267,7 +190,7 @@ describe("Devnet Testing - Step by Step", () => {
         const solBalance = await provider.connection.getBalance(poolSolVault);
         console.log("Pool SOL balance:", solBalance / LAMPORTS_PER_SOL, "SOL");
         
-        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
         console.log("Pool token balance:", poolTokenAccountInfo.amount.toString());
         
       } catch (error) {
@@ -275,6 +198,25 @@ describe("Devnet Testing - Step by Step", () => {
         throw error;
       }
     });
+    
+    it("Should open the pool for trading", async () => {
+      console.log("Opening trading...");
+      
+      try {
+        // Keep the seed locked for the shortest period the configuration allows
+        const dexConfig = await program.account.curveConfiguration.fetch(dexConfigPDA);
+        const tx = await client
+          .openTrading(program, payer.publicKey, mintAccount, dexConfig.liquidityLockPeriod)
+          .rpc();
+        
+        console.log("Transaction:", tx);
+        console.log("Trading opened!");
+        
+      } catch (error) {
+        console.error("Error opening trading:", error);
+        throw error;
+      }
+    });
   });
   
   describe("Step 5: Buy Tokens", () => {
@@ -284,10 +226,7 @@ describe("Devnet Testing - Step by Step", () => {
       const buyAmount = new BN(0.1 * LAMPORTS_PER_SOL); // Buy with 0.1 SOL
       
       // Get test user's token account
-      testUserTokenAccount = await getAssociatedTokenAddress(
-        mintAccount,
-        testUser.publicKey
-      );
+      testUserTokenAccount = client.userTokenAccount(mintAccount, testUser.publicKey);
       
       console.log("Test user buying tokens...");
       console.log("  Amount:", buyAmount.toNumber() / LAMPORTS_PER_SOL, "SOL");
@@ -298,21 +237,8 @@ describe("Devnet Testing - Step by Step", () => {
         const initialSol = await provider.connection.getBalance(testUser.publicKey);
         console.log("Initial SOL balance:", initialSol / LAMPORTS_PER_SOL, "SOL");
         
-        const tx = await program.methods
-          .buy(buyAmount)
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            pool,
-            tokenMint: mintAccount,
-            poolTokenAccount,
-            poolSolVault,
-            userTokenAccount: testUserTokenAccount,
-            user: testUser.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          })
+        const tx = await client
+          .buy(program, testUser.publicKey, mintAccount, buyAmount, new BN(0))
           .signers([testUser])
           .rpc();
         
@@ -321,7 +247,7 @@ describe("Devnet Testing - Step by Step", () => {
         
         // Get final balances
         const finalSol = await provider.connection.getBalance(testUser.publicKey);
-        const tokenBalance = await getAccount(provider.connection, testUserTokenAccount);
+        const tokenBalance = await getAccount(provider.connection, testUserTokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
         
         console.log("Results:");
         console.log("  Final SOL balance:", finalSol / LAMPORTS_PER_SOL, "SOL");
@@ -340,7 +266,7 @@ describe("Devnet Testing - Step by Step", () => {
       
       try {
         // Get current token balance
-        const tokenAccountInfo = await getAccount(provider.connection, testUserTokenAccount);
+        const tokenAccountInfo = await getAccount(provider.connection, testUserTokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
         const currentBalance = tokenAccountInfo.amount;
         
         if (currentBalance === BigInt(0)) {
@@ -358,20 +284,8 @@ describe("Devnet Testing - Step by Step", () => {
         // Get initial SOL balance
         const initialSol = await provider.connection.getBalance(testUser.publicKey);
         
-        const tx = await program.methods
-          .sell(sellAmount)
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            pool,
-            tokenMint: mintAccount,
-            poolTokenAccount,
-            poolSolVault,
-            userTokenAccount: testUserTokenAccount,
-            user: testUser.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
+        const tx = await client
+          .sell(program, testUser.publicKey, mintAccount, sellAmount, new BN(0))
           .signers([testUser])
           .rpc();
         
@@ -380,7 +294,7 @@ describe("Devnet Testing - Step by Step", () => {
         
         // Get final balances
         const finalSol = await provider.connection.getBalance(testUser.publicKey);
-        const finalTokens = await getAccount(provider.connection, testUserTokenAccount);
+        const finalTokens = await getAccount(provider.connection, testUserTokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
         
         console.log("Results:");
         console.log("  SOL received:", (finalSol - initialSol) / LAMPORTS_PER_SOL, "SOL");
@@ -401,14 +315,11 @@ describe("Devnet Testing - Step by Step", () => {
       try {
         const initialBalance = await provider.connection.getBalance(payer.publicKey);
         
-        const tx = await program.methods
-          .withdraw()
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            admin: payer.publicKey,
-            systemProgram: SystemProgram.programId,
-          })
-          .rpc();
+        const feeVault = await program.account.protocolFeeVault.fetch(
+          client.protocolFeeVaultAddress(program.programId)
+        );
+        
+        const tx = await client.withdraw(program, payer.publicKey, payer.publicKey, feeVault.fees).rpc();
         
         console.log("Transaction:", tx);
         console.log("Withdrawal successful!");
@@ -435,7 +346,6 @@ describe("Devnet Testing - Step by Step", () => {
       if (mintAccount) {
         console.log("\nToken Information:");
         console.log("  Mint:", mintAccount.toString());
-        console.log("  Metadata:", metadataAccount.toString());
         
         if (pool) {
           console.log("\nPool Information:");
@@ -445,7 +355,7 @@ describe("Devnet Testing - Step by Step", () => {
           
           try {
             const poolBalance = await provider.connection.getBalance(poolSolVault);
-            const poolTokens = await getAccount(provider.connection, poolTokenAccount);
+            const poolTokens = await getAccount(provider.connection, poolTokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
             console.log("\nPool Liquidity:");
             console.log("  SOL:", poolBalance / LAMPORTS_PER_SOL);
             console.log("  Tokens:", poolTokens.amount.toString());
@@ -1,13 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
-import { SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID, 
-  getAssociatedTokenAddress,
-  getAccount
-} from "@solana/spl-token";
+import * as client from "../client/client";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
 
 describe("Fix Pool and Test Buy", () => {
   // Setup
@@ -23,6 +19,7 @@ describe("Fix Pool and Test Buy", () => {
   let pool: PublicKey;
   let poolTokenAccount: PublicKey;
   let poolSolVault: PublicKey;
+  let tokenProgram: PublicKey;
   
   console.log("=====================================");
   console.log("FIX POOL STATE TEST");
@@ -34,23 +31,9 @@ describe("Fix Pool and Test Buy", () => {
   
   before(async () => {
     // Derive pool PDA
-    [pool] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_pool"), mintAddress.toBuffer()],
-      program.programId
-    );
-    
-    // Get pool's associated token account
-    poolTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      pool,
-      true
-    );
-    
-    // Derive pool SOL vault
-    [poolSolVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_sol_vault"), mintAddress.toBuffer()],
-      program.programId
-    );
+    // Pools created before Token-2022 launches hold legacy SPL Token mints
+    tokenProgram = await client.mintTokenProgram(provider.connection, mintAddress);
+    ({ pool, poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAddress, tokenProgram));
     
     console.log("Pool Accounts:");
     console.log("  Pool:", pool.toString());
@@ -69,7 +52,7 @@ describe("Fix Pool and Test Buy", () => {
         
         console.log("Pool Account Data:");
         console.log("  Creator:", poolAccount.creator.toString());
-        console.log("  Token Mint:", poolAccount.tokenMint ? poolAccount.tokenMint.toString() : "N/A");
+        console.log("  Token Mint:", poolAccount.token ? poolAccount.token.toString() : "N/A");
         console.log("  Reserve Token:", poolAccount.reserveToken ? poolAccount.reserveToken.toString() : "0");
         console.log("  Reserve SOL:", poolAccount.reserveSol ? poolAccount.reserveSol.toString() : "0");
         
@@ -88,7 +71,7 @@ describe("Fix Pool and Test Buy", () => {
         
         // Check actual balances
         const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         
         console.log("\nActual Pool Balances:");
         console.log("  SOL Vault Balance:", poolSolBalance / LAMPORTS_PER_SOL, "SOL");
@@ -112,32 +95,20 @@ describe("Fix Pool and Test Buy", () => {
       console.log("\n========== CALLING ADD LIQUIDITY ==========");
       
       try {
-        const userTokenAccount = await getAssociatedTokenAddress(
-          mintAddress,
-          payer.publicKey
-        );
+        const userTokenAccount = client.userTokenAccount(mintAddress, payer.publicKey, tokenProgram);
         
         console.log("Calling add_liquidity instruction...");
         console.log("  User Token Account:", userTokenAccount.toString());
         
         // Check user's token balance first
-        const userTokenAccountInfo = await getAccount(provider.connection, userTokenAccount);
+        const userTokenAccountInfo = await getAccount(provider.connection, userTokenAccount, undefined, tokenProgram);
         console.log("  User has tokens:", userTokenAccountInfo.amount.toString());
         
-        const tx = await program.methods
-          .addLiquidity()
-          .accounts({
-            pool,
-            tokenMint: mintAddress,
-            poolTokenAccount,
-            userTokenAccount,
-            poolSolVault,
-            user: payer.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          })
+        // Deposit everything the wallet holds, the program takes SOL in proportion to the reserves
+        const tokenAmount = new BN(userTokenAccountInfo.amount.toString());
+        const maxSolAmount = new BN(0.01 * LAMPORTS_PER_SOL);
+        const tx = await client
+          .addLiquidity(program, payer.publicKey, mintAddress, tokenAmount, maxSolAmount, tokenProgram)
           .rpc();
         
         console.log("Transaction:", tx);
@@ -169,7 +140,7 @@ describe("Fix Pool and Test Buy", () => {
         // Final check
         const poolAccount = await program.account.liquidityPool.fetch(pool);
         const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         
         console.log("Pool Internal State:");
         console.log("  Reserve Token:", poolAccount.reserveToken ? poolAccount.reserveToken.toString() : "0");
@@ -2,69 +2,33 @@ import BN from "bn.js";
 import * as web3 from "@solana/web3.js";
 import * as anchor from "@coral-xyz/anchor";
 import type { Pumpdotfun } from "../target/types/pumpdotfun";
+import * as client from "../client/client";
 describe("Test", () => {
   // Configure the client to use the local cluster
   anchor.setProvider(anchor.AnchorProvider.env());
 
   const program = anchor.workspace.Pumpdotfun as anchor.Program<Pumpdotfun>;
   
-  const TOKEN_METADATA_PROGRAM_ID = new web3.PublicKey(
-    "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
-  );
-
-  const dex_config_pda = web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("CurveConfiguration")],
-    program.programId
-  )[0];
+  const dex_config_pda = client.configAddress(program.programId);
   console.log("dex_config_pda", dex_config_pda.toBase58());
 
   const mintKeypair = web3.Keypair.generate();
   const mintAccount = mintKeypair.publicKey; //new web3.PublicKey("7uAjXWD6Jt5CTsJ3Huuh9abiXLd4gyNrJ8uwv98gshy7"); // 
   console.log("mintAccount", mintAccount.toBase58());
 
-  const metadataAccount = web3.PublicKey.findProgramAddressSync(
-    [
-      Buffer.from("metadata"),
-      TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-      mintAccount.toBuffer(),
-    ],
-    TOKEN_METADATA_PROGRAM_ID
-  )[0];
-  console.log("metadataAccount", metadataAccount.toBase58());
-
-  const tokenAccount = anchor.utils.token.associatedAddress({
-    mint: mintAccount,
-    owner: program.provider.publicKey,
-  });
+  const tokenAccount = client.userTokenAccount(mintAccount, program.provider.publicKey);
   console.log("tokenAccount", tokenAccount.toBase58());
 
-  const pool = web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("liquidity_pool"), mintAccount.toBuffer()],
-    program.programId
-  )[0];
+  const { pool, poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAccount);
   console.log("pool", pool.toBase58());
-
-  const poolTokenAccount = anchor.utils.token.associatedAddress({
-    mint: mintAccount,
-    owner: pool,
-  });
   console.log("poolTokenAccount", poolTokenAccount.toBase58());
-
-  const poolSolVault = web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("liquidity_sol_vault"), mintAccount.toBuffer()],
-    program.programId
-  )[0];
   console.log("poolSolVault", poolSolVault.toBase58());
 
+  const totalSupply = new BN("1000000000000000000");
+
   // it("Initialize", async () => {
-  //   await program.methods
-  //     .initialize(100)
-  //     .accounts({
-  //       dexConfigurationAccount: dex_config_pda,
-  //       systemProgram: web3.SystemProgram.programId,
-  //       admin: program.provider.publicKey,
-  //       rent: web3.SYSVAR_RENT_PUBKEY,
-  //     })
+  //   await client
+  //     .initialize(program, program.provider.publicKey, 100)
   //     .rpc()
   //     .catch((e) => console.log(e));
   // });
@@ -73,57 +37,30 @@ describe("Test", () => {
     let name = "JIH";
     let symbol = "JIH";
     let uri = "https://arweave.net/CtrUELBQ9adDTwuCsTkka1pku0R_XLYzePm_lWiL0yA";
-    let totalSupply = new BN("1000000000000000000");
-    await program.methods
-      .createToken(name, symbol, uri, totalSupply)
-      .accounts({
-        payer: program.provider.publicKey,
-        mintAccount,
-        metadataAccount,
-        tokenAccount,
-        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
-        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-        systemProgram: web3.SystemProgram.programId,
-        rent: web3.SYSVAR_RENT_PUBKEY,
-      })
-      .signers([mintKeypair])
+    await client
+      .createToken(program, program.provider.publicKey, mintKeypair, { name, symbol, uri }, totalSupply)
       .rpc()
       .catch((e) => console.log(e));
   });
 
   it("Create Pool", async () => {
-    await program.methods
-      .createPool()
-      .accounts({
-        pool,
-        tokenMint: mintAccount,
-        poolTokenAccount,
-        payer: program.provider.publicKey,
-        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-        rent: web3.SYSVAR_RENT_PUBKEY,
-        systemProgram: web3.SystemProgram.programId,
-      })
+    await client
+      .createPool(program, program.provider.publicKey, mintAccount)
       .rpc()
       .catch((e) => console.log(e));
   });
 
   it("Add Liquidity", async () => {
-    await program.methods
-      .addLiquidity()
-      .accounts({
-        pool,
-        tokenMint: mintAccount,
-        poolTokenAccount,
-        userTokenAccount: tokenAccount,
-        poolSolVault,
-        user: program.provider.publicKey,
-        rent: web3.SYSVAR_RENT_PUBKEY,
-        systemProgram: web3.SystemProgram.programId,
-        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-      })
+    await client
+      .addLiquidity(program, program.provider.publicKey, mintAccount, totalSupply, new BN(1e7))
+      .rpc()
+      .catch((e) => console.log(e));
+  });
+
+  it("Open Trading", async () => {
+    const dexConfigData = await program.account.curveConfiguration.fetch(dex_config_pda);
+    await client
+      .openTrading(program, program.provider.publicKey, mintAccount, dexConfigData.liquidityLockPeriod)
       .rpc()
       .catch((e) => console.log(e));
   });
@@ -137,21 +74,8 @@ describe("Test", () => {
     console.log("Token Balance:", tokenAccountInfo.value.uiAmount);
 
     const solAmount = new BN(1e9);
-    await program.methods
-      .buy(solAmount)
-      .accounts({
-        dexConfigurationAccount: dex_config_pda,
-        pool,
-        tokenMint: mintAccount,
-        poolTokenAccount,
-        poolSolVault,
-        userTokenAccount: tokenAccount,
-        user: program.provider.publicKey,
-        rent: web3.SYSVAR_RENT_PUBKEY,
-        systemProgram: web3.SystemProgram.programId,
-        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-      })
+    await client
+      .buy(program, program.provider.publicKey, mintAccount, solAmount, new BN(0))
       .rpc()
       .catch((e) => console.log(e));
 
@@ -163,21 +87,8 @@ describe("Test", () => {
 
   it("Buy token second", async () => {
     const solAmount = new BN(1e9);
-    await program.methods
-      .buy(solAmount)
-      .accounts({
-        dexConfigurationAccount: dex_config_pda,
-        pool,
-        tokenMint: mintAccount,
-        poolTokenAccount,
-        poolSolVault,
-        userTokenAccount: tokenAccount,
-        user: program.provider.publicKey,
-        rent: web3.SYSVAR_RENT_PUBKEY,
-        systemProgram: web3.SystemProgram.programId,
-        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-      })
+    await client
+      .buy(program, program.provider.publicKey, mintAccount, solAmount, new BN(0))
       .rpc()
       .catch((e) => console.log(e));
 
@@ -191,21 +102,8 @@ describe("Test", () => {
 
   it("Sell token", async () => {
     const tokenAmount = new BN(1e15);
-    await program.methods
-      .sell(tokenAmount)
-      .accounts({
-        dexConfigurationAccount: dex_config_pda,
-        pool,
-        tokenMint: mintAccount,
-        poolTokenAccount,
-        poolSolVault,
-        userTokenAccount: tokenAccount,
-        user: program.provider.publicKey,
-        rent: web3.SYSVAR_RENT_PUBKEY,
-        systemProgram: web3.SystemProgram.programId,
-        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-      })
+    await client
+      .sell(program, program.provider.publicKey, mintAccount, tokenAmount, new BN(0))
       .rpc()
       .catch((e) => console.log(e));
 
@@ -220,33 +118,29 @@ describe("Test", () => {
   it("withdraw", async () => {
     let balance = await program.provider.connection.getBalance(program.provider.publicKey);
     console.log(`My balance: ${balance / web3.LAMPORTS_PER_SOL} SOL`);
+    const feeVaultPda = client.protocolFeeVaultAddress(program.programId);
     let dexConfigData = await program.account.curveConfiguration.fetch(
       dex_config_pda
     );
+    let feeVaultData = await program.account.protocolFeeVault.fetch(feeVaultPda);
     console.log({
       fee: dexConfigData.fees.toLocaleString(),
       admin: dexConfigData.admin.toBase58(),
-      shares: Number(dexConfigData.shares.toString()) / web3.LAMPORTS_PER_SOL,
+      fees: Number(feeVaultData.fees.toString()) / web3.LAMPORTS_PER_SOL,
     });
 
-    await program.methods
-      .withdraw()
-      .accounts({
-        dexConfigurationAccount: dex_config_pda,
-        admin: program.provider.publicKey,
-      })
+    await client
+      .withdraw(program, program.provider.publicKey, program.provider.publicKey, feeVaultData.fees)
       .rpc()
       .catch((e) => console.log(e));
 
     balance = await program.provider.connection.getBalance(program.provider.publicKey);
     console.log(`My balance: ${balance / web3.LAMPORTS_PER_SOL} SOL`);
-    dexConfigData = await program.account.curveConfiguration.fetch(
-      dex_config_pda
-    );
+    feeVaultData = await program.account.protocolFeeVault.fetch(feeVaultPda);
     console.log({
       fee: dexConfigData.fees.toLocaleString(),
       admin: dexConfigData.admin.toBase58(),
-      shares: Number(dexConfigData.shares.toString()) / web3.LAMPORTS_PER_SOL,
+      fees: Number(feeVaultData.fees.toString()) / web3.LAMPORTS_PER_SOL,
     });
   });
 });
@@ -0,0 +1,183 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorError, BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  transferChecked,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { Pumpdotfun } from "../target/types/pumpdotfun";
+import { MockAmm } from "../target/types/mock_amm";
+import * as client from "../client/client";
+
+// Runs against a local validator with `programs/mock-amm` deployed: a pool graduates, a
+// faulty AMM migration is rolled back, the real one goes through and the providers take
+// their seed back out.
+describe("graduation and migration", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Pumpdotfun as Program<Pumpdotfun>;
+  const amm = anchor.workspace.MockAmm as Program<MockAmm>;
+  const connection = provider.connection;
+
+  const admin = provider.wallet as anchor.Wallet;
+  const secondProvider = Keypair.generate();
+  const buyer = Keypair.generate();
+  const mint = Keypair.generate();
+
+  const decimals = 9;
+  const totalSupply = new BN("1000000000000000000"); // 1 billion tokens with 9 decimals
+  const secondDeposit = totalSupply.muln(2).divn(5);
+  const graduationThreshold = new BN(2 * LAMPORTS_PER_SOL);
+
+  const addresses = client.poolAddresses(program.programId, mint.publicKey);
+  const ammPool = PublicKey.findProgramAddressSync([Buffer.from("pool"), mint.publicKey.toBuffer()], amm.programId)[0];
+  const ammAccounts = {
+    program: amm.programId,
+    pool: ammPool,
+    tokenAccount: getAssociatedTokenAddressSync(mint.publicKey, ammPool, true, TOKEN_2022_PROGRAM_ID),
+  };
+
+  async function airdrop(publicKey: PublicKey, sol: number) {
+    const signature = await connection.requestAirdrop(publicKey, sol * LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(signature);
+  }
+
+  function prepareAmmPool(tokenShortfall: BN) {
+    return amm.methods
+      .preparePool(tokenShortfall)
+      .accountsPartial({ ammPool, tokenMint: mint.publicKey, payer: admin.publicKey })
+      .rpc();
+  }
+
+  before(async () => {
+    await airdrop(secondProvider.publicKey, 10);
+    await airdrop(buyer.publicKey, 10);
+
+    try {
+      await client.initialize(program, admin.publicKey, 100).rpc();
+    } catch (e) {
+      console.log("DEX configuration already initialized");
+    }
+    await client
+      .updateConfiguration(program, admin.publicKey, { graduationThreshold, ammProgram: amm.programId })
+      .rpc();
+  });
+
+  it("graduates a pool funded by two providers", async () => {
+    const metadata = { name: "Migration Test", symbol: "MIGR", uri: "https://example.com/migr.json" };
+    await client.createToken(program, admin.publicKey, mint, metadata, totalSupply).rpc();
+    await client.createPool(program, admin.publicKey, mint.publicKey).rpc();
+
+    // The second provider's tokens come from the creator, so the pool still ends up with
+    // the whole supply
+    const secondAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      admin.payer,
+      mint.publicKey,
+      secondProvider.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await transferChecked(
+      connection,
+      admin.payer,
+      client.userTokenAccount(mint.publicKey, admin.publicKey),
+      mint.publicKey,
+      secondAccount.address,
+      admin.publicKey,
+      BigInt(secondDeposit.toString()),
+      decimals,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await client
+      .addLiquidity(program, admin.publicKey, mint.publicKey, totalSupply.sub(secondDeposit), new BN(LAMPORTS_PER_SOL))
+      .rpc();
+    await client
+      .addLiquidity(program, secondProvider.publicKey, mint.publicKey, secondDeposit, new BN(LAMPORTS_PER_SOL))
+      .signers([secondProvider])
+      .rpc();
+
+    // The lock would keep the seed in for an hour, graduation releases it early
+    await client.openTrading(program, admin.publicKey, mint.publicKey, new BN(3600)).rpc();
+    await client
+      .buy(program, buyer.publicKey, mint.publicKey, new BN(3 * LAMPORTS_PER_SOL), new BN(0))
+      .signers([buyer])
+      .rpc();
+
+    const pool = await program.account.liquidityPool.fetch(addresses.pool);
+    assert.deepEqual(pool.status, { graduated: {} });
+    assert.isTrue(pool.reserveSol.gte(graduationThreshold));
+  });
+
+  it("rolls the migration back when the AMM leaves tokens behind", async () => {
+    await prepareAmmPool(new BN(1));
+
+    try {
+      await client.migrate(program, admin.publicKey, mint.publicKey, ammAccounts).rpc();
+      assert.fail("migration should have failed");
+    } catch (e) {
+      assert.instanceOf(e, AnchorError);
+      assert.equal((e as AnchorError).error.errorCode.code, "MigrationFailed");
+    }
+
+    const pool = await program.account.liquidityPool.fetch(addresses.pool);
+    assert.deepEqual(pool.status, { graduated: {} });
+  });
+
+  it("moves everything but the seed and the vault rent into the AMM", async () => {
+    await prepareAmmPool(new BN(0));
+
+    const before = await program.account.liquidityPool.fetch(addresses.pool);
+    const vaultBefore = await connection.getBalance(addresses.poolSolVault);
+    const tokensBefore = (await getAccount(connection, addresses.poolTokenAccount, undefined, TOKEN_2022_PROGRAM_ID))
+      .amount;
+    const vaultRent = await connection.getMinimumBalanceForRentExemption(0);
+
+    await client.migrate(program, admin.publicKey, mint.publicKey, ammAccounts).rpc();
+
+    const pool = await program.account.liquidityPool.fetch(addresses.pool);
+    assert.deepEqual(pool.status, { closed: {} });
+    assert.equal(pool.reserveSol.toString(), before.seedSol.toString());
+
+    const ammState = await amm.account.pool.fetch(ammPool);
+    assert.equal(ammState.reserveSol.toNumber(), vaultBefore - before.seedSol.toNumber() - vaultRent);
+    assert.equal(ammState.reserveToken.toString(), tokensBefore.toString());
+    assert.equal(await connection.getBalance(addresses.poolSolVault), before.seedSol.toNumber() + vaultRent);
+  });
+
+  it("lets both providers withdraw their seed", async () => {
+    const vaultRent = await connection.getMinimumBalanceForRentExemption(0);
+
+    const withdrawals = [
+      { user: admin.publicKey, signers: [] as Keypair[] },
+      { user: secondProvider.publicKey, signers: [secondProvider] },
+    ];
+    for (const { user, signers } of withdrawals) {
+      const providerAccount = client.liquidityProviderAddress(program.programId, addresses.pool, user);
+      const { shares } = await program.account.liquidityProvider.fetch(providerAccount);
+      const { seedSol, totalShares } = await program.account.liquidityPool.fetch(addresses.pool);
+      const vaultBefore = await connection.getBalance(addresses.poolSolVault);
+
+      await client.removeLiquidity(program, user, mint.publicKey, shares).signers(signers).rpc();
+
+      const paid = vaultBefore - (await connection.getBalance(addresses.poolSolVault));
+      assert.equal(paid, seedSol.mul(shares).div(totalShares).toNumber());
+    }
+
+    // The last provider out takes the rest of the seed, the rent stays
+    const pool = await program.account.liquidityPool.fetch(addresses.pool);
+    assert.equal(pool.totalShares.toNumber(), 0);
+    assert.equal(pool.seedSol.toNumber(), 0);
+    assert.equal(await connection.getBalance(addresses.poolSolVault), vaultRent);
+  });
+});
@@ -1,15 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
-import { Program } from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
-import { PublicKey, SystemProgram, LAMPORTS_PER_SOL, Keypair } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID,
-  createAssociatedTokenAccountInstruction,
-  getAssociatedTokenAddress,
-  createAssociatedTokenAccount,
-  getAccount
-} from "@solana/spl-token";
+import * as client from "../client/client";
+import { PublicKey, LAMPORTS_PER_SOL, Keypair } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getAccount } from "@solana/spl-token";
 import { assert } from "chai";
 
 describe("pumpdotfun", () => {
@@ -28,13 +22,13 @@ describe("pumpdotfun", () => {
   const tokenName = "SounDAO Token123";
   const tokenSymbol = "SDT123";
   const tokenUri = "https://apricot-selective-kangaroo-871.mypinata.cloud/ipfs/bafkreihf2pleocdw3t4egry46rbgctlwi53eggw34imhn3bvs5wjq6s5i4";
-  const initialBuy = new anchor.BN(1 * LAMPORTS_PER_SOL); // 100 SOL initial buy
+  const totalSupply = new anchor.BN("1000000000000000000"); // 1 billion tokens with 9 decimals
+  const initialBuy = new anchor.BN(1 * LAMPORTS_PER_SOL); // 1 SOL initial buy
   
   let mint: PublicKey;
-  let bondingCurve: PublicKey;
-  let associatedBondingCurve: PublicKey;
-  let globalState: PublicKey;
-  let metadata: PublicKey;
+  let pool: PublicKey;
+  let poolSolVault: PublicKey;
+  let dexConfig: PublicKey;
   
   // Helper function to airdrop SOL
   // async function airdropSol(publicKey: PublicKey, amount: number) {
@@ -48,8 +42,8 @@ describe("pumpdotfun", () => {
   // Helper function to get token balance
   async function getTokenBalance(wallet: PublicKey): Promise<number> {
     try {
-      const ata = await getAssociatedTokenAddress(mint, wallet);
-      const account = await getAccount(provider.connection, ata);
+      const ata = client.userTokenAccount(mint, wallet);
+      const account = await getAccount(provider.connection, ata, undefined, TOKEN_2022_PROGRAM_ID);
       return Number(account.amount);
     } catch (e) {
       return 0;
@@ -64,24 +58,15 @@ describe("pumpdotfun", () => {
     await airdropSol(buyer2.publicKey, 1000);
     
     // Derive PDAs
-    [globalState] = PublicKey.findProgramAddressSync(
-      [Buffer.from("global")],
-      program.programId
-    );
+    dexConfig = client.configAddress(program.programId);
+    console.log("DEX Config:", dexConfig.toString());
     
-    // Initialize global state if not already initialized
+    // Initialize the DEX configuration if not already initialized
     try {
-      await program.methods
-        .initializeGlobal()
-        .accounts({
-          authority: creator.publicKey,
-          globalState: globalState,
-          systemProgram: SystemProgram.programId,
-        })
-        .rpc();
-      console.log("Global state initialized");
+      await client.initialize(program, creator.publicKey, 100).rpc();
+      console.log("DEX configuration initialized");
     } catch (e) {
-      console.log("Global state already initialized");
+      console.log("DEX configuration already initialized");
     }
   });
 
@@ -94,32 +79,7 @@ describe("pumpdotfun", () => {
       mint = mintKeypair.publicKey;
       
       // Derive PDAs
-      [bondingCurve] = PublicKey.findProgramAddressSync(
-        [Buffer.from("bonding_curve"), mint.toBuffer()],
-        program.programId
-      );
-      
-      associatedBondingCurve = await getAssociatedTokenAddress(
-        mint,
-        bondingCurve,
-        true
-      );
-      
-      const associatedUser = await getAssociatedTokenAddress(
-        mint,
-        creator.publicKey
-      );
-      
-      // Derive metadata PDA (Metaplex standard)
-      const metadataProgramId = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-      [metadata] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("metadata"),
-          metadataProgramId.toBuffer(),
-          mint.toBuffer(),
-        ],
-        metadataProgramId
-      );
+      ({ pool, poolSolVault } = client.poolAddresses(program.programId, mint));
       
       console.log("Creating token with:");
       console.log("  Name:", tokenName);
@@ -127,39 +87,29 @@ describe("pumpdotfun", () => {
       console.log("  Initial Buy:", initialBuy.toString(), "lamports");
       console.log("  Mint:", mint.toString());
       
-      const tx = await program.methods
-        .create(tokenName, tokenSymbol, tokenUri, initialBuy)
-        .accounts({
-          creator: creator.publicKey,
-          mint: mint,
-          bondingCurve: bondingCurve,
-          associatedBondingCurve: associatedBondingCurve,
-          associatedUser: associatedUser,
-          globalState: globalState,
-          metadata: metadata,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-          tokenMetadataProgram: metadataProgramId,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-        })
-        .signers([mintKeypair])
+      // The token, its pool and the creator's initial buy are one transaction
+      const tx = await client
+        .launchToken(
+          program,
+          creator.publicKey,
+          mintKeypair,
+          { name: tokenName, symbol: tokenSymbol, uri: tokenUri },
+          totalSupply,
+          { amount: initialBuy, minAmountOut: new BN(0) }
+        )
         .rpc();
       
       console.log("Transaction signature:", tx);
       
-      // Verify bonding curve was created
-      const bondingCurveAccount = await program.account.bondingCurve.fetch(bondingCurve);
-      assert.equal(bondingCurveAccount.creator.toString(), creator.publicKey.toString());
-      assert.equal(bondingCurveAccount.mint.toString(), mint.toString());
-      assert.equal(bondingCurveAccount.tokenName, tokenName);
-      assert.equal(bondingCurveAccount.tokenSymbol, tokenSymbol);
+      // Verify the pool was created
+      const poolAccount = await program.account.liquidityPool.fetch(pool);
+      assert.equal(poolAccount.creator.toString(), creator.publicKey.toString());
+      assert.equal(poolAccount.token.toString(), mint.toString());
       
       console.log("Token created successfully!");
-      console.log("  Virtual SOL reserves:", bondingCurveAccount.virtualSolReserves.toString());
-      console.log("  Virtual token reserves:", bondingCurveAccount.virtualTokenReserves.toString());
-      console.log("  Real SOL reserves:", bondingCurveAccount.realSolReserves.toString());
-      console.log("  Real token reserves:", bondingCurveAccount.realTokenReserves.toString());
+      console.log("  Reserve SOL:", poolAccount.reserveSol.toString());
+      console.log("  Reserve token:", poolAccount.reserveToken.toString());
+      console.log("  Tokens sold:", poolAccount.tokensSold.toString());
       
       // Check creator's token balance
       const creatorBalance = await getTokenBalance(creator.publicKey);
@@ -182,24 +132,10 @@ describe("pumpdotfun", () => {
       console.log("  SOL balance:", buyer1InitialSol / LAMPORTS_PER_SOL);
       console.log("  Token balance:", buyer1InitialTokens);
       
-      // Create associated token account for buyer1
-      const buyer1Ata = await getAssociatedTokenAddress(mint, buyer1.publicKey);
-      
       console.log("Buying tokens with", buyAmount.toString(), "lamports...");
       
-      const tx = await program.methods
-        .buy(buyAmount, new anchor.BN(0)) // 0 for max_sol_cost (no slippage protection for test)
-        .accounts({
-          user: buyer1.publicKey,
-          bondingCurve: bondingCurve,
-          associatedBondingCurve: associatedBondingCurve,
-          associatedUser: buyer1Ata,
-          mint: mint,
-          globalState: globalState,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        })
+      const tx = await client
+        .buy(program, buyer1.publicKey, mint, buyAmount, new BN(0))
         .signers([buyer1])
         .rpc();
       
@@ -218,11 +154,11 @@ describe("pumpdotfun", () => {
       assert.isBelow(buyer1FinalSol, buyer1InitialSol, "SOL balance should decrease");
       assert.isAbove(buyer1FinalTokens, buyer1InitialTokens, "Token balance should increase");
       
-      // Check bonding curve state
-      const bondingCurveAccount = await program.account.bondingCurve.fetch(bondingCurve);
-      console.log("\nBonding curve after buy:");
-      console.log("  Real SOL reserves:", bondingCurveAccount.realSolReserves.toString());
-      console.log("  Real token reserves:", bondingCurveAccount.realTokenReserves.toString());
+      // Check pool state
+      const poolAccount = await program.account.liquidityPool.fetch(pool);
+      console.log("\nPool after buy:");
+      console.log("  Reserve SOL:", poolAccount.reserveSol.toString());
+      console.log("  Reserve token:", poolAccount.reserveToken.toString());
     });
     
     it("Should handle multiple buyers correctly", async () => {
@@ -231,23 +167,10 @@ describe("pumpdotfun", () => {
       const buyAmount = new anchor.BN(5 * LAMPORTS_PER_SOL); // Buy with 5 SOL
       
       // Buyer2 buys tokens
-      const buyer2Ata = await getAssociatedTokenAddress(mint, buyer2.publicKey);
-      
       console.log("Buyer2 buying tokens...");
       
-      const tx = await program.methods
-        .buy(buyAmount, new anchor.BN(0))
-        .accounts({
-          user: buyer2.publicKey,
-          bondingCurve: bondingCurve,
-          associatedBondingCurve: associatedBondingCurve,
-          associatedUser: buyer2Ata,
-          mint: mint,
-          globalState: globalState,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        })
+      const tx = await client
+        .buy(program, buyer2.publicKey, mint, buyAmount, new BN(0))
         .signers([buyer2])
         .rpc();
       
@@ -282,20 +205,8 @@ describe("pumpdotfun", () => {
       
       console.log("Selling", sellAmount.toString(), "tokens...");
       
-      const buyer1Ata = await getAssociatedTokenAddress(mint, buyer1.publicKey);
-      
-      const tx = await program.methods
-        .sell(sellAmount, new anchor.BN(0)) // 0 for min_sol_output (no slippage protection for test)
-        .accounts({
-          user: buyer1.publicKey,
-          bondingCurve: bondingCurve,
-          associatedBondingCurve: associatedBondingCurve,
-          associatedUser: buyer1Ata,
-          mint: mint,
-          globalState: globalState,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        })
+      const tx = await client
+        .sell(program, buyer1.publicKey, mint, sellAmount, new BN(0))
         .signers([buyer1])
         .rpc();
       
@@ -314,11 +225,11 @@ describe("pumpdotfun", () => {
       assert.isAbove(buyer1FinalSol, buyer1InitialSol, "SOL balance should increase");
       assert.isBelow(buyer1FinalTokens, buyer1InitialTokens, "Token balance should decrease");
       
-      // Check bonding curve state
-      const bondingCurveAccount = await program.account.bondingCurve.fetch(bondingCurve);
-      console.log("\nBonding curve after sell:");
-      console.log("  Real SOL reserves:", bondingCurveAccount.realSolReserves.toString());
-      console.log("  Real token reserves:", bondingCurveAccount.realTokenReserves.toString());
+      // Check pool state
+      const poolAccount = await program.account.liquidityPool.fetch(pool);
+      console.log("\nPool after sell:");
+      console.log("  Reserve SOL:", poolAccount.reserveSol.toString());
+      console.log("  Reserve token:", poolAccount.reserveToken.toString());
     });
   });
 
@@ -326,43 +237,36 @@ describe("pumpdotfun", () => {
     it("Should allow authorized withdrawals", async () => {
       console.log("\n=== Testing Withdraw Functionality ===");
       
-      // Check bonding curve balance before withdrawal
-      const bondingCurveAccount = await program.account.bondingCurve.fetch(bondingCurve);
-      const bcBalance = await provider.connection.getBalance(bondingCurve);
+      // Check the accrued protocol fees before withdrawal
+      const feeVault = client.protocolFeeVaultAddress(program.programId);
+      const feesBefore = (await program.account.protocolFeeVault.fetch(feeVault)).fees;
       
       console.log("Before withdrawal:");
-      console.log("  Bonding curve SOL balance:", bcBalance / LAMPORTS_PER_SOL);
-      console.log("  Bonding curve real SOL reserves:", bondingCurveAccount.realSolReserves.toString());
+      console.log("  Accrued fees:", feesBefore.toNumber() / LAMPORTS_PER_SOL, "SOL");
       
-      if (bcBalance > 0) {
-        const withdrawAmount = new anchor.BN(Math.floor(bcBalance * 0.1)); // Withdraw 10%
+      if (feesBefore.gtn(0)) {
+        const withdrawAmount = feesBefore.divn(10); // Withdraw 10%
         
         console.log("Attempting to withdraw", withdrawAmount.toString(), "lamports...");
         
         try {
-          const tx = await program.methods
-            .withdraw(withdrawAmount)
-            .accounts({
-              authority: creator.publicKey,
-              bondingCurve: bondingCurve,
-              globalState: globalState,
-              systemProgram: SystemProgram.programId,
-            })
+          const tx = await client
+            .withdraw(program, creator.publicKey, creator.publicKey, withdrawAmount)
             .rpc();
           
           console.log("Withdraw transaction:", tx);
           
-          const bcBalanceAfter = await provider.connection.getBalance(bondingCurve);
+          const feesAfter = (await program.account.protocolFeeVault.fetch(feeVault)).fees;
           console.log("After withdrawal:");
-          console.log("  Bonding curve SOL balance:", bcBalanceAfter / LAMPORTS_PER_SOL);
-          console.log("  Amount withdrawn:", (bcBalance - bcBalanceAfter) / LAMPORTS_PER_SOL, "SOL");
+          console.log("  Accrued fees:", feesAfter.toNumber() / LAMPORTS_PER_SOL, "SOL");
+          console.log("  Amount withdrawn:", feesBefore.sub(feesAfter).toNumber() / LAMPORTS_PER_SOL, "SOL");
           
-          assert.isBelow(bcBalanceAfter, bcBalance, "Bonding curve balance should decrease");
+          assert.isTrue(feesAfter.lt(feesBefore), "Accrued fees should decrease");
         } catch (e) {
           console.log("Withdrawal failed (might be restricted):", e.message);
         }
       } else {
-        console.log("No SOL to withdraw from bonding curve");
+        console.log("No fees to withdraw");
       }
     });
     
@@ -374,21 +278,15 @@ describe("pumpdotfun", () => {
       console.log("Attempting unauthorized withdrawal...");
       
       try {
-        await program.methods
-          .withdraw(withdrawAmount)
-          .accounts({
-            authority: buyer1.publicKey, // Using buyer1 instead of creator
-            bondingCurve: bondingCurve,
-            globalState: globalState,
-            systemProgram: SystemProgram.programId,
-          })
+        await client
+          .withdraw(program, buyer1.publicKey, buyer1.publicKey, withdrawAmount) // Using buyer1 instead of the admin
           .signers([buyer1])
           .rpc();
         
         assert.fail("Unauthorized withdrawal should have failed");
       } catch (e) {
         console.log("Unauthorized withdrawal correctly rejected:", e.message);
-        assert.include(e.message, "A seeds constraint was violated", "Should fail with proper error");
+        assert.include(e.message, "InvalidAdmin", "Should fail with proper error");
       }
     });
   });
@@ -398,22 +296,10 @@ describe("pumpdotfun", () => {
       console.log("\n=== Testing Minimum Buy Amount ===");
       
       const tinyAmount = new anchor.BN(1000); // Very small amount
-      const buyerAta = await getAssociatedTokenAddress(mint, buyer2.publicKey);
       
       try {
-        await program.methods
-          .buy(tinyAmount, new anchor.BN(0))
-          .accounts({
-            user: buyer2.publicKey,
-            bondingCurve: bondingCurve,
-            associatedBondingCurve: associatedBondingCurve,
-            associatedUser: buyerAta,
-            mint: mint,
-            globalState: globalState,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-            systemProgram: SystemProgram.programId,
-          })
+        await client
+          .buy(program, buyer2.publicKey, mint, tinyAmount, new BN(0))
           .signers([buyer2])
           .rpc();
         
@@ -426,17 +312,18 @@ describe("pumpdotfun", () => {
     it("Should calculate prices correctly along the bonding curve", async () => {
       console.log("\n=== Testing Bonding Curve Pricing ===");
       
-      const bondingCurveAccount = await program.account.bondingCurve.fetch(bondingCurve);
+      const poolAccount = await program.account.liquidityPool.fetch(pool);
+      const curve = poolAccount.curve.virtualConstantProduct;
       
       console.log("Current bonding curve state:");
-      console.log("  Virtual SOL reserves:", bondingCurveAccount.virtualSolReserves.toString());
-      console.log("  Virtual token reserves:", bondingCurveAccount.virtualTokenReserves.toString());
-      console.log("  Real SOL reserves:", bondingCurveAccount.realSolReserves.toString());
-      console.log("  Real token reserves:", bondingCurveAccount.realTokenReserves.toString());
+      console.log("  Virtual SOL reserves:", curve.virtualSol.toString());
+      console.log("  Virtual token reserves:", curve.virtualToken.toString());
+      console.log("  Real SOL reserves:", poolAccount.reserveSol.toString());
+      console.log("  Real token reserves:", poolAccount.reserveToken.toString());
       
       // Calculate implied price
-      const virtualSol = bondingCurveAccount.virtualSolReserves.toNumber();
-      const virtualTokens = bondingCurveAccount.virtualTokenReserves.toNumber();
+      const virtualSol = curve.virtualSol.add(poolAccount.reserveSol).toNumber();
+      const virtualTokens = curve.virtualToken.add(poolAccount.reserveToken).toNumber();
       const k = virtualSol * virtualTokens;
       
       console.log("\nBonding curve metrics:");
@@ -460,13 +347,15 @@ describe("pumpdotfun", () => {
       console.log("  Symbol:", tokenSymbol);
       console.log("  Mint:", mint.toString());
       
-      // Bonding curve state
-      const bondingCurveAccount = await program.account.bondingCurve.fetch(bondingCurve);
-      console.log("\nBonding Curve Final State:");
-      console.log("  SOL reserves:", bondingCurveAccount.realSolReserves.toNumber() / LAMPORTS_PER_SOL, "SOL");
-      console.log("  Token reserves:", bondingCurveAccount.realTokenReserves.toString(), "tokens");
-      console.log("  Virtual SOL:", bondingCurveAccount.virtualSolReserves.toNumber() / LAMPORTS_PER_SOL, "SOL");
-      console.log("  Virtual tokens:", bondingCurveAccount.virtualTokenReserves.toString());
+      // Pool state
+      const poolAccount = await program.account.liquidityPool.fetch(pool);
+      const curve = poolAccount.curve.virtualConstantProduct;
+      console.log("\nPool Final State:");
+      console.log("  SOL reserves:", poolAccount.reserveSol.toNumber() / LAMPORTS_PER_SOL, "SOL");
+      console.log("  Token reserves:", poolAccount.reserveToken.toString(), "tokens");
+      console.log("  Virtual SOL:", curve.virtualSol.toNumber() / LAMPORTS_PER_SOL, "SOL");
+      console.log("  Virtual tokens:", curve.virtualToken.toString());
+      console.log("  SOL vault balance:", (await provider.connection.getBalance(poolSolVault)) / LAMPORTS_PER_SOL, "SOL");
       
       // Account balances
       console.log("\nAccount Balances:");
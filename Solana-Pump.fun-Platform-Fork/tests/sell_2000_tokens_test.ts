@@ -1,13 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
+import * as client from "../client/client";
 import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID, 
-  getAssociatedTokenAddress,
-  getAccount
-} from "@solana/spl-token";
+import { getAccount } from "@solana/spl-token";
 
 describe("Sell 100 Tokens Test", () => {
   // Setup
@@ -27,6 +23,7 @@ describe("Sell 100 Tokens Test", () => {
   let pool: PublicKey;
   let poolTokenAccount: PublicKey;
   let poolSolVault: PublicKey;
+  let tokenProgram: PublicKey;
   let sellerTokenAccount: PublicKey;
   
   console.log("=====================================");
@@ -40,35 +37,15 @@ describe("Sell 100 Tokens Test", () => {
   
   before(async () => {
     // Derive DEX config PDA
-    [dexConfigPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("CurveConfiguration")],
-      program.programId
-    );
+    dexConfigPDA = client.configAddress(program.programId);
     
     // Derive pool PDA
-    [pool] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_pool"), mintAddress.toBuffer()],
-      program.programId
-    );
-    
-    // Get pool's associated token account
-    poolTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      pool,
-      true
-    );
-    
-    // Derive pool SOL vault
-    [poolSolVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_sol_vault"), mintAddress.toBuffer()],
-      program.programId
-    );
+    // Pools created before Token-2022 launches hold legacy SPL Token mints
+    tokenProgram = await client.mintTokenProgram(provider.connection, mintAddress);
+    ({ pool, poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAddress, tokenProgram));
     
     // Get seller's token account
-    sellerTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      seller.publicKey
-    );
+    sellerTokenAccount = client.userTokenAccount(mintAddress, seller.publicKey, tokenProgram);
     
     console.log("PDAs and Accounts:");
     console.log("  DEX Config:", dexConfigPDA.toString());
@@ -80,7 +57,7 @@ describe("Sell 100 Tokens Test", () => {
     
     // First, let's buy tokens with the seller wallet if they don't have any
     try {
-      const sellerTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount);
+      const sellerTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount, undefined, tokenProgram);
       const currentTokens = Number(sellerTokenAccountInfo.amount) / 1e9;
       console.log("Seller's current token balance:", currentTokens, "tokens");
       
@@ -100,27 +77,14 @@ describe("Sell 100 Tokens Test", () => {
         
         // Buy tokens
         const buyAmount = new BN(0.3 * LAMPORTS_PER_SOL);
-        const tx = await program.methods
-          .buy(buyAmount)
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            pool,
-            tokenMint: mintAddress,
-            poolTokenAccount,
-            poolSolVault,
-            userTokenAccount: sellerTokenAccount,
-            user: seller.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          })
+        const tx = await client
+          .buy(program, seller.publicKey, mintAddress, buyAmount, new BN(0), { tokenProgram })
           .signers([seller])
           .rpc();
         
         console.log("Bought tokens for testing. Transaction:", tx);
         
-        const updatedTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount);
+        const updatedTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount, undefined, tokenProgram);
         console.log("New token balance:", Number(updatedTokenAccountInfo.amount) / 1e9, "tokens");
       }
     } catch (e) {
@@ -139,27 +103,14 @@ describe("Sell 100 Tokens Test", () => {
       
       // Buy tokens
       const buyAmount = new BN(0.3 * LAMPORTS_PER_SOL);
-      const tx = await program.methods
-        .buy(buyAmount)
-        .accounts({
-          dexConfigurationAccount: dexConfigPDA,
-          pool,
-          tokenMint: mintAddress,
-          poolTokenAccount,
-          poolSolVault,
-          userTokenAccount: sellerTokenAccount,
-          user: seller.publicKey,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-          systemProgram: SystemProgram.programId,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        })
+      const tx = await client
+        .buy(program, seller.publicKey, mintAddress, buyAmount, new BN(0), { tokenProgram })
         .signers([seller])
         .rpc();
       
       console.log("Bought tokens for testing. Transaction:", tx);
       
-      const tokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount);
+      const tokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount, undefined, tokenProgram);
       console.log("Token balance:", Number(tokenAccountInfo.amount) / 1e9, "tokens");
     }
   });
@@ -170,7 +121,7 @@ describe("Sell 100 Tokens Test", () => {
       
       // Check pool balances
       const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+      const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
       
       console.log("Pool State:");
       console.log("  SOL:", poolSolBalance / LAMPORTS_PER_SOL, "SOL");
@@ -186,7 +137,7 @@ describe("Sell 100 Tokens Test", () => {
       }
       
       // Check seller's balance
-      const sellerTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount);
+      const sellerTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount, undefined, tokenProgram);
       const sellerSolBalance = await provider.connection.getBalance(seller.publicKey);
       
       console.log("\nSeller State:");
@@ -217,7 +168,7 @@ describe("Sell 100 Tokens Test", () => {
       try {
         // Get initial balances
         const initialSol = await provider.connection.getBalance(seller.publicKey);
-        const initialTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount);
+        const initialTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount, undefined, tokenProgram);
         const initialTokens = initialTokenAccountInfo.amount;
         
         console.log("\nSeller before sell:");
@@ -226,26 +177,15 @@ describe("Sell 100 Tokens Test", () => {
         
         // Check pool state before
         const poolSolBefore = await provider.connection.getBalance(poolSolVault);
-        const poolTokensBefore = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokensBefore = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         console.log("\nPool before sell:");
         console.log("  SOL:", poolSolBefore / LAMPORTS_PER_SOL);
         console.log("  Tokens:", Number(poolTokensBefore.amount) / 1e9);
         
         // Execute sell
         console.log("\nExecuting sell transaction...");
-        const tx = await program.methods
-          .sell(sellAmount)
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            pool,
-            tokenMint: mintAddress,
-            poolTokenAccount,
-            poolSolVault,
-            userTokenAccount: sellerTokenAccount,
-            user: seller.publicKey,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
+        const tx = await client
+          .sell(program, seller.publicKey, mintAddress, sellAmount, new BN(0), { tokenProgram })
           .signers([seller])
           .rpc();
         
@@ -254,7 +194,7 @@ describe("Sell 100 Tokens Test", () => {
         
         // Get final balances
         const finalSol = await provider.connection.getBalance(seller.publicKey);
-        const finalTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount);
+        const finalTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount, undefined, tokenProgram);
         const finalTokens = finalTokenAccountInfo.amount;
         
         console.log("\nSeller after sell:");
@@ -265,7 +205,7 @@ describe("Sell 100 Tokens Test", () => {
         
         // Check pool state after
         const poolSolAfter = await provider.connection.getBalance(poolSolVault);
-        const poolTokensAfter = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokensAfter = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         
         console.log("\nPool after sell:");
         console.log("  SOL:", poolSolAfter / LAMPORTS_PER_SOL);
@@ -302,7 +242,7 @@ describe("Sell 100 Tokens Test", () => {
       
       // Pool state
       const poolSol = await provider.connection.getBalance(poolSolVault);
-      const poolTokens = await getAccount(provider.connection, poolTokenAccount);
+      const poolTokens = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
       
       console.log("Pool Final State:");
       console.log("  SOL:", poolSol / LAMPORTS_PER_SOL, "SOL");
@@ -319,7 +259,7 @@ describe("Sell 100 Tokens Test", () => {
       
       // Seller state
       const sellerSol = await provider.connection.getBalance(seller.publicKey);
-      const sellerTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount);
+      const sellerTokenAccountInfo = await getAccount(provider.connection, sellerTokenAccount, undefined, tokenProgram);
       
       console.log("\nSeller Final State:");
       console.log("  SOL:", sellerSol / LAMPORTS_PER_SOL, "SOL");
@@ -1,13 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
+import * as client from "../client/client";
 import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { 
-  TOKEN_PROGRAM_ID, 
-  ASSOCIATED_TOKEN_PROGRAM_ID, 
-  getAssociatedTokenAddress,
-  getAccount
-} from "@solana/spl-token";
+import { getAccount } from "@solana/spl-token";
 
 describe("Simple Buy Test - 0.01 SOL", () => {
   // Setup
@@ -27,6 +23,7 @@ describe("Simple Buy Test - 0.01 SOL", () => {
   let pool: PublicKey;
   let poolTokenAccount: PublicKey;
   let poolSolVault: PublicKey;
+  let tokenProgram: PublicKey;
   let buyerTokenAccount: PublicKey;
   
   console.log("=====================================");
@@ -40,35 +37,15 @@ describe("Simple Buy Test - 0.01 SOL", () => {
   
   before(async () => {
     // Derive DEX config PDA
-    [dexConfigPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("CurveConfiguration")],
-      program.programId
-    );
+    dexConfigPDA = client.configAddress(program.programId);
     
     // Derive pool PDA
-    [pool] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_pool"), mintAddress.toBuffer()],
-      program.programId
-    );
-    
-    // Get pool's associated token account
-    poolTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      pool,
-      true
-    );
-    
-    // Derive pool SOL vault
-    [poolSolVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("liquidity_sol_vault"), mintAddress.toBuffer()],
-      program.programId
-    );
+    // Pools created before Token-2022 launches hold legacy SPL Token mints
+    tokenProgram = await client.mintTokenProgram(provider.connection, mintAddress);
+    ({ pool, poolTokenAccount, poolSolVault } = client.poolAddresses(program.programId, mintAddress, tokenProgram));
     
     // Get buyer's token account
-    buyerTokenAccount = await getAssociatedTokenAddress(
-      mintAddress,
-      testBuyer.publicKey
-    );
+    buyerTokenAccount = client.userTokenAccount(mintAddress, testBuyer.publicKey, tokenProgram);
     
     console.log("PDAs and Accounts:");
     console.log("  DEX Config:", dexConfigPDA.toString());
@@ -102,7 +79,7 @@ describe("Simple Buy Test - 0.01 SOL", () => {
       try {
         // Check pool balances
         const poolSolBalance = await provider.connection.getBalance(poolSolVault);
-        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokenAccountInfo = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         
         console.log("Pool Liquidity:");
         console.log("  SOL:", poolSolBalance / LAMPORTS_PER_SOL, "SOL");
@@ -147,28 +124,15 @@ describe("Simple Buy Test - 0.01 SOL", () => {
         
         // Check pool state before
         const poolSolBefore = await provider.connection.getBalance(poolSolVault);
-        const poolTokensBefore = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokensBefore = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         console.log("\nPool before buy:");
         console.log("  SOL:", poolSolBefore / LAMPORTS_PER_SOL);
         console.log("  Tokens:", Number(poolTokensBefore.amount) / 1e9);
         
         // Execute buy
         console.log("\nExecuting buy transaction...");
-        const tx = await program.methods
-          .buy(buyAmount)
-          .accounts({
-            dexConfigurationAccount: dexConfigPDA,
-            pool,
-            tokenMint: mintAddress,
-            poolTokenAccount,
-            poolSolVault,
-            userTokenAccount: buyerTokenAccount,
-            user: testBuyer.publicKey,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-          })
+        const tx = await client
+          .buy(program, testBuyer.publicKey, mintAddress, buyAmount, new BN(0), { tokenProgram })
           .signers([testBuyer])
           .rpc();
         
@@ -177,7 +141,7 @@ describe("Simple Buy Test - 0.01 SOL", () => {
         
         // Get final balances
         const finalSol = await provider.connection.getBalance(testBuyer.publicKey);
-        const buyerTokenAccountInfo = await getAccount(provider.connection, buyerTokenAccount);
+        const buyerTokenAccountInfo = await getAccount(provider.connection, buyerTokenAccount, undefined, tokenProgram);
         
         console.log("\nBuyer after buy:");
         console.log("  SOL balance:", finalSol / LAMPORTS_PER_SOL, "SOL");
@@ -187,7 +151,7 @@ describe("Simple Buy Test - 0.01 SOL", () => {
         
         // Check pool state after
         const poolSolAfter = await provider.connection.getBalance(poolSolVault);
-        const poolTokensAfter = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokensAfter = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         
         console.log("\nPool after buy:");
         console.log("  SOL:", poolSolAfter / LAMPORTS_PER_SOL);
@@ -222,7 +186,7 @@ describe("Simple Buy Test - 0.01 SOL", () => {
       try {
         // Pool state
         const poolSol = await provider.connection.getBalance(poolSolVault);
-        const poolTokens = await getAccount(provider.connection, poolTokenAccount);
+        const poolTokens = await getAccount(provider.connection, poolTokenAccount, undefined, tokenProgram);
         
         console.log("Pool Final State:");
         console.log("  SOL:", poolSol / LAMPORTS_PER_SOL, "SOL");
@@ -243,7 +207,7 @@ describe("Simple Buy Test - 0.01 SOL", () => {
         console.log("  SOL:", buyerSol / LAMPORTS_PER_SOL, "SOL");
         
         try {
-          const buyerTokens = await getAccount(provider.connection, buyerTokenAccount);
+          const buyerTokens = await getAccount(provider.connection, buyerTokenAccount, undefined, tokenProgram);
           console.log("  Tokens:", Number(buyerTokens.amount) / 1e9);
         } catch (e) {
           console.log("  Tokens: 0 (no account created)");
@@ -1,8 +1,8 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { Pumpdotfun } from "../target/types/pumpdotfun";
-import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from "@solana/spl-token";
+import * as client from "../client/client";
+import { Keypair, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { assert } from "chai";
 
 describe("Simple Pumpdotfun Tests", () => {
@@ -12,12 +12,8 @@ describe("Simple Pumpdotfun Tests", () => {
   const program = anchor.workspace.Pumpdotfun as Program<Pumpdotfun>;
   const payer = provider.wallet;
   
-  // Token metadata program
-  const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-  
   let mintKeypair: Keypair;
   let mintAccount: PublicKey;
-  let metadataAccount: PublicKey;
   let tokenAccount: PublicKey;
   
   console.log("Program ID:", program.programId.toString());
@@ -29,21 +25,8 @@ describe("Simple Pumpdotfun Tests", () => {
       mintKeypair = Keypair.generate();
       mintAccount = mintKeypair.publicKey;
       
-      // Derive metadata PDA
-      [metadataAccount] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("metadata"),
-          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-          mintAccount.toBuffer(),
-        ],
-        TOKEN_METADATA_PROGRAM_ID
-      );
-      
       // Get associated token address
-      tokenAccount = await getAssociatedTokenAddress(
-        mintAccount,
-        payer.publicKey
-      );
+      tokenAccount = client.userTokenAccount(mintAccount, payer.publicKey);
       
       const name = "TestToken";
       const symbol = "TEST";
@@ -57,20 +40,8 @@ describe("Simple Pumpdotfun Tests", () => {
       console.log("  Mint:", mintAccount.toString());
       
       try {
-        const tx = await program.methods
-          .createToken(name, symbol, uri, totalSupply)
-          .accounts({
-            payer: payer.publicKey,
-            mintAccount,
-            metadataAccount,
-            tokenAccount,
-            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
-            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            systemProgram: SystemProgram.programId,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-          })
-          .signers([mintKeypair])
+        const tx = await client
+          .createToken(program, payer.publicKey, mintKeypair, { name, symbol, uri }, totalSupply)
           .rpc();
         
         console.log("  Transaction:", tx);
@@ -165,7 +136,6 @@ describe("Simple Pumpdotfun Tests", () => {
       if (mintAccount) {
         console.log("\nCreated Token:");
         console.log("  Mint:", mintAccount.toString());
-        console.log("  Metadata:", metadataAccount.toString());
         console.log("  Token Account:", tokenAccount.toString());
       }
       